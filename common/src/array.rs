@@ -68,7 +68,8 @@ fn flatten_array_in_place(array: &SyntaxNode) {
     array.splice_children(0..count, flat);
 }
 
-use crate::create::{make_comma, make_newline, make_whitespace_n};
+use crate::create::{make_comma, make_comment, make_newline, make_whitespace_n};
+use crate::explain::{is_recording, record_generic};
 use crate::string::{load_text, update_content};
 use crate::util::is_group_marker;
 
@@ -140,6 +141,7 @@ pub fn ensure_trailing_comma(array: &SyntaxNode) {
         return;
     }
 
+    let before = is_recording().then(|| array.to_string());
     if !has_trailing {
         add_trailing_comma_if_missing(array);
     }
@@ -147,12 +149,19 @@ pub fn ensure_trailing_comma(array: &SyntaxNode) {
     if !multiline {
         insert_newlines_around_content(array);
     }
+    if let Some(before) = before {
+        record_generic(array, "array/multiline", &before, &array.to_string());
+    }
 }
 
 pub fn ensure_all_arrays_multiline(root: &SyntaxNode, column_width: usize) {
     let arrays: Vec<_> = root.descendants().filter(|d| d.kind() == ARRAY).collect();
     for array in arrays.iter().rev() {
+        let before = is_recording().then(|| array.to_string());
         ensure_array_multiline(array, column_width);
+        if let Some(before) = before.filter(|before| *before != array.to_string()) {
+            record_generic(array, "array/multiline", &before, &array.to_string());
+        }
     }
 }
 
@@ -202,6 +211,15 @@ fn has_comment_before_bracket_end(array: &SyntaxNode) -> bool {
 pub fn transform<F>(array: &SyntaxNode, transform: &F)
 where
     F: Fn(&str) -> String,
+{
+    transform_with_node(array, &|_, s| transform(s));
+}
+
+/// [`transform`] that also hands over each string's node, to report it in [`crate::diagnostic::Diagnostics`]. What the
+/// closure records with [`crate::explain::record_here`] lands at the string it rewrote.
+pub fn transform_with_node<F>(array: &SyntaxNode, transform: &F)
+where
+    F: Fn(&SyntaxNode, &str) -> String,
 {
     if array.kind() != ARRAY {
         return;
@@ -211,7 +229,7 @@ where
         if (entry.kind() == BASIC_STRING || entry.kind() == LITERAL_STRING)
             && let Some(string_node) = entry.as_node()
         {
-            update_content(string_node, transform);
+            update_content(string_node, |s| transform(string_node, s));
         }
     }
 }
@@ -268,6 +286,7 @@ where
         return;
     }
     flatten_array_in_place(array);
+    let before = is_recording().then(|| value_texts(array));
     let has_trailing_comma = array
         .children_with_tokens()
         .map(|x| x.kind())
@@ -372,6 +391,18 @@ where
             array.splice_children(i..i + 1, vec![]);
         }
     }
+    if let Some(before) = before.filter(|before| *before != value_texts(array)) {
+        record_generic(array, "array/sort", &before.join(", "), &value_texts(array).join(", "));
+    }
+}
+
+/// The values of `array` as written, without their comments.
+fn value_texts(array: &SyntaxNode) -> Vec<String> {
+    flat_array_children(array)
+        .iter()
+        .filter(|child| is_array_value(child.kind()))
+        .map(|child| child.to_string().trim().to_string())
+        .collect()
 }
 
 #[allow(clippy::range_plus_one, clippy::too_many_lines)]
@@ -401,12 +432,180 @@ where
     K: Fn(&str) -> String,
 {
     let mut seen: HashSet<String> = HashSet::new();
-    remove_strings(array, |s| !seen.insert(to_key(s)));
+    remove_strings_as(array, "array/dedupe", |_, s| !seen.insert(to_key(s)));
+}
+
+/// Merges the string values sharing a key into the first of them: `merge` gets the first value's node and the values of
+/// a key in array order, and returns their combination, or `None` to leave them apart. Values without a key are left
+/// alone. The comments of the dropped values move above the kept one; `# Group:` markers stay where they are.
+#[allow(clippy::range_plus_one, clippy::too_many_lines)]
+pub fn merge_strings<K, M>(array: &SyntaxNode, to_key: K, mut merge: M)
+where
+    K: Fn(&str) -> Option<String>,
+    M: FnMut(&SyntaxNode, &[String]) -> Option<String>,
+{
+    if array.kind() != ARRAY {
+        return;
+    }
+    flatten_array_in_place(array);
+    let children: Vec<SyntaxElement> = array.children_with_tokens().collect();
+    let mut by_key: Vec<(String, Vec<(usize, String)>)> = Vec::new();
+    for (at, child) in children.iter().enumerate() {
+        let Some(value) = string_value(child) else {
+            continue;
+        };
+        let Some(key) = to_key(&value) else {
+            continue;
+        };
+        match by_key.iter_mut().find(|(seen, _)| *seen == key) {
+            Some((_, members)) => members.push((at, value)),
+            None => by_key.push((key, vec![(at, value)])),
+        }
+    }
+    let mut kept_as: HashMap<usize, (String, String)> = HashMap::new();
+    let mut dropped_into: HashMap<usize, usize> = HashMap::new();
+    for (_, members) in by_key.into_iter().filter(|(_, members)| members.len() > 1) {
+        let values: Vec<String> = members.iter().map(|(_, value)| value.clone()).collect();
+        let kept = members[0].0;
+        if let Some(merged) = merge(children[kept].as_node().unwrap(), &values) {
+            kept_as.insert(kept, (merged, values.join(", ")));
+            dropped_into.extend(members[1..].iter().map(|(at, _)| (*at, kept)));
+        }
+    }
+    if kept_as.is_empty() {
+        return;
+    }
+
+    // Each value owns the lines above it back to the previous value's line, and the rest of its own line.
+    let had_trailing_comma = has_trailing_comma(array);
+    let count = children.len();
+    let mut head = Vec::new();
+    let mut pending = Vec::new();
+    let mut owned: Vec<(usize, Vec<SyntaxElement>, Vec<SyntaxElement>)> = Vec::new();
+    let mut on_value_line = false;
+    for (at, child) in children.into_iter().enumerate() {
+        match child.kind() {
+            BRACKET_START => head.push(child),
+            BRACKET_END => pending.push(child),
+            kind if is_array_value(kind) => {
+                let mut leading = std::mem::take(&mut pending);
+                leading.push(child);
+                owned.push((at, leading, Vec::new()));
+                on_value_line = true;
+            }
+            kind if on_value_line => {
+                on_value_line = kind != LINE_BREAK;
+                owned.last_mut().unwrap().2.push(child);
+            }
+            _ => pending.push(child),
+        }
+    }
+
+    let mut moved: HashMap<usize, Vec<String>> = HashMap::new();
+    for (at, leading, trailing) in &owned {
+        if let Some(kept) = dropped_into.get(at) {
+            let comments = leading.iter().chain(trailing).flat_map(comment_texts);
+            moved
+                .entry(*kept)
+                .or_default()
+                .extend(comments.filter(|text| !is_group_marker(text)));
+        }
+    }
+    let mut entries = head;
+    for (at, leading, trailing) in owned {
+        if dropped_into.contains_key(&at) {
+            // Keep blank lines and group markers, the comments went to the kept value.
+            for line in leading.split_inclusive(|e| e.kind() == LINE_BREAK) {
+                let comment = line.iter().find(|e| e.kind() == COMMENT);
+                if line.last().is_some_and(|e| e.kind() == LINE_BREAK)
+                    && comment.is_none_or(|c| is_group_marker(&c.to_string()))
+                {
+                    entries.extend(line.iter().cloned());
+                }
+            }
+            continue;
+        }
+        let value_line = leading
+            .iter()
+            .rposition(|e| e.kind() == LINE_BREAK)
+            .map_or(0, |pos| pos + 1);
+        entries.extend(leading[..value_line].iter().cloned());
+        let indent = leading[value_line..]
+            .iter()
+            .find(|e| e.kind() == WHITESPACE)
+            .map_or(0, |e| e.to_string().len());
+        for text in moved.remove(&at).unwrap_or_default() {
+            if indent > 0 {
+                entries.push(make_whitespace_n(indent));
+            }
+            entries.push(make_comment(&text));
+            entries.push(make_newline());
+        }
+        if let Some((merged, values)) = kept_as.get(&at)
+            && let Some(value) = leading.last().and_then(SyntaxElement::as_node)
+        {
+            update_content(value, |_| {
+                record_generic(value, "array/merge", values, merged);
+                merged.clone()
+            });
+        }
+        entries.extend(leading[value_line..].iter().cloned());
+        entries.extend(trailing);
+    }
+    entries.append(&mut pending);
+    array.splice_children(0..count, entries);
+
+    if !had_trailing_comma && has_trailing_comma(array) {
+        let last_comma = array
+            .children_with_tokens()
+            .enumerate()
+            .filter(|(_, x)| x.kind() == COMMA)
+            .last()
+            .map(|(at, _)| at);
+        if let Some(at) = last_comma {
+            array.splice_children(at..at + 1, vec![]);
+        }
+    }
+}
+
+fn string_value(entry: &SyntaxElement) -> Option<String> {
+    let kind = entry.kind();
+    if kind != BASIC_STRING && kind != LITERAL_STRING {
+        return None;
+    }
+    entry
+        .as_node()
+        .and_then(|n| {
+            n.descendants_with_tokens()
+                .filter_map(|e| e.into_token())
+                .find(|token| token.kind() == kind)
+        })
+        .map(|token| load_text(token.text(), kind))
 }
 
-pub fn remove_strings<P>(array: &SyntaxNode, mut predicate: P)
+fn comment_texts(element: &SyntaxElement) -> Vec<String> {
+    match element.as_node() {
+        Some(node) => node
+            .descendants_with_tokens()
+            .filter(|e| e.kind() == COMMENT)
+            .map(|e| e.to_string())
+            .collect(),
+        None if element.kind() == COMMENT => vec![element.to_string()],
+        None => vec![],
+    }
+}
+
+/// Removes the string values `predicate` picks; it gets each value with its node, still in the array.
+pub fn remove_strings<P>(array: &SyntaxNode, predicate: P)
 where
-    P: FnMut(&str) -> bool,
+    P: FnMut(&SyntaxNode, &str) -> bool,
+{
+    remove_strings_as(array, "array/remove", predicate);
+}
+
+fn remove_strings_as<P>(array: &SyntaxNode, rule: &str, mut predicate: P)
+where
+    P: FnMut(&SyntaxNode, &str) -> bool,
 {
     if array.kind() != ARRAY {
         return;
@@ -419,21 +618,10 @@ where
     for entry in array.children_with_tokens() {
         let kind = entry.kind();
         if is_array_value(kind) {
-            let text = if kind == BASIC_STRING || kind == LITERAL_STRING {
-                entry
-                    .as_node()
-                    .and_then(|n| {
-                        n.descendants_with_tokens()
-                            .filter_map(|e| e.into_token())
-                            .find(|token| token.kind() == kind)
-                    })
-                    .map(|token| load_text(token.text(), kind))
-            } else {
-                None
-            };
-            if let Some(value) = text
-                && predicate(&value)
+            if let Some(value) = string_value(&entry)
+                && predicate(entry.as_node().unwrap(), &value)
             {
+                record_generic(entry.as_node().unwrap(), rule, entry.to_string().trim(), "");
                 skip_until_next_value = true;
                 continue;
             }
@@ -0,0 +1,546 @@
+//! Native command line front end for the `pyproject-fmt` and `tox-toml-fmt` binaries.
+//!
+//! It mirrors `run` from the Python `toml-fmt-common` package (same flags, config discovery, output and exit codes) so
+//! the formatters can run where no Python interpreter is installed.
+
+use std::env;
+use std::ffi::OsString;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use clap::error::ErrorKind;
+use clap::{Arg, ArgAction, Args, Command, FromArgMatches};
+use similar::{ChangeTag, TextDiff};
+
+use crate::formatted::{Formatted, FormattedRange};
+use crate::range::Selection;
+
+pub use toml::Value as ConfigValue;
+
+/// clap treats a `Vec` field as a repeatable flag; behind the alias `--expand-tables a,b` stays one comma-separated
+/// value where the last occurrence wins, as in the Python CLI.
+pub type CommaList = Vec<String>;
+
+/// One formatter binary: its identity, the flags it adds on top of [`FormatArgs`] and how it formats.
+pub trait Formatter {
+    /// Flags only this formatter has, e.g. `--keep-full-version`.
+    type Args: clap::Args + Clone;
+    /// Program name, also the name of the shared `<prog>.toml` config file.
+    const PROG: &'static str;
+    const VERSION: &'static str;
+    /// File looked up when an input is a directory.
+    const FILENAME: &'static str;
+    /// Table inside the formatted file holding per-file overrides, e.g. `["tool", "pyproject-fmt"]`.
+    const OVERRIDE_SECTION: &'static [&'static str];
+
+    /// Applies one config key to the formatter's own flags, returning `false` for a key it does not know. Relative
+    /// paths in `value` are relative to `config_dir`, the directory of the file the key was read from.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message when the value has the wrong type.
+    fn apply_config(args: &mut Self::Args, key: &str, value: &ConfigValue, config_dir: &Path) -> Result<bool, String>;
+
+    /// # Errors
+    ///
+    /// Returns a message when the formatter rejects the content.
+    fn format(text: &str, shared: &FormatArgs, args: &Self::Args) -> Result<Formatted, String>;
+
+    /// Formats only what `selection` touches; without range support the whole document is formatted.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message when the formatter rejects the content.
+    fn format_range(
+        text: &str,
+        _selection: &Selection,
+        shared: &FormatArgs,
+        args: &Self::Args,
+    ) -> Result<FormattedRange, String> {
+        let formatted = Self::format(text, shared, args)?;
+        Ok(FormattedRange {
+            edits: formatted.edits(text),
+            diagnostics: formatted.diagnostics,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+#[command(next_help_heading = "formatting behavior")]
+pub struct FormatArgs {
+    /// max column width in the TOML file
+    #[arg(long, default_value_t = 120, value_name = "count")]
+    pub column_width: usize,
+    /// number of spaces to use for indentation
+    #[arg(long, default_value_t = 2, value_name = "count")]
+    pub indent: usize,
+    /// table format: 'short' collapses sub-tables, 'long' expands to [table.subtable]
+    #[arg(long, default_value = "short", value_parser = ["short", "long"])]
+    pub table_format: String,
+    /// extra newlines between sub-tables in the same group (e.g. '' for compact, '\n' for one blank line)
+    #[arg(long, default_value = "", value_parser = spacing_argument)]
+    pub sub_table_spacing: String,
+    /// extra newlines between root table groups (e.g. '\n' for one blank line, '\n\n' for two)
+    #[arg(long, default_value = r"\n", value_parser = spacing_argument)]
+    pub separate_root_table: String,
+    /// comma-separated list of tables to force expand
+    #[arg(long, default_value = "", value_parser = list_argument)]
+    pub expand_tables: CommaList,
+    /// comma-separated list of tables to force collapse
+    #[arg(long, default_value = "", value_parser = list_argument)]
+    pub collapse_tables: CommaList,
+    /// comma-separated list of key patterns to skip string wrapping (supports wildcards like '*.parse')
+    #[arg(long, default_value = "", value_parser = list_argument)]
+    pub skip_wrap_for_keys: CommaList,
+    /// comma-separated list of rules to run, e.g. 'project,uv' (default: all)
+    #[arg(long, default_value = "", value_parser = list_argument)]
+    pub select: CommaList,
+    /// comma-separated list of rules to skip, e.g. 'tox.alias-normalization,project.classifiers.generate'
+    #[arg(long, default_value = "", value_parser = list_argument)]
+    pub ignore: CommaList,
+    /// Record the rule behind each change, set from `--explain` rather than from config.
+    #[arg(skip)]
+    pub explain: bool,
+}
+
+impl FormatArgs {
+    /// Applies one config key shared by every formatter, returning `false` for a key it does not know.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message when the value has the wrong type.
+    pub fn apply_config(&mut self, key: &str, value: &ConfigValue) -> Result<bool, String> {
+        match key {
+            "column_width" => self.column_width = config_usize(key, value)?,
+            "indent" => self.indent = config_usize(key, value)?,
+            "table_format" => self.table_format = config_string(key, value)?,
+            "sub_table_spacing" => self.sub_table_spacing = spacing_argument(&config_string(key, value)?)?,
+            "separate_root_table" => self.separate_root_table = spacing_argument(&config_string(key, value)?)?,
+            "expand_tables" => self.expand_tables = config_list(key, value)?,
+            "collapse_tables" => self.collapse_tables = config_list(key, value)?,
+            "skip_wrap_for_keys" => self.skip_wrap_for_keys = config_list(key, value)?,
+            "select" => self.select = config_list(key, value)?,
+            "ignore" => self.ignore = config_list(key, value)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// Converts literal `\n` sequences to newlines.
+///
+/// # Errors
+///
+/// Never fails; the signature is what clap expects from a value parser.
+pub fn spacing_argument(value: &str) -> Result<String, String> {
+    Ok(value.replace("\\n", "\n"))
+}
+
+/// Splits a comma-separated value into its trimmed, non-empty parts.
+///
+/// # Errors
+///
+/// Never fails; the signature is what clap expects from a value parser.
+pub fn list_argument(value: &str) -> Result<CommaList, String> {
+    Ok(value
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// # Errors
+///
+/// Returns a message when the value is not a boolean.
+pub fn config_bool(key: &str, value: &ConfigValue) -> Result<bool, String> {
+    value.as_bool().ok_or_else(|| invalid(key, "a boolean", value))
+}
+
+/// # Errors
+///
+/// Returns a message when the value is not a non-negative integer.
+pub fn config_usize(key: &str, value: &ConfigValue) -> Result<usize, String> {
+    value
+        .as_integer()
+        .and_then(|number| usize::try_from(number).ok())
+        .ok_or_else(|| invalid(key, "a non-negative integer", value))
+}
+
+/// # Errors
+///
+/// Returns a message when the value is not a string.
+pub fn config_string(key: &str, value: &ConfigValue) -> Result<String, String> {
+    value
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| invalid(key, "a string", value))
+}
+
+/// Accepts an array of strings or a comma-separated string, like the flag.
+///
+/// # Errors
+///
+/// Returns a message when the value is neither.
+pub fn config_list(key: &str, value: &ConfigValue) -> Result<CommaList, String> {
+    match value {
+        ConfigValue::String(raw) => list_argument(raw),
+        ConfigValue::Array(items) => items
+            .iter()
+            .map(|item| item.as_str().map(String::from))
+            .collect::<Option<_>>()
+            .ok_or_else(|| invalid(key, "an array of strings", value)),
+        _ => Err(invalid(key, "an array of strings", value)),
+    }
+}
+
+fn invalid(key: &str, expected: &str, value: &ConfigValue) -> String {
+    format!("{key} must be {expected}, got {value}")
+}
+
+/// Parses `args` (program name first), formats every input and returns the exit code.
+///
+/// The code is 0 when every input was already formatted and 1 when any changed, was rejected or has diagnostics. Usage
+/// errors exit the process with code 2.
+#[must_use]
+pub fn run<F: Formatter>(args: impl IntoIterator<Item = impl Into<OsString> + Clone>) -> u8 {
+    let mut command = command::<F>();
+    let matches = command.clone().get_matches_from(args);
+    let cli = Cli::<F> {
+        stdout: matches.get_flag("stdout"),
+        check: matches.get_flag("check"),
+        no_print_diff: matches.get_flag("no_print_diff"),
+        config: matches.get_one::<PathBuf>("config").cloned(),
+        shared: FormatArgs {
+            explain: matches.get_flag("explain"),
+            ..FormatArgs::from_arg_matches(&matches).unwrap_or_else(|err| err.exit())
+        },
+        args: F::Args::from_arg_matches(&matches).unwrap_or_else(|err| err.exit()),
+    };
+    if let Some(config) = cli.config.as_ref().filter(|config| !config.is_file()) {
+        let message = format!("config file does not exist: {}", config.display());
+        command.error(ErrorKind::ValueValidation, message).exit();
+    }
+    let inputs: Vec<Option<PathBuf>> = matches
+        .get_many::<PathBuf>("inputs")
+        .into_iter()
+        .flatten()
+        .map(|input| {
+            toml_path(F::FILENAME, input).unwrap_or_else(|message| {
+                command
+                    .error(ErrorKind::ValueValidation, format!("argument inputs: {message}"))
+                    .exit()
+            })
+        })
+        .collect();
+    if !cli.stdout && !cli.check {
+        for path in inputs.iter().flatten() {
+            if OpenOptions::new().append(true).open(path).is_err() {
+                let message = format!("argument inputs: cannot write path {}", path.display());
+                command.error(ErrorKind::ValueValidation, message).exit();
+            }
+        }
+    }
+    let mut failed = false;
+    for input in &inputs {
+        failed |= cli.handle_one(input.as_deref());
+    }
+    u8::from(failed) // exit with non success on change or rejection
+}
+
+/// Parses `args` (program name first) the way [`run`] does and returns the matches without formatting anything, so a
+/// caller can turn away a bad command line before acting on it.
+///
+/// # Errors
+///
+/// Returns the usage error, or the help or version request, [`run`] would exit the process with.
+pub fn try_parse<F: Formatter>(
+    args: impl IntoIterator<Item = impl Into<OsString> + Clone>,
+) -> Result<clap::ArgMatches, clap::Error> {
+    command::<F>().try_get_matches_from(args)
+}
+
+/// The options the binary would format `text`, read from `path`, with when given no flags: the defaults, then the
+/// shared config file found from the file's directory, then the file's own override section. Editor integrations use
+/// this to format exactly like the command line.
+///
+/// # Errors
+///
+/// Returns a message when the config file cannot be read or a value has the wrong type.
+pub fn options_for<F: Formatter>(path: Option<&Path>, text: &str) -> Result<(FormatArgs, F::Args), String> {
+    let matches = command::<F>()
+        .try_get_matches_from([F::PROG, "-"])
+        .map_err(|err| err.to_string())?;
+    let cli = Cli::<F> {
+        stdout: false,
+        check: false,
+        no_print_diff: false,
+        config: None,
+        shared: FormatArgs::from_arg_matches(&matches).map_err(|err| err.to_string())?,
+        args: F::Args::from_arg_matches(&matches).map_err(|err| err.to_string())?,
+    };
+    cli.options_for(path, text)
+}
+
+fn command<F: Formatter>() -> Command {
+    let command = Command::new(F::PROG)
+        .bin_name(F::PROG)
+        .version(format!("({})", F::VERSION))
+        .disable_version_flag(true)
+        .arg(
+            Arg::new("version")
+                .short('V')
+                .long("version")
+                .action(ArgAction::Version)
+                .help(format!("print package version of {}", F::PROG)),
+        )
+        .next_help_heading("run mode")
+        .arg(
+            Arg::new("stdout")
+                .short('s')
+                .long("stdout")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("check")
+                .help("print the formatted TOML to the stdout, implied if reading from stdin"),
+        )
+        .arg(
+            Arg::new("check")
+                .long("check")
+                .action(ArgAction::SetTrue)
+                .help("check and fail if any input would be formatted, printing any diffs"),
+        )
+        .arg(
+            Arg::new("no_print_diff")
+                .short('n')
+                .long("no-print-diff")
+                .action(ArgAction::SetTrue)
+                .help("Flag indicating to print diff for the check mode"),
+        )
+        .arg(
+            Arg::new("explain")
+                .long("explain")
+                .action(ArgAction::SetTrue)
+                .help("print the rule behind each change, e.g. 'project.dependencies[3]: pep508/normalize-spacing'"),
+        )
+        .next_help_heading(None::<&str>)
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .value_name("path")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help(format!("path to a shared {}.toml config file", F::PROG)),
+        )
+        .arg(
+            Arg::new("inputs")
+                .required(true)
+                .num_args(1..)
+                .value_parser(clap::value_parser!(PathBuf))
+                .help(format!("{} file(s) to format, use '-' to read from stdin", F::FILENAME)),
+        );
+    F::Args::augment_args(FormatArgs::augment_args(command))
+}
+
+struct Cli<F: Formatter> {
+    stdout: bool,
+    check: bool,
+    no_print_diff: bool,
+    config: Option<PathBuf>,
+    shared: FormatArgs,
+    args: F::Args,
+}
+
+impl<F: Formatter> Cli<F> {
+    /// Returns `true` when the input changed, was rejected or has diagnostics.
+    fn handle_one(&self, path: Option<&Path>) -> bool {
+        let name = display_name(path);
+        let before = match read_input(path) {
+            Ok(text) => text,
+            Err(err) => {
+                eprintln!("{name}: {err}");
+                return true;
+            }
+        };
+        let (shared, args) = match self.options_for(path, &before) {
+            Ok(options) => options,
+            Err(err) => {
+                eprintln!("{name}: {err}");
+                return true;
+            }
+        };
+        let result = match F::format(&before, &shared, &args) {
+            Ok(result) => result,
+            Err(err) => {
+                // the formatter rejected the content, e.g. an invalid project.version
+                eprintln!("{name}: {err}");
+                return true;
+            }
+        };
+        for diagnostic in &result.diagnostics {
+            eprintln!("{name}:{diagnostic}");
+        }
+        for explanation in &result.explanations {
+            eprintln!("{name}: {explanation}");
+        }
+        let formatted = result.content;
+        let changed = before != formatted || !result.diagnostics.is_empty();
+        let Some(path) = path.filter(|_| !self.stdout) else {
+            // when reading from stdin or writing to stdout, print new format
+            print!("{formatted}");
+            return changed;
+        };
+        if before != formatted
+            && !self.check
+            && let Err(err) = fs::write(path, &formatted)
+        {
+            eprintln!("{name}: {err}");
+            return true;
+        }
+        if self.no_print_diff {
+            return changed;
+        }
+        if before == formatted {
+            println!("no change for {name}");
+        } else {
+            println!("{}", color_diff(unified_diff(&before, &formatted, &name)).join("\n"));
+        }
+        changed
+    }
+
+    /// CLI flags, then the shared config file, then the file's own override section; later sources win.
+    fn options_for(&self, path: Option<&Path>, text: &str) -> Result<(FormatArgs, F::Args), String> {
+        let mut shared = self.shared.clone();
+        let mut args = self.args.clone();
+        let start = path
+            .and_then(Path::parent)
+            .map_or_else(|| env::current_dir().unwrap_or_default(), Path::to_path_buf);
+        let config_file = self.config.clone().or_else(|| find_config_file(F::PROG, &start));
+        if let Some(config_file) = config_file {
+            let content =
+                fs::read_to_string(&config_file).map_err(|err| format!("{}: {err}", config_file.display()))?;
+            let table = content
+                .parse::<toml::Table>()
+                .map_err(|err| format!("{}: {err}", config_file.display()))?;
+            let config_dir = config_file.parent().unwrap_or_else(|| Path::new(""));
+            Self::apply(&mut shared, &mut args, &table, config_dir)?;
+        }
+        if let Some(section) = override_section(text, F::OVERRIDE_SECTION) {
+            Self::apply(&mut shared, &mut args, &section, &start)?;
+        }
+        Ok((shared, args))
+    }
+
+    fn apply(
+        shared: &mut FormatArgs,
+        args: &mut F::Args,
+        table: &toml::Table,
+        config_dir: &Path,
+    ) -> Result<(), String> {
+        for (key, value) in table {
+            if !shared.apply_config(key, value)? {
+                F::apply_config(args, key, value, config_dir)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn toml_path(filename: &str, argument: &Path) -> Result<Option<PathBuf>, &'static str> {
+    if argument.as_os_str() == "-" {
+        return Ok(None); // stdin, no further validation needed
+    }
+    let mut path = std::path::absolute(argument).map_err(|_| "path does not exist")?;
+    if path.is_dir() {
+        path.push(filename);
+    }
+    if !path.exists() {
+        return Err("path does not exist");
+    }
+    if !path.is_file() {
+        return Err("path is not a file");
+    }
+    if fs::File::open(&path).is_err() {
+        return Err("cannot read path");
+    }
+    Ok(Some(path))
+}
+
+fn read_input(path: Option<&Path>) -> io::Result<String> {
+    if let Some(path) = path {
+        return fs::read_to_string(path);
+    }
+    let mut text = String::new();
+    io::stdin().read_to_string(&mut text)?;
+    Ok(text)
+}
+
+fn display_name(path: Option<&Path>) -> String {
+    let Some(path) = path else {
+        return String::from("<stdin>");
+    };
+    env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| path.to_path_buf())
+        .display()
+        .to_string()
+}
+
+fn find_config_file(prog: &str, start: &Path) -> Option<PathBuf> {
+    let start = fs::canonicalize(start).unwrap_or_else(|_| start.to_path_buf());
+    start
+        .ancestors()
+        .map(|dir| dir.join(format!("{prog}.toml")))
+        .find(|candidate| candidate.is_file())
+}
+
+fn override_section(text: &str, section: &[&str]) -> Option<toml::Table> {
+    let mut table = text.parse::<toml::Table>().ok()?;
+    for part in section {
+        match table.remove(*part)? {
+            ConfigValue::Table(inner) => table = inner,
+            _ => return None,
+        }
+    }
+    Some(table)
+}
+
+fn unified_diff(before: &str, after: &str, name: &str) -> Vec<String> {
+    let diff = TextDiff::from_lines(before, after);
+    let mut lines = vec![format!("--- {name}"), format!("+++ {name}")];
+    for hunk in diff.unified_diff().iter_hunks() {
+        lines.push(hunk.header().to_string());
+        for change in hunk.iter_changes() {
+            let sign = match change.tag() {
+                ChangeTag::Delete => '-',
+                ChangeTag::Insert => '+',
+                ChangeTag::Equal => ' ',
+            };
+            lines.push(format!("{sign}{}", change.value().trim_end_matches(['\r', '\n'])));
+        }
+    }
+    lines
+}
+
+const GREEN: &str = "\u{1b}[32m";
+const RED: &str = "\u{1b}[31m";
+const RESET: &str = "\u{1b}[0m";
+
+fn color_diff(lines: Vec<String>) -> Vec<String> {
+    if env::var_os("NO_COLOR").is_some() {
+        // https://no-color.org
+        return lines;
+    }
+    lines
+        .into_iter()
+        .map(|line| {
+            if line.starts_with('+') {
+                format!("{GREEN}{line}{RESET}")
+            } else if line.starts_with('-') {
+                format!("{RED}{line}{RESET}")
+            } else {
+                line
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,272 @@
+//! Problems a pass finds but cannot fix, such as a dependency that is not valid PEP 508.
+//!
+//! The pass leaves the value as written and reports it here, so one bad entry no longer aborts formatting the rest of
+//! the file.
+//!
+//! Passes see the tree after earlier passes reshaped it (sub-tables collapsed, values normalized), so a node's offset
+//! no longer points into the user's file. [`Diagnostics::report_node`] records the node's offset together with the
+//! document as it was at that moment, and [`Diagnostics::locate`] maps it back through the edits made until then.
+//! Checks that work on parsed values have no node; [`Diagnostics::report`] finds their key path in the source instead.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use tombi_syntax::SyntaxKind::{ARRAY_OF_TABLE, KEY_VALUE, KEYS, TABLE};
+use tombi_syntax::SyntaxNode;
+
+use crate::formatted::TextEdit;
+
+/// `line` and `column` are 1-based and count characters; `offset` is the byte offset into the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "pyo3", pyo3::pyclass(frozen, get_all))]
+pub struct Diagnostic {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+    pub key_path: String,
+    pub value: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}: {}", self.line, self.column, self.key_path, self.message)
+    }
+}
+
+enum Position {
+    /// Byte offset of the node in `document`, the text of the tree when the node was reported.
+    Node {
+        document: Rc<str>,
+        offset: usize,
+    },
+    KeyPath,
+}
+
+struct Report {
+    position: Position,
+    key_path: String,
+    value: String,
+    message: String,
+}
+
+/// Passes report from inside the `Fn` closures that [`crate::array::transform`] and [`crate::array::sort`] take, so
+/// the collector works through a shared reference.
+#[derive(Default)]
+pub struct Diagnostics {
+    reports: RefCell<Vec<Report>>,
+}
+
+impl Diagnostics {
+    /// Reports `value` under `key_path`, located at the key-value or table `key_path` names in the source.
+    pub fn report(&self, key_path: &str, value: &str, message: &str) {
+        self.push(Position::KeyPath, key_path, value, message);
+    }
+
+    /// Reports `value` at `node`, which must still be part of the document being formatted.
+    pub fn report_node(&self, node: &SyntaxNode, key_path: &str, value: &str, message: &str) {
+        let root = node.ancestors().last().unwrap_or_else(|| node.clone());
+        let mut offset = 0;
+        for element in root.descendants_with_tokens() {
+            if element.as_node() == Some(node) {
+                break;
+            }
+            if let Some(token) = element.as_token() {
+                offset += token.text().len();
+            }
+        }
+        // A string is reported at its value, past the opening quotes.
+        let node_text = node.to_string();
+        offset += if node_text.starts_with("\"\"\"") || node_text.starts_with("'''") {
+            3
+        } else {
+            usize::from(node_text.starts_with(['"', '\'']))
+        };
+        let text = root.to_string();
+        // Reports made without a change in between share one copy of the document.
+        let last = self
+            .reports
+            .borrow()
+            .iter()
+            .rev()
+            .find_map(|report| match &report.position {
+                Position::Node { document, .. } => Some(document.clone()),
+                Position::KeyPath => None,
+            });
+        let document = match last {
+            Some(last) if *last == *text => last,
+            _ => Rc::from(text),
+        };
+        self.push(Position::Node { document, offset }, key_path, value, message);
+    }
+
+    fn push(&self, position: Position, key_path: &str, value: &str, message: &str) {
+        self.reports.borrow_mut().push(Report {
+            position,
+            key_path: key_path.to_string(),
+            value: value.to_string(),
+            message: message.to_string(),
+        });
+    }
+
+    /// Resolves the reports against `source`, the text that was formatted.
+    #[must_use]
+    pub fn locate(self, source: &str) -> Vec<Diagnostic> {
+        let entries = KeyPathIndex::new(source);
+        let mut seen: HashMap<(String, String), usize> = HashMap::new();
+        let mut edits: Option<(Rc<str>, Vec<TextEdit>)> = None;
+        self.reports
+            .into_inner()
+            .into_iter()
+            .map(|report| {
+                let offset = match report.position {
+                    Position::Node { document, offset } => {
+                        if !edits.as_ref().is_some_and(|(cached, _)| Rc::ptr_eq(cached, &document)) {
+                            edits = Some((document.clone(), TextEdit::between(source, &document)));
+                        }
+                        original_offset(&edits.as_ref().unwrap().1, offset)
+                    }
+                    Position::KeyPath => {
+                        let nth = seen.entry((report.key_path.clone(), report.value.clone())).or_default();
+                        let offset = entries.find(source, &report.key_path, &report.value, *nth);
+                        *nth += 1;
+                        offset
+                    }
+                }
+                .min(source.len());
+                let (line, column) = line_column(source, offset);
+                Diagnostic {
+                    offset,
+                    line,
+                    column,
+                    key_path: report.key_path,
+                    value: report.value,
+                    message: report.message,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Maps an offset in the edited text back to the source: text the edits kept moves with them, text an edit wrote
+/// maps to where that edit starts.
+fn original_offset(edits: &[TextEdit], offset: usize) -> usize {
+    let (mut old_at, mut new_at) = (0, 0);
+    for edit in edits {
+        let kept = edit.range.start - old_at;
+        if offset < new_at + kept {
+            return old_at + offset - new_at;
+        }
+        new_at += kept;
+        if offset < new_at + edit.new_text.len() {
+            return edit.range.start;
+        }
+        new_at += edit.new_text.len();
+        old_at = edit.range.end;
+    }
+    old_at + offset.saturating_sub(new_at)
+}
+
+/// The byte range of every key-value and table header in the source, by dotted key path without quotes.
+pub(crate) struct KeyPathIndex {
+    entries: Vec<(String, std::ops::Range<usize>)>,
+}
+
+impl KeyPathIndex {
+    pub(crate) fn new(source: &str) -> Self {
+        let root = tombi_parser::parse(source).syntax_node();
+        let mut entries = vec![];
+        let mut offset = 0;
+        for element in root.descendants_with_tokens() {
+            if let Some(token) = element.as_token() {
+                offset += token.text().len();
+                continue;
+            }
+            let node = element.as_node().unwrap();
+            if matches!(node.kind(), KEY_VALUE | TABLE | ARRAY_OF_TABLE) {
+                entries.push((key_path_of(node), offset..offset + node.to_string().len()));
+            }
+        }
+        Self { entries }
+    }
+
+    /// The key path of the innermost key-value or table around `offset`, empty outside of them.
+    pub(crate) fn innermost(&self, offset: usize) -> String {
+        self.entries
+            .iter()
+            .rfind(|(_, range)| range.contains(&offset))
+            .map(|(key_path, _)| key_path.clone())
+            .unwrap_or_default()
+    }
+
+    /// The n-th quoted occurrence of `value` within the entry `key_path` names, else its first occurrence there, else
+    /// the start of the entry. An entry missing from the source falls back to its closest parent.
+    fn find(&self, source: &str, key_path: &str, value: &str, nth: usize) -> usize {
+        let wanted = plain_key_path(key_path);
+        let mut path = wanted.as_str();
+        loop {
+            let ranges: Vec<&std::ops::Range<usize>> = self
+                .entries
+                .iter()
+                .filter(|(entry, _)| entry == path)
+                .map(|(_, range)| range)
+                .collect();
+            if let Some(&first) = ranges.first() {
+                let mut quoted = ranges.iter().flat_map(|range| {
+                    let start = range.start;
+                    quoted_occurrences(&source[(*range).clone()], value).map(move |at| start + at)
+                });
+                return quoted
+                    .nth(nth)
+                    .or_else(|| {
+                        source[first.clone()]
+                            .find(value)
+                            .filter(|_| !value.is_empty())
+                            .map(|at| first.start + at)
+                    })
+                    .unwrap_or(first.start);
+            }
+            match path.rfind('.') {
+                Some(dot) => path = &path[..dot],
+                None => return 0,
+            }
+        }
+    }
+}
+
+/// `[tool.tox]` then `env."3.13".base_python` gives `tool.tox.env.3.13.base_python`.
+pub(crate) fn key_path_of(node: &SyntaxNode) -> String {
+    let mut segments: Vec<String> = node
+        .ancestors()
+        .filter(|ancestor| matches!(ancestor.kind(), KEY_VALUE | TABLE | ARRAY_OF_TABLE))
+        .filter_map(|ancestor| ancestor.children().find(|child| child.kind() == KEYS))
+        .map(|keys| keys.text().to_string())
+        .collect();
+    segments.reverse();
+    plain_key_path(&segments.join("."))
+}
+
+fn plain_key_path(key_path: &str) -> String {
+    key_path
+        .split('.')
+        .map(|segment| segment.trim().trim_matches(['"', '\'']))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn quoted_occurrences<'a>(source: &'a str, value: &'a str) -> impl Iterator<Item = usize> + 'a {
+    source
+        .match_indices(value)
+        .map(|(at, _)| at)
+        .filter(move |&at| !value.is_empty() && source[..at].ends_with(['"', '\'']))
+}
+
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |at| at + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
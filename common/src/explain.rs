@@ -0,0 +1,200 @@
+//! Explains a formatting run: which transformation changed which key, for `--explain`.
+//!
+//! Each transformation records its own changes under a stable rule ID, e.g. `pep508/normalize-spacing` from the
+//! requirement normalizer or `table/reorder-keys` from [`crate::table::reorder_table_keys`]. The primitives are called
+//! from every pass, so rather than threading a recorder through all of them, [`collect`] switches recording
+//! on for the current thread while it formats, and [`record`] does nothing outside of it.
+//!
+//! The generic primitives (sorting an array, rewriting a string) record under their own rule unless the caller names
+//! the change with [`with_rule`], as `project/normalize-description` does for the description rewrite.
+
+use std::cell::{Cell, RefCell};
+
+use tombi_syntax::SyntaxKind::{ARRAY, ARRAY_OF_TABLE, COMMA, KEY_VALUE, TABLE, VALUE_WITH_COMMA_GROUP};
+use tombi_syntax::SyntaxNode;
+
+use crate::diagnostic::{KeyPathIndex, key_path_of};
+use crate::formatted::TextEdit;
+
+/// One change a transformation made. `key_path` is dotted from the document root, with `[n]` for the n-th element of
+/// an array; `before` and `after` are the changed snippets as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "pyo3", pyo3::pyclass(frozen, get_all))]
+pub struct Explanation {
+    pub rule: String,
+    pub key_path: String,
+    pub before: String,
+    pub after: String,
+}
+
+impl std::fmt::Display for Explanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.key_path, self.rule)
+    }
+}
+
+thread_local! {
+    static RECORDED: RefCell<Option<Vec<Explanation>>> = const { RefCell::new(None) };
+    /// The value a string rewrite is running on, see [`at`].
+    static AT: RefCell<Option<SyntaxNode>> = const { RefCell::new(None) };
+    /// The rule [`with_rule`] names the generic changes after.
+    static RULE: RefCell<Option<String>> = const { RefCell::new(None) };
+    static COUNT: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Puts back the recording of an enclosing [`collect`], also when formatting panics.
+struct Restore(Option<Vec<Explanation>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        RECORDED.set(self.0.take());
+    }
+}
+
+/// Runs `format`, returning its result with the changes recorded meanwhile, none unless `enabled`.
+#[must_use]
+pub fn collect<T>(enabled: bool, format: impl FnOnce() -> T) -> (T, Vec<Explanation>) {
+    let _restore = Restore(RECORDED.replace(enabled.then(Vec::new)));
+    let result = format();
+    (result, RECORDED.take().unwrap_or_default())
+}
+
+/// Whether changes are being recorded, to skip computing snippets nobody reads.
+#[must_use]
+pub fn is_recording() -> bool {
+    RECORDED.with_borrow(Option::is_some)
+}
+
+/// Records `rule` changing `before` into `after` at `node`.
+pub fn record(node: &SyntaxNode, rule: &str, before: &str, after: &str) {
+    if is_recording() {
+        push(rule, node_key_path(node), before, after);
+    }
+}
+
+/// Records `rule` changing `before` into `after` under `key_path`, for changes spanning several tables.
+pub fn record_path(key_path: &str, rule: &str, before: &str, after: &str) {
+    if is_recording() {
+        push(rule, key_path.to_string(), before, after);
+    }
+}
+
+/// Records a change of the string value being rewritten, for transformations that only see the string, such as the
+/// closure [`crate::array::transform`] takes.
+pub fn record_here(rule: &str, before: &str, after: &str) {
+    if !is_recording() {
+        return;
+    }
+    let key_path = AT.with_borrow(|at| at.as_ref().map(node_key_path)).unwrap_or_default();
+    push(rule, key_path, before, after);
+}
+
+/// Records a change of a generic primitive under the rule of the enclosing [`with_rule`], else under `rule`.
+pub(crate) fn record_generic(node: &SyntaxNode, rule: &str, before: &str, after: &str) {
+    if is_recording() {
+        let named = RULE.with_borrow(Clone::clone);
+        push(named.as_deref().unwrap_or(rule), node_key_path(node), before, after);
+    }
+}
+
+/// Runs `rewrite` on the value at `node`, so what it records with [`record_here`] lands at `node`. Returns whether
+/// anything was recorded.
+pub(crate) fn at<T>(node: &SyntaxNode, rewrite: impl FnOnce() -> T) -> (T, bool) {
+    if !is_recording() {
+        return (rewrite(), false);
+    }
+    let previous = AT.replace(Some(node.clone()));
+    let before = COUNT.get();
+    let result = rewrite();
+    AT.set(previous);
+    (result, COUNT.get() != before)
+}
+
+/// Runs `change`, recording what the generic primitives inside it change under `rule`, e.g. a string rewrite as
+/// `project/normalize-license`.
+pub fn with_rule<T>(rule: &str, change: impl FnOnce() -> T) -> T {
+    if !is_recording() {
+        return change();
+    }
+    let previous = RULE.replace(Some(rule.to_string()));
+    let result = change();
+    RULE.set(previous);
+    result
+}
+
+/// Records the edits turning the whole document `before` into `after` under `rule`, one explanation per run of edits
+/// within the same key-value or table, for the layout steps that work on the text.
+pub fn record_edits(rule: &str, before: &str, after: &str) {
+    if !is_recording() || before == after {
+        return;
+    }
+    let index = KeyPathIndex::new(before);
+    let mut runs: Vec<(String, std::ops::Range<usize>, String)> = vec![];
+    for edit in TextEdit::between(before, after) {
+        let key_path = index.innermost(edit.range.start);
+        match runs.last_mut() {
+            Some((path, range, text)) if *path == key_path => {
+                text.push_str(&before[range.end..edit.range.start]);
+                text.push_str(&edit.new_text);
+                range.end = edit.range.end;
+            }
+            _ => runs.push((key_path, edit.range, edit.new_text)),
+        }
+    }
+    for (key_path, range, text) in runs {
+        push(rule, key_path, &before[range], &text);
+    }
+}
+
+fn push(rule: &str, key_path: String, before: &str, after: &str) {
+    RECORDED.with_borrow_mut(|recorded| {
+        if let Some(recorded) = recorded {
+            recorded.push(Explanation {
+                rule: rule.to_string(),
+                key_path,
+                before: before.to_string(),
+                after: after.to_string(),
+            });
+        }
+    });
+    COUNT.set(COUNT.get() + 1);
+}
+
+/// The key path of `node`, with the index of the array element it is or sits in.
+fn node_key_path(node: &SyntaxNode) -> String {
+    let mut key_path = key_path_of(node);
+    for ancestor in node.ancestors() {
+        if matches!(ancestor.kind(), KEY_VALUE | TABLE | ARRAY_OF_TABLE) {
+            break;
+        }
+        let Some(mut parent) = ancestor.parent() else {
+            break;
+        };
+        if parent.kind() == VALUE_WITH_COMMA_GROUP
+            && let Some(array) = parent.parent()
+        {
+            parent = array;
+        }
+        if parent.kind() == ARRAY
+            && let Some(at) = array_elements(&parent).iter().position(|element| *element == ancestor)
+        {
+            key_path = format!("{key_path}[{at}]");
+            break;
+        }
+    }
+    key_path
+}
+
+fn array_elements(array: &SyntaxNode) -> Vec<SyntaxNode> {
+    array
+        .children()
+        .flat_map(|child| {
+            if child.kind() == VALUE_WITH_COMMA_GROUP {
+                child.children().collect()
+            } else {
+                vec![child]
+            }
+        })
+        .filter(|child| child.kind() != COMMA)
+        .collect()
+}
@@ -0,0 +1,121 @@
+use similar::{Algorithm, DiffTag, capture_diff_slices};
+
+use crate::diagnostic::Diagnostic;
+use crate::explain::Explanation;
+
+/// What a formatter hands back: the new document, plus the problems it found and left as written. `explanations` is
+/// filled only when the formatter was asked to explain its changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "pyo3", pyo3::pyclass(frozen, get_all))]
+pub struct Formatted {
+    pub content: String,
+    pub diagnostics: Vec<Diagnostic>,
+    pub explanations: Vec<Explanation>,
+}
+
+impl Formatted {
+    /// The smallest edits that turn `source`, the text that was formatted, into [`Formatted::content`], so an editor
+    /// can apply them without moving the cursor or touching hunks the formatter left alone.
+    #[must_use]
+    pub fn edits(&self, source: &str) -> Vec<TextEdit> {
+        TextEdit::between(source, &self.content)
+    }
+}
+
+/// What range formatting hands back: the edits to apply to the whole document, and the problems found in the
+/// formatted part, positioned in the whole document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormattedRange {
+    pub edits: Vec<TextEdit>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Replace the bytes `range` of the original source with `new_text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: std::ops::Range<usize>,
+    pub new_text: String,
+}
+
+impl TextEdit {
+    /// Diffs at the token level, so a re-quoted string or a respaced `=` is its own edit rather than a whole line.
+    /// Runs of changed tokens with nothing unchanged between them merge into one edit.
+    #[must_use]
+    pub fn between(before: &str, after: &str) -> Vec<Self> {
+        let old = tokens(before);
+        let new = tokens(after);
+        let old_text: Vec<&str> = old.iter().map(|range| &before[range.clone()]).collect();
+        let new_text: Vec<&str> = new.iter().map(|range| &after[range.clone()]).collect();
+        let offset =
+            |tokens: &[std::ops::Range<usize>], at: usize, len: usize| tokens.get(at).map_or(len, |range| range.start);
+        let mut edits: Vec<Self> = vec![];
+        for op in capture_diff_slices(Algorithm::Myers, &old_text, &new_text) {
+            let (tag, old_range, new_range) = op.as_tag_tuple();
+            if tag == DiffTag::Equal {
+                continue;
+            }
+            let start = offset(&old, old_range.start, before.len());
+            let end = offset(&old, old_range.end, before.len());
+            let inserted = &after[offset(&new, new_range.start, after.len())..offset(&new, new_range.end, after.len())];
+            match edits.last_mut() {
+                Some(last) if last.range.end == start => {
+                    last.range.end = end;
+                    last.new_text.push_str(inserted);
+                }
+                _ => edits.push(Self {
+                    range: start..end,
+                    new_text: inserted.to_string(),
+                }),
+            }
+        }
+        edits
+    }
+
+    /// Applies edits that do not overlap, in any order.
+    #[must_use]
+    pub fn apply(source: &str, edits: &[Self]) -> String {
+        let mut sorted: Vec<&Self> = edits.iter().collect();
+        sorted.sort_by_key(|edit| edit.range.start);
+        let mut out = String::with_capacity(source.len());
+        let mut at = 0;
+        for edit in sorted {
+            out.push_str(&source[at..edit.range.start]);
+            out.push_str(&edit.new_text);
+            at = edit.range.end;
+        }
+        out.push_str(&source[at..]);
+        out
+    }
+}
+
+#[derive(PartialEq)]
+enum TokenClass {
+    Word,
+    Space,
+    Other,
+}
+
+fn token_class(c: char) -> TokenClass {
+    if c.is_alphanumeric() || c == '_' {
+        TokenClass::Word
+    } else if c == ' ' || c == '\t' {
+        TokenClass::Space
+    } else {
+        TokenClass::Other
+    }
+}
+
+/// Words and runs of blanks are one token each; every other character, newlines and quotes included, is its own.
+fn tokens(text: &str) -> Vec<std::ops::Range<usize>> {
+    let mut tokens: Vec<std::ops::Range<usize>> = vec![];
+    let mut previous = TokenClass::Other;
+    for (at, c) in text.char_indices() {
+        let class = token_class(c);
+        match tokens.last_mut() {
+            Some(last) if class != TokenClass::Other && class == previous => last.end = at + c.len_utf8(),
+            _ => tokens.push(at..at + c.len_utf8()),
+        }
+        previous = class;
+    }
+    tokens
+}
@@ -1,8 +1,16 @@
 pub mod array;
+#[cfg(feature = "cli")]
+pub mod cli;
 pub mod create;
+pub mod diagnostic;
 pub mod disabled;
+pub mod explain;
 pub mod format_options;
+pub mod formatted;
 pub mod pep508;
+pub mod range;
+pub mod rule_file;
+pub mod rules;
 pub mod string;
 pub mod table;
 pub mod util;
@@ -16,6 +16,102 @@ impl MarkerExpr {
         }
         Ok(expr)
     }
+
+    /// The canonical spelling of the marker, so equivalent markers print and compare the same: legacy variable names
+    /// such as `os.name` become their PEP 508 names, values are single quoted, nested `and`/`or` are flattened,
+    /// parentheses are kept only around an `or` inside an `and`, and the operands of each are sorted and deduplicated.
+    pub fn normalize(self) -> Self {
+        match self {
+            MarkerExpr::Paren(expr) => expr.normalize(),
+            MarkerExpr::Comparison { left, op, right } => MarkerExpr::Comparison {
+                left: canonical_variable(&left).to_string(),
+                op,
+                right: canonical_value(right),
+            },
+            MarkerExpr::And(exprs) => combine(exprs, true),
+            MarkerExpr::Or(exprs) => combine(exprs, false),
+        }
+    }
+
+    /// Settles the comparisons whose outcome `decide` knows, called with the variable, the operator and the unquoted
+    /// value, and folds the constants into the `and`/`or` around them.
+    pub fn resolve(self, decide: &dyn Fn(&str, &str, &str) -> Option<bool>) -> Resolved {
+        match self {
+            MarkerExpr::Comparison { left, op, right } => match decide(&left, &op, right.trim_matches(['\'', '"'])) {
+                Some(value) => Resolved::Always(value),
+                None => Resolved::Marker(MarkerExpr::Comparison { left, op, right }),
+            },
+            MarkerExpr::Paren(expr) => match expr.resolve(decide) {
+                Resolved::Marker(inner) => Resolved::Marker(MarkerExpr::Paren(Box::new(inner))),
+                always => always,
+            },
+            MarkerExpr::And(exprs) => fold(exprs, true, decide),
+            MarkerExpr::Or(exprs) => fold(exprs, false, decide),
+        }
+    }
+}
+
+/// What is left of a marker after [`MarkerExpr::resolve`]: a constant, or the comparisons still to evaluate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolved {
+    Always(bool),
+    Marker(MarkerExpr),
+}
+
+/// `true` is the identity of `and` and `false` of `or`, the other constant decides the whole expression.
+fn fold(exprs: Vec<MarkerExpr>, and: bool, decide: &dyn Fn(&str, &str, &str) -> Option<bool>) -> Resolved {
+    let mut left = Vec::new();
+    for expr in exprs {
+        match expr.resolve(decide) {
+            Resolved::Always(value) if value == and => {}
+            Resolved::Always(value) => return Resolved::Always(value),
+            Resolved::Marker(marker) => left.push(marker),
+        }
+    }
+    match left.len() {
+        0 => Resolved::Always(and),
+        1 => Resolved::Marker(left.remove(0)),
+        _ if and => Resolved::Marker(MarkerExpr::And(left)),
+        _ => Resolved::Marker(MarkerExpr::Or(left)),
+    }
+}
+
+/// PEP 508 keeps accepting the dotted and pre-standard variable names; map them to the standard ones.
+fn canonical_variable(name: &str) -> &str {
+    match name {
+        "os.name" => "os_name",
+        "sys.platform" => "sys_platform",
+        "platform.version" => "platform_version",
+        "platform.machine" => "platform_machine",
+        "platform.python_implementation" | "python_implementation" => "platform_python_implementation",
+        _ => name,
+    }
+}
+
+fn canonical_value(value: String) -> String {
+    match value.as_bytes().first() {
+        Some(b'"') if !value.contains('\'') => format!("'{}'", &value[1..value.len() - 1]),
+        _ => value,
+    }
+}
+
+fn combine(exprs: Vec<MarkerExpr>, and: bool) -> MarkerExpr {
+    let mut operands = Vec::new();
+    for expr in exprs {
+        match expr.normalize() {
+            MarkerExpr::And(inner) if and => operands.extend(inner),
+            MarkerExpr::Or(inner) if !and => operands.extend(inner),
+            or @ MarkerExpr::Or(_) => operands.push(MarkerExpr::Paren(Box::new(or))),
+            other => operands.push(other),
+        }
+    }
+    operands.sort_by_cached_key(ToString::to_string);
+    operands.dedup();
+    match (operands.len(), and) {
+        (1, _) => operands.remove(0),
+        (_, true) => MarkerExpr::And(operands),
+        (_, false) => MarkerExpr::Or(operands),
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -66,7 +162,7 @@ fn tokenize(input: &str) -> Result<Vec<Token<'_>>, String> {
             }
             c if c.is_ascii_alphabetic() || c == '_' => {
                 let mut j = i + 1;
-                while j < chars.len() && (chars[j].is_ascii_alphanumeric() || chars[j] == '_') {
+                while j < chars.len() && (chars[j].is_ascii_alphanumeric() || chars[j] == '_' || chars[j] == '.') {
                     j += 1;
                 }
                 let word = &input[i..j];
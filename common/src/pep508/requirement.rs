@@ -1,6 +1,8 @@
-use crate::pep508::marker::MarkerExpr;
-use crate::pep508::version_op::{Operator, VersionOp};
+use crate::explain::{is_recording, record_here};
+use crate::pep508::marker::{MarkerExpr, Resolved};
+use crate::pep508::version_op::{Operator, Version, VersionOp};
 use regex::Regex;
+use std::cmp::Ordering;
 use std::fmt::Write;
 use std::str::FromStr;
 
@@ -98,7 +100,15 @@ impl Requirement {
 
     pub fn normalize(mut self, keep_full_version: bool) -> Self {
         self.name = self.canonical_name();
-        if !keep_full_version && let Some(VersionOrUrl::Versions(ref mut specs)) = self.version_or_url {
+        self.marker = self.marker.map(MarkerExpr::normalize);
+        if !keep_full_version {
+            self.strip_trailing_zeros();
+        }
+        self
+    }
+
+    fn strip_trailing_zeros(&mut self) {
+        if let Some(VersionOrUrl::Versions(ref mut specs)) = self.version_or_url {
             for version_op in specs.iter_mut() {
                 // Trailing `.0` is only redundant without pre/post/dev/local segments.
                 if version_op.op != Operator::Compatible
@@ -114,7 +124,68 @@ impl Requirement {
                 }
             }
         }
-        self
+    }
+
+    /// Drops the version specifiers the others already imply: the looser of two lower or two upper bounds, exclusions
+    /// outside the bounds (including those of `~=` and a wildcard `==`), repeated specifiers, and everything an exact `==`
+    /// pin satisfies. `>=1.2,>=1.4` becomes `>=1.4`, `>=2,<3,!=1.5` becomes `>=2,<3`, `~=1.4,!=2.1` becomes `~=1.4`.
+    ///
+    /// # Errors
+    ///
+    /// When no version satisfies every specifier, such as `>=3,<2` or `~=1.4,<1.4`; nothing is rewritten then.
+    pub fn simplify_specifiers(mut self) -> Result<Self, String> {
+        if let Some(VersionOrUrl::Versions(ref mut specs)) = self.version_or_url {
+            *specs = simplify(specs)?;
+        }
+        Ok(self)
+    }
+
+    /// Settles the marker comparisons `decide` knows the outcome of, see [`MarkerExpr::resolve`]: `None` when the marker
+    /// never holds, otherwise the requirement without the clauses that always do.
+    pub fn resolve_marker(mut self, decide: &dyn Fn(&str, &str, &str) -> Option<bool>) -> Option<Self> {
+        if let Some(marker) = self.marker.take() {
+            match marker.resolve(decide) {
+                Resolved::Always(false) => return None,
+                Resolved::Always(true) => {}
+                Resolved::Marker(marker) => self.marker = Some(marker.normalize()),
+            }
+        }
+        Some(self)
+    }
+
+    /// The requirement without its version specifiers or URL: requirements with the same identity differ only in
+    /// which versions they accept and can be merged, see [`Requirement::merge`].
+    pub fn identity(&self) -> String {
+        let mut bare = self.clone();
+        bare.name = self.canonical_name();
+        bare.version_or_url = None;
+        bare.to_string()
+    }
+
+    /// Combines two requirements of the same identity: the specifier sets are joined, a URL must be the same in both.
+    ///
+    /// # Errors
+    ///
+    /// When the two pin different URLs, or one a URL and the other versions, or no version satisfies the joined
+    /// specifiers, such as `pkg==1` and `pkg==2`.
+    pub fn merge(mut self, other: &Self) -> Result<Self, String> {
+        match (&mut self.version_or_url, &other.version_or_url) {
+            (_, None) => {}
+            (mine @ None, theirs) => mine.clone_from(theirs),
+            (Some(VersionOrUrl::Versions(mine)), Some(VersionOrUrl::Versions(theirs))) => {
+                for spec in theirs {
+                    if !mine.contains(spec) {
+                        mine.push(spec.clone());
+                    }
+                }
+                if let Err(err) = simplify(mine) {
+                    return Err(format!("conflicting version pins for {}, {err}", self.canonical_name()));
+                }
+            }
+            (Some(VersionOrUrl::Url(mine)), Some(VersionOrUrl::Url(theirs))) if mine == theirs => {}
+            _ => return Err(format!("conflicting URL pins for {}", self.canonical_name())),
+        }
+        Ok(self)
     }
 
     pub fn is_name_only(&self) -> bool {
@@ -122,11 +193,188 @@ impl Requirement {
     }
 
     pub fn canonical_name(&self) -> String {
-        Regex::new(r"[-_.]+")
-            .unwrap()
-            .replace_all(&self.name.to_lowercase(), "-")
-            .into_owned()
+        collapse_separators(&self.name.to_lowercase())
+    }
+}
+
+/// Replaces runs of `-`, `_` and `.` in a name by a single `-`, keeping its case.
+fn collapse_separators(name: &str) -> String {
+    Regex::new(r"[-_.]+").unwrap().replace_all(name, "-").into_owned()
+}
+
+/// Parses `raw` and normalizes it like [`Requirement::normalize`], recording each step that changed it for `--explain`:
+/// `pep508/normalize-spacing` (or `pep508/reformat` for more than whitespace, like sorting the extras),
+/// `pep508/lowercase-name`, `pep508/normalize-name`, `pep508/normalize-marker` and `pep508/strip-trailing-zeros`.
+/// Without `lowercase_name` the name keeps its case and only has its separators normalized.
+///
+/// # Errors
+///
+/// When `raw` is not a valid PEP 508 requirement.
+pub fn normalize_requirement(raw: &str, keep_full_version: bool, lowercase_name: bool) -> Result<String, String> {
+    let mut req = Requirement::new(raw)?;
+    if !is_recording() {
+        let name = collapse_separators(&req.name);
+        let mut req = req.normalize(keep_full_version);
+        if !lowercase_name {
+            req.name = name;
+        }
+        return Ok(req.to_string());
     }
+    let mut text = raw.to_string();
+    let mut step = |rule: &str, next: String| {
+        if next != text {
+            record_here(rule, &text, &next);
+            text = next;
+        }
+    };
+    let squashed = |s: &str| s.split_whitespace().collect::<String>();
+    let rendered = req.to_string();
+    if squashed(raw) == squashed(&rendered) {
+        step("pep508/normalize-spacing", rendered);
+    } else {
+        step("pep508/reformat", rendered);
+    }
+    if lowercase_name {
+        req.name = req.name.to_lowercase();
+        step("pep508/lowercase-name", req.to_string());
+    }
+    req.name = collapse_separators(&req.name);
+    step("pep508/normalize-name", req.to_string());
+    req.marker = req.marker.take().map(MarkerExpr::normalize);
+    step("pep508/normalize-marker", req.to_string());
+    if !keep_full_version {
+        req.strip_trailing_zeros();
+        step("pep508/strip-trailing-zeros", req.to_string());
+    }
+    Ok(req.to_string())
+}
+
+fn simplify(specs: &[VersionOp]) -> Result<Vec<VersionOp>, String> {
+    let unsatisfiable = || {
+        let joined: Vec<String> = specs.iter().map(ToString::to_string).collect();
+        format!("no version satisfies {}", joined.join(","))
+    };
+    let pin = specs
+        .iter()
+        .position(|spec| spec.op == Operator::Equal && !spec.version.has_wildcard);
+    if let Some(pin) = pin {
+        let version = &specs[pin].version;
+        if specs
+            .iter()
+            .any(|spec| spec.op != Operator::ArbitraryEqual && !spec.contains(version))
+        {
+            return Err(unsatisfiable());
+        }
+        return Ok(specs
+            .iter()
+            .enumerate()
+            .filter(|(at, spec)| *at == pin || spec.op == Operator::ArbitraryEqual)
+            .map(|(_, spec)| spec.clone())
+            .collect());
+    }
+
+    let (low, high) = range(specs);
+    if let (Some((low, low_open)), Some((high, high_open))) = (low, high) {
+        match low.cmp(&high) {
+            Ordering::Greater => return Err(unsatisfiable()),
+            Ordering::Equal if low_open || high_open => return Err(unsatisfiable()),
+            _ => {}
+        }
+    }
+    let lower = tightest(
+        specs,
+        &[Operator::GreaterEqual, Operator::GreaterThan],
+        Ordering::Greater,
+    );
+    let upper = tightest(specs, &[Operator::LessEqual, Operator::LessThan], Ordering::Less);
+    let within_bounds = |version: &Version| {
+        specs
+            .iter()
+            .filter(|spec| is_range(spec))
+            .all(|spec| spec.contains(version))
+    };
+    Ok(specs
+        .iter()
+        .enumerate()
+        .filter(|&(at, spec)| match spec.op {
+            Operator::GreaterEqual | Operator::GreaterThan => lower.is_some_and(|(low, _)| low == at),
+            Operator::LessEqual | Operator::LessThan => upper.is_some_and(|(high, _)| high == at),
+            Operator::NotEqual if !spec.version.has_wildcard => {
+                within_bounds(&spec.version) && !specs[..at].contains(spec)
+            }
+            _ => !specs[..at].contains(spec),
+        })
+        .map(|(_, spec)| spec.clone())
+        .collect())
+}
+
+/// Whether `spec` bounds the versions to a range: the comparisons, `~=` and a wildcard `==`.
+fn is_range(spec: &VersionOp) -> bool {
+    match spec.op {
+        Operator::Equal => spec.version.has_wildcard,
+        Operator::ArbitraryEqual | Operator::NotEqual => false,
+        _ => true,
+    }
+}
+
+/// The highest lower and lowest upper bound of the range specifiers, each with whether it is open (excluded). `~=1.4`
+/// spans `>=1.4` to `<2.dev0`, `==1.4.*` spans `>=1.4.dev0` to `<1.5.dev0`, and `<2` stops before `2.dev0` as it
+/// excludes the pre-releases of 2.
+fn range(specs: &[VersionOp]) -> (Option<(Version, bool)>, Option<(Version, bool)>) {
+    let dev0 = |version: Version| Version {
+        dev: Some(("dev".to_string(), Some(0))),
+        ..version.base()
+    };
+    let next = |version: &Version, keep: usize| {
+        let mut prefix = version.base();
+        prefix.release.truncate(keep);
+        *prefix.release.last_mut()? += 1;
+        Some((dev0(prefix), true))
+    };
+    let mut lows = Vec::new();
+    let mut highs = Vec::new();
+    for spec in specs {
+        let version = &spec.version;
+        match spec.op {
+            Operator::GreaterEqual => lows.push((version.public(), false)),
+            Operator::GreaterThan => lows.push((version.public(), true)),
+            Operator::LessEqual => highs.push((version.public(), false)),
+            Operator::LessThan if version.is_prerelease() || version.public() != version.base() => {
+                highs.push((version.public(), true));
+            }
+            Operator::LessThan => highs.push((dev0(version.base()), true)),
+            Operator::Compatible => {
+                lows.push((version.public(), false));
+                highs.extend(next(version, version.release.len().saturating_sub(1)));
+            }
+            Operator::Equal if version.has_wildcard => {
+                lows.push((dev0(version.base()), false));
+                highs.extend(next(version, version.release.len()));
+            }
+            _ => {}
+        }
+    }
+    let low = lows
+        .into_iter()
+        .max_by(|(a, a_open), (b, b_open)| a.cmp(b).then(a_open.cmp(b_open)));
+    let high = highs
+        .into_iter()
+        .min_by(|(a, a_open), (b, b_open)| a.cmp(b).then(b_open.cmp(a_open)));
+    (low, high)
+}
+
+/// The strictest bound among `ops`, the inclusive operator then the exclusive one: the highest lower or lowest upper
+/// bound as `toward` says, the exclusive one on a tie.
+fn tightest<'a>(specs: &'a [VersionOp], ops: &[Operator], toward: Ordering) -> Option<(usize, &'a VersionOp)> {
+    specs
+        .iter()
+        .enumerate()
+        .filter(|(_, spec)| ops.contains(&spec.op))
+        .reduce(|best, next| match next.1.version.cmp(&best.1.version) {
+            order if order == toward => next,
+            Ordering::Equal if next.1.op == ops[1] && best.1.op == ops[0] => next,
+            _ => best,
+        })
 }
 
 impl FromStr for Requirement {
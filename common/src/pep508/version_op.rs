@@ -1,5 +1,5 @@
 pub(crate) use crate::pep508::version_op::operator::Operator;
-pub(crate) use crate::pep508::version_op::version::Version;
+pub use crate::pep508::version_op::version::Version;
 pub use crate::pep508::version_op::version::is_valid_version;
 
 mod operator;
@@ -20,6 +20,43 @@ impl VersionOp {
         let version = Version::new(version_str);
         Ok(Self { op, version })
     }
+
+    /// Whether `version` satisfies this specifier, per the PEP 440 rules for each operator: `<2` excludes `2.0rc1`,
+    /// `>2` excludes `2.post1` and `2+local`, and `==`/`!=` compare the public version unless the specifier has a local
+    /// segment. `===` compares the text.
+    pub fn contains(&self, version: &Version) -> bool {
+        let spec = &self.version;
+        match self.op {
+            Operator::ArbitraryEqual => spec.to_string() == version.to_string(),
+            Operator::Equal | Operator::NotEqual => {
+                let equal = if spec.has_wildcard {
+                    version.matches_prefix(spec)
+                } else if spec.local.is_some() {
+                    version == spec
+                } else {
+                    &version.public() == spec
+                };
+                equal == (self.op == Operator::Equal)
+            }
+            Operator::Compatible => {
+                let mut prefix = spec.base();
+                prefix.release.pop();
+                version.public() >= *spec && version.matches_prefix(&prefix)
+            }
+            Operator::GreaterEqual => version.public() >= *spec,
+            Operator::LessEqual => version.public() <= *spec,
+            Operator::LessThan => {
+                version.public() < *spec
+                    && (spec.is_prerelease() || !version.is_prerelease() || version.base() != spec.base())
+            }
+            Operator::GreaterThan => {
+                let same_base = version.base() == spec.base();
+                version.public() > *spec
+                    && (spec.is_postrelease() || !version.is_postrelease() || !same_base)
+                    && (version.local.is_none() || !same_base)
+            }
+        }
+    }
 }
 
 impl std::fmt::Display for VersionOp {
@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::sync::LazyLock;
 
 use regex::Regex;
@@ -23,7 +24,14 @@ static PEP440: LazyLock<Regex> = LazyLock::new(|| {
     .unwrap()
 });
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// A post release at the end of a version: `.post1`, the `r` and `rev` spellings, or the implicit `-1`.
+static POST: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:-(?P<implicit>[0-9]+)|[-_.]?(?P<label>post|rev|r)[-_.]?(?P<number>[0-9]+)?)$").unwrap()
+});
+
+/// Equality and ordering follow PEP 440: `1.0` equals `1.0.0`, `1.0.dev0` < `1.0a1` < `1.0` < `1.0.post1`, and
+/// a local version sorts after its public one.
+#[derive(Debug, Clone)]
 pub struct Version {
     pub epoch: Option<u64>,
     pub release: Vec<u64>,
@@ -75,12 +83,13 @@ impl Version {
             main = Self::trim_separators_end(before);
         }
 
-        if let Some(idx) = main.to_ascii_lowercase().rfind("post") {
-            let (before, after) = main.split_at(idx);
-            let after = &after[4..];
-            let post_num = Self::extract_number(after);
-            post = Some((Some("post".to_string()), post_num));
-            main = Self::trim_separators_end(before);
+        if let Some(caps) = POST.captures(main) {
+            let number = caps.name("implicit").or_else(|| caps.name("number"));
+            post = Some((
+                caps.name("label").map(|label| label.as_str().to_ascii_lowercase()),
+                number.and_then(|number| number.as_str().parse().ok()),
+            ));
+            main = &main[..caps.get(0).map_or(main.len(), |found| found.start())];
         }
 
         // Labels are checked longest first so a shorter one is not matched inside a longer one ("rc" before "c").
@@ -109,6 +118,81 @@ impl Version {
         }
     }
 
+    /// The version without its local segment, the part indexes compare.
+    pub fn public(&self) -> Self {
+        Self {
+            local: None,
+            has_wildcard: false,
+            ..self.clone()
+        }
+    }
+
+    /// Epoch and release only, `1.2` for `1.2rc1.post3+local`.
+    pub fn base(&self) -> Self {
+        Self {
+            epoch: self.epoch,
+            release: self.release.clone(),
+            pre: None,
+            post: None,
+            dev: None,
+            local: None,
+            has_wildcard: false,
+        }
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some() || self.dev.is_some()
+    }
+
+    pub fn is_postrelease(&self) -> bool {
+        self.post.is_some()
+    }
+
+    /// Whether the release starts with `prefix`'s, padding with zeros: `3.1.dev0` and `3.1.0.4` both match `3.1.*`.
+    pub fn matches_prefix(&self, prefix: &Self) -> bool {
+        self.epoch.unwrap_or(0) == prefix.epoch.unwrap_or(0)
+            && prefix
+                .release
+                .iter()
+                .enumerate()
+                .all(|(at, part)| self.release.get(at).copied().unwrap_or(0) == *part)
+    }
+
+    /// A development release without a pre-release label sorts before every pre-release of its release, anything
+    /// without a pre-release label after them.
+    fn pre_key(&self) -> (u8, u64) {
+        match &self.pre {
+            None if self.post.is_none() && self.dev.is_some() => (0, 0),
+            None => (4, 0),
+            Some((label, number)) => {
+                let rank = match label.as_str() {
+                    "alpha" | "a" => 1,
+                    "beta" | "b" => 2,
+                    _ => 3,
+                };
+                (rank, number.unwrap_or(0))
+            }
+        }
+    }
+
+    fn dev_key(&self) -> (u8, u64) {
+        self.dev.as_ref().map_or((1, 0), |(_, number)| (0, number.unwrap_or(0)))
+    }
+
+    fn local_key(&self) -> Option<Vec<LocalSegment>> {
+        self.local.as_ref().map(|local| {
+            local
+                .split(['.', '-', '_'])
+                .map(|segment| {
+                    segment.parse().map_or_else(
+                        |_| LocalSegment::Text(segment.to_ascii_lowercase()),
+                        LocalSegment::Number,
+                    )
+                })
+                .collect()
+        })
+    }
+
     fn extract_number(s: &str) -> Option<u64> {
         let trimmed = s.trim_start_matches(['-', '_', '.']);
         let (num, _) = trimmed.split_at(trimmed.find(|c: char| !c.is_ascii_digit()).unwrap_or(trimmed.len()));
@@ -120,6 +204,50 @@ impl Version {
     }
 }
 
+/// A numeric local segment compares greater than any alphanumeric one.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum LocalSegment {
+    Text(String),
+    Number(u64),
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let release_len = self.release.len().max(other.release.len());
+        let release = |version: &Self| -> Vec<u64> {
+            (0..release_len)
+                .map(|at| version.release.get(at).copied().unwrap_or(0))
+                .collect()
+        };
+        self.epoch
+            .unwrap_or(0)
+            .cmp(&other.epoch.unwrap_or(0))
+            .then_with(|| release(self).cmp(&release(other)))
+            .then_with(|| self.pre_key().cmp(&other.pre_key()))
+            .then_with(|| {
+                let post = |version: &Self| version.post.as_ref().map(|(_, number)| number.unwrap_or(0));
+                post(self).cmp(&post(other))
+            })
+            .then_with(|| self.dev_key().cmp(&other.dev_key()))
+            .then_with(|| self.local_key().cmp(&other.local_key()))
+            .then_with(|| self.has_wildcard.cmp(&other.has_wildcard))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
 impl std::fmt::Display for Version {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let Some(epoch) = self.epoch {
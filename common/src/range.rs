@@ -0,0 +1,151 @@
+//! Formats only the part of a document a selection touches, for "format selection" in editors.
+//!
+//! The passes and the table reordering work on whole documents, so the selection is widened to whole top-level tables
+//! and handed to the formatter as a document of its own. Tables of one group (`[project]` and `[project.urls]`) are
+//! always taken together, since collapsing a sub-table into its parent needs both, so the run can grow until it covers
+//! every group it touches. Everything outside the run is left byte for byte.
+
+use std::collections::HashSet;
+use std::ops::{Range, RangeInclusive};
+
+use tombi_syntax::SyntaxKind::{ARRAY_OF_TABLE, KEYS, TABLE};
+
+use crate::diagnostic::Diagnostic;
+use crate::formatted::{Formatted, FormattedRange, TextEdit};
+use crate::table::get_key;
+
+/// The part of a document to format: a byte range, or 1-based inclusive line numbers as in
+/// [`crate::diagnostic::Diagnostic`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Selection {
+    Bytes(Range<usize>),
+    Lines(RangeInclusive<usize>),
+}
+
+impl Selection {
+    /// 0-based inclusive lines, clamped to the document.
+    fn lines(&self, line_starts: &[usize]) -> RangeInclusive<usize> {
+        let last = line_starts.len() - 1;
+        let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset).saturating_sub(1);
+        match self {
+            Self::Bytes(range) => {
+                line_of(range.start).min(last)..=line_of(range.end.max(range.start + 1) - 1).min(last)
+            }
+            Self::Lines(lines) => lines.start().saturating_sub(1).min(last)..=lines.end().saturating_sub(1).min(last),
+        }
+    }
+}
+
+/// A top-level table from the first line of the comments above its header, or the root key-values before any header.
+struct Section {
+    start_line: usize,
+    group: String,
+}
+
+fn line_starts(source: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(
+            source
+                .match_indices('\n')
+                .map(|(at, _)| at + 1)
+                .filter(|&at| at < source.len()),
+        )
+        .collect()
+}
+
+fn sections(source: &str, multi_level_prefixes: &[&str]) -> Vec<Section> {
+    let lines: Vec<&str> = source.lines().collect();
+    let root = tombi_parser::parse(source).syntax_node();
+    let mut sections = vec![Section {
+        start_line: 0,
+        group: String::new(),
+    }];
+    for table in root.children().filter(|n| matches!(n.kind(), TABLE | ARRAY_OF_TABLE)) {
+        let Some(keys) = table.children().find(|c| c.kind() == KEYS) else {
+            continue;
+        };
+        let header_line = keys.range().start.line as usize;
+        let start_line = (0..header_line)
+            .rev()
+            .take_while(|&line| lines[line].trim_start().starts_with('#'))
+            .last()
+            .unwrap_or(header_line);
+        let name = keys.text().to_string().trim().to_string();
+        sections.push(Section {
+            start_line,
+            group: get_key(&name, multi_level_prefixes),
+        });
+    }
+    // Only blank lines before the first table: there is no root section to format.
+    let root_end = sections.get(1).map_or(lines.len(), |first| first.start_line);
+    if lines[..root_end].iter().all(|line| line.trim().is_empty()) {
+        sections.remove(0);
+    }
+    sections
+}
+
+/// Grows the run `first..=last` of section indexes until every group in it is complete.
+fn widen(sections: &[Section], mut first: usize, mut last: usize) -> (usize, usize) {
+    loop {
+        let groups: HashSet<&str> = sections[first..=last].iter().map(|s| s.group.as_str()).collect();
+        let members: Vec<usize> = (0..sections.len())
+            .filter(|&at| groups.contains(sections[at].group.as_str()))
+            .collect();
+        let (lo, hi) = (members[0], members[members.len() - 1]);
+        if lo >= first && hi <= last {
+            return (first, last);
+        }
+        (first, last) = (first.min(lo), last.max(hi));
+    }
+}
+
+/// Formats the tables `selection` overlaps with `format`, a whole-document formatter, and returns the minimal edits
+/// (see [`TextEdit::between`]) that splice the result back, none when the run is already formatted, along with the
+/// diagnostics of the run moved to their place in `source`. Pass the same `multi_level_prefixes` as to
+/// [`crate::table::Tables::reorder`] so the run covers whole groups.
+///
+/// # Errors
+///
+/// Propagates whatever `format` rejected the run with.
+pub fn format_range<E>(
+    source: &str,
+    selection: &Selection,
+    multi_level_prefixes: &[&str],
+    format: impl FnOnce(&str) -> Result<Formatted, E>,
+) -> Result<FormattedRange, E> {
+    let line_starts = line_starts(source);
+    let sections = sections(source, multi_level_prefixes);
+    let selected = selection.lines(&line_starts);
+    let end_line = |at: usize| sections.get(at + 1).map_or(usize::MAX, |next| next.start_line);
+    let overlapping: Vec<usize> = (0..sections.len())
+        .filter(|&at| sections[at].start_line <= *selected.end() && *selected.start() < end_line(at))
+        .collect();
+    let (Some(&first), Some(&last)) = (overlapping.first(), overlapping.last()) else {
+        return Ok(FormattedRange::default());
+    };
+    let (first, last) = widen(&sections, first, last);
+
+    let start_line = sections[first].start_line;
+    let start = line_starts[start_line];
+    let end = line_starts.get(end_line(last)).copied().unwrap_or(source.len());
+    let original = source[start..end].trim_end();
+    let formatted = format(&source[start..end])?;
+    // The run starts a line, so only offsets and line numbers move.
+    let diagnostics = formatted
+        .diagnostics
+        .into_iter()
+        .map(|diagnostic| Diagnostic {
+            offset: start + diagnostic.offset,
+            line: start_line + diagnostic.line,
+            ..diagnostic
+        })
+        .collect();
+    let edits = TextEdit::between(original, formatted.content.trim_end())
+        .into_iter()
+        .map(|edit| TextEdit {
+            range: start + edit.range.start..start + edit.range.end,
+            new_text: edit.new_text,
+        })
+        .collect();
+    Ok(FormattedRange { edits, diagnostics })
+}
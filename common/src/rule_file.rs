@@ -0,0 +1,185 @@
+//! Declarative formatting rules for one tool's table.
+//!
+//! Most tools only need their keys put in a fixed order and some arrays sorted, so instead of code they are described
+//! by a rule file that [`RuleFile::fix`] interprets. The built-in tools ship as embedded rule files, and users can load
+//! their own for tools the formatter does not know:
+//!
+//! ```toml
+//! table = "tool.acme-deploy"
+//! key_order = ["name", "targets", "environments"]
+//! sort_arrays = ["targets"]
+//! dedupe_arrays = ["targets"]
+//! case_sensitive = false
+//!
+//! [[inline_tables]]
+//! discriminator = "region"
+//! key_order = ["region", "replicas"]
+//! ```
+//!
+//! Array names are keys relative to `table`, dotted for nested ones (`src.include`). Keys missing from `key_order`
+//! follow alphabetically. An inline table inside the table that has the `discriminator` key gets its keys ordered by
+//! that schema's `key_order`.
+
+use std::fs;
+use std::path::Path;
+
+use lexical_sort::natural_lexical_cmp;
+use toml::{Table, Value};
+
+use crate::array::{dedupe_strings, sort_strings};
+use crate::table::{InlineTableSchema, Tables, for_entries, reorder_inline_table_keys, reorder_table_keys};
+
+/// Key order for the inline tables of a [`RuleFile`] that contain `discriminator`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "pyo3", pyo3::pyclass(frozen, get_all))]
+pub struct InlineTableRule {
+    pub discriminator: String,
+    pub key_order: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "pyo3", pyo3::pyclass(frozen, get_all))]
+pub struct RuleFile {
+    /// Full dotted name of the table, e.g. `tool.black`.
+    pub table: String,
+    pub key_order: Vec<String>,
+    pub sort_arrays: Vec<String>,
+    pub dedupe_arrays: Vec<String>,
+    /// Whether values differing only in case are distinct: deduplication keeps both and sorting breaks ties by case.
+    pub case_sensitive: bool,
+    pub inline_tables: Vec<InlineTableRule>,
+}
+
+impl RuleFile {
+    /// # Errors
+    ///
+    /// Returns a message when the text is not TOML, `table` is missing, a value has the wrong type or a key is unknown.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut root = text.parse::<Table>().map_err(|err| err.to_string())?;
+        let table = match root.remove("table") {
+            Some(Value::String(table)) => table,
+            Some(value) => return Err(invalid("table", "a string", &value)),
+            None => return Err(String::from("table is required")),
+        };
+        let rules = Self {
+            table,
+            key_order: string_list(&mut root, "key_order")?,
+            sort_arrays: string_list(&mut root, "sort_arrays")?,
+            dedupe_arrays: string_list(&mut root, "dedupe_arrays")?,
+            case_sensitive: match root.remove("case_sensitive") {
+                Some(Value::Boolean(flag)) => flag,
+                Some(value) => return Err(invalid("case_sensitive", "a boolean", &value)),
+                None => false,
+            },
+            inline_tables: match root.remove("inline_tables") {
+                Some(Value::Array(items)) => items.into_iter().map(inline_table_rule).collect::<Result<_, _>>()?,
+                Some(value) => return Err(invalid("inline_tables", "an array of tables", &value)),
+                None => vec![],
+            },
+        };
+        if let Some(key) = root.keys().next() {
+            return Err(format!("unknown key {key}"));
+        }
+        Ok(rules)
+    }
+
+    /// Reads and parses the rule file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the file when it cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|text| Self::parse(&text))
+            .map_err(|err| format!("rule file {}: {err}", path.display()))
+    }
+
+    /// Sorts and deduplicates the listed arrays, orders the inline tables and then the keys of the table.
+    pub fn fix(&self, tables: &Tables) {
+        let Some(elements) = tables.get(&self.table) else {
+            return;
+        };
+        let inline_orders: Vec<Vec<&str>> = self
+            .inline_tables
+            .iter()
+            .map(|rule| rule.key_order.iter().map(String::as_str).collect())
+            .collect();
+        let schemas: Vec<InlineTableSchema> = self
+            .inline_tables
+            .iter()
+            .zip(&inline_orders)
+            .map(|(rule, key_order)| InlineTableSchema {
+                discriminator: &rule.discriminator,
+                key_order,
+            })
+            .collect();
+        let table = &mut elements.first().unwrap().borrow_mut();
+        for_entries(table, &mut |key, entry| {
+            if self.dedupe_arrays.contains(&key) {
+                dedupe_strings(entry, |s| self.compare_key(s));
+            }
+            if self.sort_arrays.contains(&key) {
+                sort_strings::<String, _, _>(entry, |s| self.compare_key(&s), &|lhs, rhs| {
+                    natural_lexical_cmp(lhs, rhs)
+                });
+            }
+            if !schemas.is_empty() {
+                reorder_inline_table_keys(entry, &schemas);
+            }
+        });
+        reorder_table_keys(table, &self.table_key_order());
+    }
+
+    /// The `key_order` in the form [`reorder_table_keys`] takes, with the table's own header first.
+    #[must_use]
+    pub fn table_key_order(&self) -> Vec<&str> {
+        std::iter::once("")
+            .chain(self.key_order.iter().map(String::as_str))
+            .collect()
+    }
+
+    fn compare_key(&self, value: &str) -> String {
+        if self.case_sensitive {
+            value.to_string()
+        } else {
+            value.to_lowercase()
+        }
+    }
+}
+
+fn inline_table_rule(value: Value) -> Result<InlineTableRule, String> {
+    let mut table = match value {
+        Value::Table(table) => table,
+        value => return Err(invalid("inline_tables", "an array of tables", &value)),
+    };
+    let discriminator = match table.remove("discriminator") {
+        Some(Value::String(discriminator)) => discriminator,
+        Some(value) => return Err(invalid("inline_tables.discriminator", "a string", &value)),
+        None => return Err(String::from("inline_tables.discriminator is required")),
+    };
+    let key_order = string_list(&mut table, "key_order")?;
+    if let Some(key) = table.keys().next() {
+        return Err(format!("unknown key inline_tables.{key}"));
+    }
+    Ok(InlineTableRule {
+        discriminator,
+        key_order,
+    })
+}
+
+fn string_list(table: &mut Table, key: &str) -> Result<Vec<String>, String> {
+    match table.remove(key) {
+        None => Ok(vec![]),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| item.as_str().map(String::from))
+            .collect::<Option<_>>()
+            .ok_or_else(|| invalid(key, "an array of strings", &Value::Array(items.clone()))),
+        Some(value) => Err(invalid(key, "an array of strings", &value)),
+    }
+}
+
+fn invalid(key: &str, expected: &str, value: &Value) -> String {
+    format!("{key} must be {expected}, got {value}")
+}
@@ -0,0 +1,61 @@
+//! Ruff-style `select` and `ignore` lists choosing which formatter passes run.
+//!
+//! Rules are dotted names, and a selector covers the rule it names and every rule below it: `uv` covers the whole
+//! `[tool.uv]` pass and `project` covers `project.classifiers.generate`. When a `select` and an `ignore` entry both
+//! cover a rule, the longer one wins, so `select = ["uv", "project"]` with `ignore = ["project.classifiers.generate"]`
+//! runs both passes without generating classifiers. An empty `select` selects every rule. A sub-rule only runs as part
+//! of its pass, so a pass that is not selected skips its sub-rules too.
+
+/// The `select` and `ignore` lists of one formatting run.
+#[derive(Debug, Clone, Copy)]
+pub struct Rules<'a> {
+    select: &'a [String],
+    ignore: &'a [String],
+}
+
+impl<'a> Rules<'a> {
+    #[must_use]
+    pub const fn new(select: &'a [String], ignore: &'a [String]) -> Self {
+        Self { select, ignore }
+    }
+
+    /// Whether the pass or sub-rule named `rule` should run.
+    #[must_use]
+    pub fn enabled(&self, rule: &str) -> bool {
+        let longest = |selectors: &[String]| {
+            selectors
+                .iter()
+                .filter(|selector| covers(selector, rule))
+                .map(String::len)
+                .max()
+        };
+        let selected = if self.select.is_empty() {
+            Some(0)
+        } else {
+            longest(self.select)
+        };
+        match (selected, longest(self.ignore)) {
+            (Some(selected), Some(ignored)) => selected > ignored,
+            (selected, None) => selected.is_some(),
+            (None, Some(_)) => false,
+        }
+    }
+
+    /// The `select` and `ignore` entries that cover none of the `known` rules, such as the typo `uv.sort-array`, each
+    /// with the list it is in.
+    #[must_use]
+    pub fn unknown(&self, known: &[&str]) -> Vec<(&'static str, &'a str)> {
+        let select = self.select.iter().map(|selector| ("select", selector));
+        let ignore = self.ignore.iter().map(|selector| ("ignore", selector));
+        select
+            .chain(ignore)
+            .filter(|(_, selector)| !known.iter().any(|rule| covers(selector, rule)))
+            .map(|(list, selector)| (list, selector.as_str()))
+            .collect()
+    }
+}
+
+fn covers(selector: &str, rule: &str) -> bool {
+    rule.strip_prefix(selector)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
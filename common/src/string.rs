@@ -15,6 +15,7 @@ fn is_string_kind(kind: SyntaxKind) -> bool {
 use crate::create::{
     make_literal_string_node, make_multiline_literal_string_node, make_multiline_string_node, make_string_node,
 };
+use crate::explain::{at, record, record_generic};
 
 fn escape(text: &str) -> String {
     let escaped = tombi_toml_text::to_basic_string(text);
@@ -279,7 +280,7 @@ where
                 .text()
                 .to_string();
             let found_str_value = load_text(&string_text, kind);
-            let output = transform(found_str_value.as_str());
+            let (output, explained) = at(entry, || transform(found_str_value.as_str()));
 
             let is_multiline = kind == MULTI_LINE_BASIC_STRING || kind == MULTI_LINE_LITERAL_STRING;
             let is_literal = kind == LITERAL_STRING || kind == MULTI_LINE_LITERAL_STRING;
@@ -310,6 +311,11 @@ where
                 } else {
                     make_string_node(&output)
                 };
+                if !content_changed {
+                    record(entry, "string/normalize-quotes", &string_text, &child.to_string());
+                } else if !explained {
+                    record_generic(entry, "string/rewrite", &found_str_value, &output);
+                }
             }
         }
         to_insert.push(child);
@@ -373,6 +379,12 @@ pub fn normalize_key_quotes(root: &SyntaxNode) {
             }
         }
         let new_key = make_key(&key_parts.join("."));
+        record(
+            &descendant,
+            "key/normalize-quotes",
+            &descendant.to_string(),
+            &new_key.to_string(),
+        );
         let count = descendant.children_with_tokens().count();
         let new_children: Vec<SyntaxElement> = new_key.as_node().unwrap().children_with_tokens().collect();
         descendant.splice_children(0..count, new_children);
@@ -447,6 +459,12 @@ fn wrap_string_node_if_needed(
         } else {
             make_string_node(&text)
         };
+        let rule = if needs_wrap || single_to_multiline {
+            "string/wrap"
+        } else {
+            "string/normalize-quotes"
+        };
+        record(string_node, rule, token.text(), &new_element.to_string());
         let mut new_children: Vec<SyntaxElement> = Vec::new();
         let count = string_node.children_with_tokens().count();
         for child in string_node.children_with_tokens() {
@@ -35,6 +35,7 @@ fn get_value_text(element: &SyntaxElement) -> String {
 }
 
 use crate::create::{make_empty_inline_table, make_empty_newline, make_key, make_newline, make_table_entry};
+use crate::explain::{is_recording, record, record_path};
 
 fn ensure_table_exists(tables: &mut Tables, name: &str) {
     if !tables.header_to_pos.contains_key(name) {
@@ -272,10 +273,10 @@ impl Tables {
         );
     }
 
-    /// Like [`Tables::reorder`], but sub-tables of a table listed in `order` follow that table's key order, the
-    /// same list [`reorder_table_keys`] applies to its dotted keys: `key_order` maps a listed table name to its
-    /// key list, and a sub-table ranks by the first entry matching its remaining path. Sub-tables without a match
-    /// come after the matched ones, alphabetically.
+    /// Like [`Tables::reorder`], but sub-tables follow their table's key order, the same list
+    /// [`reorder_table_keys`] applies to its dotted keys: `key_order` maps a table name to its key list, and a
+    /// sub-table ranks by the first entry matching its remaining path. Sub-tables without a match come after the
+    /// matched ones, alphabetically. This holds for tables missing from `order` too, which keep their first position.
     pub fn reorder_with_key_order(
         &self,
         root_ast: &SyntaxNode,
@@ -296,6 +297,33 @@ impl Tables {
             key_order,
         );
 
+        if is_recording() {
+            let mut before: Vec<(usize, &String)> = self
+                .header_to_pos
+                .iter()
+                .filter(|(name, _)| order.contains(name))
+                .map(|(name, positions)| (*positions.iter().min().unwrap(), name))
+                .collect();
+            before.sort();
+            let before: Vec<&str> = before.iter().map(|(_, name)| name.as_str()).collect();
+            let after: Vec<&str> = order.iter().map(String::as_str).collect();
+            if before != after {
+                let listed = |names: &[&str]| {
+                    names
+                        .iter()
+                        .filter(|name| !name.is_empty())
+                        .copied()
+                        .collect::<Vec<_>>()
+                };
+                record_path(
+                    "",
+                    "tables/reorder",
+                    &listed(&before).join(", "),
+                    &listed(&after).join(", "),
+                );
+            }
+        }
+
         let pos_group = compute_pos_groups(&self.table_set);
         let mut group_marker: HashMap<usize, Vec<SyntaxElement>> = HashMap::new();
         for (pos, cell) in self.table_set.iter().enumerate() {
@@ -437,6 +465,7 @@ fn calculate_order(
                     let base_pos2 = base_key_first_pos.get(&key2).unwrap_or(&usize::MAX);
                     base_pos1
                         .cmp(base_pos2)
+                        .then_with(|| sub_table_rank.get(k1).cmp(&sub_table_rank.get(k2)))
                         .then_with(|| k1.to_lowercase().cmp(&k2.to_lowercase()))
                 }
             }
@@ -473,7 +502,7 @@ fn take_leading_group_marker(content: &mut Vec<SyntaxElement>) -> Option<Vec<Syn
     Some(content.drain(..after).collect())
 }
 
-fn get_key(k: &str, multi_level_prefixes: &[&str]) -> String {
+pub(crate) fn get_key(k: &str, multi_level_prefixes: &[&str]) -> String {
     let parts: Vec<&str> = k.splitn(3, '.').collect();
     let is_multi_level = multi_level_prefixes.iter().any(|prefix| *prefix == parts[0]);
     if is_multi_level && parts.len() >= 2 {
@@ -578,6 +607,7 @@ pub fn reorder_table_keys(table: &mut RefMut<Vec<SyntaxElement>>, order: &[&str]
     }
 
     let mut handled_positions = HashSet::<usize>::new();
+    let mut emitted = Vec::<usize>::new();
     for (group, header) in headers.iter().enumerate() {
         if !header.is_empty() {
             let starts_with_break = header.first().map(SyntaxElement::kind) == Some(LINE_BREAK);
@@ -607,6 +637,7 @@ pub fn reorder_table_keys(table: &mut RefMut<Vec<SyntaxElement>>, order: &[&str]
                 }
                 emit_key(&mut to_insert, &key_set[position]);
                 handled_positions.insert(position);
+                emitted.push(position);
             }
         }
         let mut unhandled: Vec<(String, usize)> = key_to_position
@@ -618,6 +649,28 @@ pub fn reorder_table_keys(table: &mut RefMut<Vec<SyntaxElement>>, order: &[&str]
         for (_, position) in unhandled {
             emit_key(&mut to_insert, &key_set[position]);
             handled_positions.insert(position);
+            emitted.push(position);
+        }
+    }
+    if is_recording() && emitted.windows(2).any(|pair| pair[0] > pair[1]) {
+        let names: HashMap<usize, &str> = key_to_position
+            .iter()
+            .map(|(key, position)| (*position, key.as_str()))
+            .collect();
+        let listed = |positions: &[usize]| {
+            let keys = positions.iter().filter_map(|position| names.get(position).copied());
+            keys.filter(|key| !key.is_empty()).collect::<Vec<_>>().join(", ")
+        };
+        let mut before = emitted.clone();
+        before.sort_unstable();
+        // The header's keys are the only KEYS among the table's own elements; the root table has none.
+        match table
+            .iter()
+            .find(|element| element.kind() == KEYS)
+            .and_then(SyntaxElement::as_node)
+        {
+            Some(header) => record(header, "table/reorder-keys", &listed(&before), &listed(&emitted)),
+            None => record_path("", "table/reorder-keys", &listed(&before), &listed(&emitted)),
         }
     }
     table.splice(0..size, to_insert);
@@ -715,6 +768,7 @@ pub fn rename_keys(table: &mut RefMut<Vec<SyntaxElement>>, aliases: &[(&str, &st
         let key_text = keys_node.text().to_string().trim().to_string();
         for &(old, new) in aliases {
             if key_text == old {
+                record(node, "table/rename-key", old, new);
                 let new_key = make_key(new);
                 let count = keys_node.children_with_tokens().count();
                 let new_children: Vec<SyntaxElement> = new_key.as_node().unwrap().children_with_tokens().collect();
@@ -794,6 +848,7 @@ pub fn collapse_sub_tables(tables: &mut Tables, name: &str) {
             }
             main.push(make_empty_inline_table(sub_name));
             sub.clear();
+            record_collapse(name, sub_name);
             continue;
         }
 
@@ -841,7 +896,34 @@ pub fn collapse_sub_tables(tables: &mut Tables, name: &str) {
             main.push(child.clone());
         }
         sub.clear();
+        record_collapse(name, sub_name);
+    }
+}
+
+fn record_collapse(parent_name: &str, sub_name: &str) {
+    if !is_recording() {
+        return;
+    }
+    let full_name = format!("{parent_name}.{sub_name}");
+    record_path(
+        &full_name,
+        "table/collapse",
+        &format!("[{full_name}]"),
+        &format!("[{parent_name}] {sub_name}"),
+    );
+}
+
+fn record_expand(parent_name: &str, sub_name: &str) {
+    if !is_recording() {
+        return;
     }
+    let full_name = format!("{parent_name}.{sub_name}");
+    record_path(
+        &full_name,
+        "table/expand",
+        &format!("[{parent_name}] {sub_name}"),
+        &format!("[{full_name}]"),
+    );
 }
 
 pub fn expand_sub_tables(tables: &mut Tables, name: &str) {
@@ -885,6 +967,7 @@ pub fn expand_sub_tables(tables: &mut Tables, name: &str) {
     );
 
     for (sub_name, entries) in groups {
+        record_expand(name, &sub_name);
         let full_name = format!("{name}.{sub_name}");
 
         let mut new_table = make_table_entry(&full_name);
@@ -1248,10 +1331,17 @@ where
     });
     for full_name in all_sub_tables {
         let (parent, sub) = split_table_name(&full_name);
+        let existed = tables.get(&full_name).is_some();
         if should_collapse(&full_name) {
             collapse_sub_table(tables, parent, sub, column_width);
+            if existed && tables.get(&full_name).is_none() {
+                record_collapse(parent, sub);
+            }
         } else {
             expand_sub_table(tables, parent, sub);
+            if !existed && tables.get(&full_name).is_some() {
+                record_expand(parent, sub);
+            }
         }
     }
 }
@@ -1297,9 +1387,9 @@ fn add_intermediate_parents(table_name: &str, prefix_dots: usize, result: &mut V
     }
 }
 
-pub struct InlineTableSchema {
-    pub discriminator: &'static str,
-    pub key_order: &'static [&'static str],
+pub struct InlineTableSchema<'a> {
+    pub discriminator: &'a str,
+    pub key_order: &'a [&'a str],
 }
 
 fn inline_table_key_name(kv_node: &SyntaxNode) -> String {
@@ -1315,7 +1405,7 @@ fn inline_table_key_name(kv_node: &SyntaxNode) -> String {
         .to_string()
 }
 
-fn detect_schema<'a>(keys: &[String], schemas: &'a [InlineTableSchema]) -> Option<&'a [&'static str]> {
+fn detect_schema<'a>(keys: &[String], schemas: &'a [InlineTableSchema<'a>]) -> Option<&'a [&'a str]> {
     schemas
         .iter()
         .find(|s| keys.iter().any(|k| k == s.discriminator))
@@ -1429,7 +1519,7 @@ fn build_inline_table_text(entries: &[&InlineEntry]) -> String {
     out
 }
 
-fn reorder_single_inline_table(node: &SyntaxNode, schemas: &[InlineTableSchema]) {
+fn reorder_single_inline_table(node: &SyntaxNode, schemas: &[InlineTableSchema<'_>]) {
     let Some(entries) = collect_inline_entries(node) else {
         return;
     };
@@ -1445,12 +1535,28 @@ fn reorder_single_inline_table(node: &SyntaxNode, schemas: &[InlineTableSchema])
     let key_position = |k: &str| -> usize { schema.iter().position(|s| *s == k).unwrap_or(usize::MAX) };
     let mut order: Vec<usize> = (0..entries.len()).collect();
     order.sort_by_key(|&i| key_position(&entries[i].key));
+    rebuild_inline_table(node, &entries, &order);
+}
 
+/// Rewrite the inline table with its entries in `order`, a permutation of entry indices; a no-op when
+/// the order is unchanged.
+fn rebuild_inline_table(node: &SyntaxNode, entries: &[InlineEntry], order: &[usize]) {
     if order.iter().enumerate().all(|(new, &old)| new == old) {
         return;
     }
 
     let sorted: Vec<&InlineEntry> = order.iter().map(|&i| &entries[i]).collect();
+    if is_recording() {
+        let keys = |entries: &mut dyn Iterator<Item = &InlineEntry>| {
+            entries.map(|entry| entry.key.as_str()).collect::<Vec<_>>().join(", ")
+        };
+        record(
+            node,
+            "inline-table/reorder-keys",
+            &keys(&mut entries.iter()),
+            &keys(&mut sorted.iter().copied()),
+        );
+    }
     let rebuilt = build_inline_table_text(&sorted);
     let parsed = parse(&rebuilt);
     let new_children: Option<Vec<SyntaxElement>> = parsed
@@ -1467,7 +1573,24 @@ fn reorder_single_inline_table(node: &SyntaxNode, schemas: &[InlineTableSchema])
     }
 }
 
-pub fn reorder_inline_table_keys(root_ast: &SyntaxNode, schemas: &[InlineTableSchema]) {
+/// Sort the keys of one inline table by `to_key`, keeping each key's comments attached to it.
+pub fn sort_inline_table_keys<T, K>(node: &SyntaxNode, to_key: K)
+where
+    T: Ord,
+    K: Fn(&str) -> T,
+{
+    if node.kind() != INLINE_TABLE {
+        return;
+    }
+    let Some(entries) = collect_inline_entries(node) else {
+        return;
+    };
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    order.sort_by_key(|&i| to_key(&entries[i].key));
+    rebuild_inline_table(node, &entries, &order);
+}
+
+pub fn reorder_inline_table_keys(root_ast: &SyntaxNode, schemas: &[InlineTableSchema<'_>]) {
     let inline_tables: Vec<SyntaxNode> = root_ast.descendants().filter(|n| n.kind() == INLINE_TABLE).collect();
     for node in inline_tables {
         reorder_single_inline_table(&node, schemas);
@@ -3,8 +3,8 @@ use tombi_syntax::SyntaxKind::{ARRAY, KEY_VALUE, KEY_VALUE_GROUP, KEYS};
 use tombi_syntax::SyntaxNode;
 
 use crate::array::{
-    align_array_comments, dedupe_strings, ensure_all_arrays_multiline, ensure_trailing_comma, remove_strings, sort,
-    sort_strings, transform,
+    align_array_comments, dedupe_strings, ensure_all_arrays_multiline, ensure_trailing_comma, merge_strings,
+    remove_strings, sort, sort_strings, transform,
 };
 use crate::pep508::Requirement;
 use crate::tests::{format_toml, format_toml_str};
@@ -82,6 +82,20 @@ fn dedupe_strings_helper(start: &str) -> String {
     })
 }
 
+fn merge_strings_helper(start: &str) -> String {
+    apply_to_arrays(start, |array| {
+        merge_strings(
+            array,
+            |s| s.split(['<', '>', '=']).next().map(str::to_string),
+            |_, values| {
+                let key_len = values[0].find(['<', '>', '=']).unwrap_or(values[0].len());
+                let specs: Vec<&str> = values.iter().map(|v| &v[key_len..]).filter(|v| !v.is_empty()).collect();
+                Some(format!("{}{}", &values[0][..key_len], specs.join(",")))
+            },
+        );
+    })
+}
+
 #[test]
 fn test_normalize_requirement_strip_micro_no_keep() {
     let start = indoc! {r#"
@@ -369,6 +383,49 @@ fn test_dedupe_strings_multiline_with_trailing_duplicate() {
     insta::assert_snapshot!(res, @r#"a = [ "A", "B" ]"#);
 }
 
+#[test]
+fn test_merge_strings_single_line() {
+    let start = indoc! {r#"
+    a = ["a>=1", "b", "a<2"]
+    "#};
+    let res = merge_strings_helper(start);
+    insta::assert_snapshot!(res, @r#"a = [ "a>=1,<2", "b" ]"#);
+}
+
+#[test]
+fn test_merge_strings_moves_comments_to_kept_value() {
+    let start = indoc! {r#"
+    a = [
+      # first
+      "a>=1",  # keep
+      "b",
+      # second
+      "a<2",  # trailing
+    ]
+    "#};
+    let res = merge_strings_helper(start);
+    insta::assert_snapshot!(res, @r#"
+    a = [
+      # first
+      # second
+      # trailing
+      "a>=1,<2", # keep
+      "b",
+    ]
+    "#);
+}
+
+#[test]
+fn test_merge_strings_declined_leaves_values() {
+    let start = indoc! {r#"
+    a = ["a>=1", "a<2"]
+    "#};
+    let res = apply_to_arrays(start, |array| {
+        merge_strings(array, |s| s.get(..1).map(str::to_string), |_, _| None);
+    });
+    insta::assert_snapshot!(res, @r#"a = [ "a>=1", "a<2" ]"#);
+}
+
 #[test]
 fn test_dedupe_strings_duplicate_at_end_no_trailing_comma() {
     let start = indoc! {r#"
@@ -1061,7 +1118,7 @@ fn test_dedupe_on_non_array_is_noop() {
 
 fn remove_strings_helper(start: &str, value: &str) -> String {
     apply_to_arrays(start, |array| {
-        remove_strings(array, |s| s == value);
+        remove_strings(array, |_, s| s == value);
     })
 }
 
@@ -0,0 +1,241 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::{
+    ConfigValue, FormatArgs, Formatter, config_list, config_string, list_argument, options_for, run, spacing_argument,
+};
+use crate::diagnostic::Diagnostic;
+use crate::explain::Explanation;
+use crate::formatted::{Formatted, TextEdit};
+use crate::range::Selection;
+
+struct Dumb;
+
+#[derive(Debug, Clone, clap::Args)]
+struct DumbArgs {
+    #[arg(long, default_value = "none")]
+    extra: String,
+}
+
+impl Formatter for Dumb {
+    type Args = DumbArgs;
+    const PROG: &'static str = "dumb-fmt";
+    const VERSION: &'static str = "1.0";
+    const FILENAME: &'static str = "dumb.toml";
+    const OVERRIDE_SECTION: &'static [&'static str] = &["tool", "dumb-fmt"];
+
+    fn apply_config(args: &mut DumbArgs, key: &str, value: &ConfigValue, _config_dir: &Path) -> Result<bool, String> {
+        if key != "extra" {
+            return Ok(false);
+        }
+        args.extra = config_string(key, value)?;
+        Ok(true)
+    }
+
+    fn format(text: &str, shared: &FormatArgs, args: &DumbArgs) -> Result<Formatted, String> {
+        if text.contains("reject") {
+            return Err(String::from("rejected"));
+        }
+        let mut content: String = text
+            .lines()
+            .filter(|line| !line.starts_with("# width="))
+            .map(|line| format!("{line}\n"))
+            .collect();
+        let explain = if shared.explain { " explain" } else { "" };
+        content.push_str(&format!(
+            "# width={} extra={}{explain}\n",
+            shared.column_width, args.extra
+        ));
+        let diagnostics = text
+            .find("bad")
+            .map(|offset| Diagnostic {
+                offset,
+                line: 1,
+                column: offset + 1,
+                key_path: String::from("a"),
+                value: String::from("bad"),
+                message: String::from("invalid"),
+            })
+            .into_iter()
+            .collect();
+        let explanations = shared
+            .explain
+            .then(|| Explanation {
+                rule: String::from("dumb/add-width"),
+                key_path: String::from("width"),
+                before: String::new(),
+                after: shared.column_width.to_string(),
+            })
+            .into_iter()
+            .collect();
+        Ok(Formatted {
+            content,
+            diagnostics,
+            explanations,
+        })
+    }
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("toml-fmt-cli-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn run_dumb(args: &[&str]) -> u8 {
+    run::<Dumb>(std::iter::once("dumb-fmt").chain(args.iter().copied()))
+}
+
+#[test]
+fn test_run_formats_in_place_then_reports_no_change() {
+    let dir = temp_dir("in-place");
+    let file = dir.join("dumb.toml");
+    fs::write(&file, "a = 1\n").unwrap();
+    assert_eq!(run_dumb(&[file.to_str().unwrap()]), 1);
+    assert_eq!(fs::read_to_string(&file).unwrap(), "a = 1\n# width=120 extra=none\n");
+    assert_eq!(run_dumb(&[file.to_str().unwrap()]), 0);
+}
+
+#[test]
+fn test_run_explain_reaches_formatter_but_not_config() {
+    let dir = temp_dir("explain");
+    fs::write(dir.join("dumb-fmt.toml"), "column_width = 90\n").unwrap();
+    let file = dir.join("dumb.toml");
+    fs::write(&file, "a = 1\n").unwrap();
+    assert_eq!(run_dumb(&["--explain", file.to_str().unwrap()]), 1);
+    assert_eq!(
+        fs::read_to_string(&file).unwrap(),
+        "a = 1\n# width=90 extra=none explain\n"
+    );
+    assert_eq!(run_dumb(&["--explain", "--check", file.to_str().unwrap()]), 0);
+}
+
+#[test]
+fn test_run_check_leaves_file_untouched() {
+    let dir = temp_dir("check");
+    let file = dir.join("dumb.toml");
+    fs::write(&file, "a = 1\n").unwrap();
+    assert_eq!(run_dumb(&["--check", file.to_str().unwrap()]), 1);
+    assert_eq!(fs::read_to_string(&file).unwrap(), "a = 1\n");
+}
+
+#[test]
+fn test_run_directory_input_uses_filename() {
+    let dir = temp_dir("directory");
+    fs::write(dir.join("dumb.toml"), "a = 1\n# width=80 extra=none\n").unwrap();
+    assert_eq!(run_dumb(&["--column-width", "80", dir.to_str().unwrap()]), 0);
+}
+
+#[test]
+fn test_run_config_file_then_section_override_cli() {
+    let dir = temp_dir("config");
+    let nested = dir.join("nested");
+    fs::create_dir_all(&nested).unwrap();
+    fs::write(dir.join("dumb-fmt.toml"), "column_width = 90\nextra = \"shared\"\n").unwrap();
+    let file = nested.join("dumb.toml");
+    fs::write(&file, "[tool.dumb-fmt]\nextra = \"section\"\n").unwrap();
+    assert_eq!(
+        run_dumb(&["--column-width", "70", "--extra", "cli", file.to_str().unwrap()]),
+        1
+    );
+    assert_eq!(
+        fs::read_to_string(&file).unwrap(),
+        "[tool.dumb-fmt]\nextra = \"section\"\n# width=90 extra=section\n"
+    );
+}
+
+#[test]
+fn test_run_explicit_config_skips_discovery() {
+    let dir = temp_dir("explicit");
+    fs::write(dir.join("dumb-fmt.toml"), "column_width = 90\n").unwrap();
+    let explicit = dir.join("other.toml");
+    fs::write(&explicit, "column_width = 60\n").unwrap();
+    let file = dir.join("dumb.toml");
+    fs::write(&file, "a = 1\n").unwrap();
+    assert_eq!(
+        run_dumb(&["--config", explicit.to_str().unwrap(), file.to_str().unwrap()]),
+        1
+    );
+    assert_eq!(fs::read_to_string(&file).unwrap(), "a = 1\n# width=60 extra=none\n");
+}
+
+#[test]
+fn test_run_rejection_fails_without_writing() {
+    let dir = temp_dir("reject");
+    let file = dir.join("dumb.toml");
+    fs::write(&file, "reject = 1\n").unwrap();
+    assert_eq!(run_dumb(&[file.to_str().unwrap()]), 1);
+    assert_eq!(fs::read_to_string(&file).unwrap(), "reject = 1\n");
+}
+
+#[test]
+fn test_run_diagnostics_fail_formatted_file() {
+    let dir = temp_dir("diagnostics");
+    let file = dir.join("dumb.toml");
+    fs::write(&file, "a = \"bad\"\n# width=120 extra=none\n").unwrap();
+    assert_eq!(run_dumb(&[file.to_str().unwrap()]), 1);
+}
+
+#[test]
+fn test_run_invalid_config_value_fails() {
+    let dir = temp_dir("invalid-config");
+    let file = dir.join("dumb.toml");
+    fs::write(&file, "[tool.dumb-fmt]\ncolumn_width = \"wide\"\n").unwrap();
+    assert_eq!(run_dumb(&[file.to_str().unwrap()]), 1);
+    assert_eq!(
+        fs::read_to_string(&file).unwrap(),
+        "[tool.dumb-fmt]\ncolumn_width = \"wide\"\n"
+    );
+}
+
+#[test]
+fn test_options_for_applies_config_file_then_section() {
+    let dir = temp_dir("options-for");
+    fs::write(dir.join("dumb-fmt.toml"), "column_width = 90\nextra = \"shared\"\n").unwrap();
+    let file = dir.join("dumb.toml");
+    let text = "[tool.dumb-fmt]\nextra = \"section\"\n";
+    let (shared, args) = options_for::<Dumb>(Some(&file), text).unwrap();
+    assert_eq!((shared.column_width, args.extra.as_str()), (90, "section"));
+    assert_eq!(shared.indent, 2);
+}
+
+#[test]
+fn test_options_for_reads_select_and_ignore() {
+    let text = "[tool.dumb-fmt]\nselect = [\"project\", \"uv\"]\nignore = \"project.classifiers.generate\"\n";
+    let (shared, _) = options_for::<Dumb>(None, text).unwrap();
+    assert_eq!(shared.select, ["project", "uv"]);
+    assert_eq!(shared.ignore, ["project.classifiers.generate"]);
+}
+
+#[test]
+fn test_format_range_defaults_to_whole_document_edits() {
+    let (shared, args) = options_for::<Dumb>(None, "").unwrap();
+    let got = Dumb::format_range("a = 1\n", &Selection::Lines(1..=1), &shared, &args).unwrap();
+    assert_eq!(
+        TextEdit::apply("a = 1\n", &got.edits),
+        "a = 1\n# width=120 extra=none\n"
+    );
+}
+
+#[test]
+fn test_list_argument_trims_and_drops_empty() {
+    assert_eq!(list_argument(" a, b ,,c ").unwrap(), ["a", "b", "c"]);
+    assert!(list_argument("").unwrap().is_empty());
+}
+
+#[test]
+fn test_spacing_argument_unescapes_newlines() {
+    assert_eq!(spacing_argument(r"\n\n").unwrap(), "\n\n");
+}
+
+#[test]
+fn test_config_list_accepts_array_or_string() {
+    let array = ConfigValue::Array(vec![ConfigValue::from("a"), ConfigValue::from("b")]);
+    assert_eq!(config_list("k", &array).unwrap(), ["a", "b"]);
+    assert_eq!(config_list("k", &ConfigValue::from("a,b")).unwrap(), ["a", "b"]);
+    assert_eq!(
+        config_list("k", &ConfigValue::from(1)).unwrap_err(),
+        "k must be an array of strings, got 1"
+    );
+}
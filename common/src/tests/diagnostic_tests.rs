@@ -0,0 +1,112 @@
+use indoc::indoc;
+use tombi_syntax::SyntaxKind::BASIC_STRING;
+use tombi_syntax::SyntaxNode;
+
+use crate::diagnostic::Diagnostics;
+use crate::string::update_content;
+use crate::test_util::parse;
+
+fn strings(root: &SyntaxNode) -> Vec<SyntaxNode> {
+    root.descendants().filter(|node| node.kind() == BASIC_STRING).collect()
+}
+
+#[test]
+fn test_report_node_points_at_string_value() {
+    let source = indoc! {r#"
+        [project]
+        dependencies = [
+          "requests>=2.0,,",
+        ]
+    "#};
+    let root = parse(source);
+    let diagnostics = Diagnostics::default();
+    diagnostics.report_node(
+        &strings(&root)[0],
+        "project.dependencies",
+        "requests>=2.0,,",
+        "bad specifier",
+    );
+    let located = diagnostics.locate(source);
+    assert_eq!(located.len(), 1);
+    assert_eq!((located[0].line, located[0].column), (3, 4));
+    assert_eq!(located[0].offset, source.find("requests").unwrap());
+    assert_eq!(located[0].to_string(), "3:4: project.dependencies: bad specifier");
+}
+
+#[test]
+fn test_report_node_maps_back_through_earlier_edits() {
+    let source = "a = [\"x  >=  1\", \"bad,,\"]\nb = [\"bad,,\"]\n";
+    let root = parse(source);
+    let nodes = strings(&root);
+    update_content(&nodes[0], |_| String::from("x>=1"));
+    let diagnostics = Diagnostics::default();
+    diagnostics.report_node(&nodes[2], "b", "bad,,", "second");
+    diagnostics.report_node(&nodes[1], "a", "bad,,", "first");
+    let located = diagnostics.locate(source);
+    assert_eq!((located[0].line, located[0].column), (2, 7));
+    assert_eq!(located[1].offset, source.find("bad").unwrap());
+}
+
+#[test]
+fn test_report_node_after_its_own_value_changed() {
+    let source = "a = [\"Requests >= 2\", \"requests>=2\"]\n";
+    let root = parse(source);
+    let nodes = strings(&root);
+    update_content(&nodes[0], |_| String::from("requests>=2"));
+    let diagnostics = Diagnostics::default();
+    diagnostics.report_node(&nodes[0], "a", "requests>=2", "normalized");
+    let located = diagnostics.locate(source);
+    assert_eq!(located[0].offset, source.find("Requests").unwrap());
+}
+
+#[test]
+fn test_report_key_path_picks_its_entry() {
+    let source = indoc! {r#"
+        a = ["bad,,"]
+        b = ["bad,,", "bad,,"]
+    "#};
+    let diagnostics = Diagnostics::default();
+    diagnostics.report("b", "bad,,", "first");
+    diagnostics.report("b", "bad,,", "second");
+    diagnostics.report("a", "bad,,", "third");
+    let located = diagnostics.locate(source);
+    assert_eq!((located[0].line, located[0].column), (2, 7));
+    assert_eq!((located[1].line, located[1].column), (2, 16));
+    assert_eq!((located[2].line, located[2].column), (1, 7));
+}
+
+#[test]
+fn test_report_key_path_in_table_and_quoted_keys() {
+    let source = indoc! {r#"
+        [env."3.13"]
+        base_python = "python3.12"
+    "#};
+    let diagnostics = Diagnostics::default();
+    diagnostics.report("env.3.13.base_python", "python3.12", "mismatch");
+    diagnostics.report("env.3.13", "3.13", "unproduced");
+    let located = diagnostics.locate(source);
+    assert_eq!((located[0].line, located[0].column), (2, 16));
+    assert_eq!((located[1].line, located[1].column), (1, 7));
+}
+
+#[test]
+fn test_report_key_path_falls_back_to_parent_then_start() {
+    let source = "a = 1\n[tool]\nx = 2\n";
+    let diagnostics = Diagnostics::default();
+    diagnostics.report("tool.missing", "nothing", "parent");
+    diagnostics.report("nowhere", "nothing", "absent");
+    let located = diagnostics.locate(source);
+    assert_eq!((located[0].line, located[0].column), (2, 1));
+    assert_eq!((located[1].offset, located[1].line, located[1].column), (0, 1, 1));
+}
+
+#[test]
+fn test_locate_counts_characters_not_bytes() {
+    let source = "name = \"é\" # x\ndeps = [\"bad,,\"]\nother = [\"ü\", \"bad,,\"]\n";
+    let diagnostics = Diagnostics::default();
+    diagnostics.report("deps", "bad,,", "one");
+    diagnostics.report("other", "bad,,", "two");
+    let located = diagnostics.locate(source);
+    assert_eq!((located[0].line, located[0].column), (2, 10));
+    assert_eq!((located[1].line, located[1].column), (3, 16));
+}
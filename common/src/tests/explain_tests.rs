@@ -0,0 +1,148 @@
+use indoc::indoc;
+
+use crate::array::{sort_strings, transform};
+use crate::explain::{Explanation, collect, record_edits, record_here, with_rule};
+use crate::table::{Tables, for_entries, rename_keys, reorder_table_keys};
+
+fn parse(source: &str) -> tombi_syntax::SyntaxNode {
+    tombi_parser::parse(source).syntax_node().clone_for_update()
+}
+
+fn explained(source: &str, pass: impl FnOnce(&mut Tables)) -> Vec<Explanation> {
+    let root_ast = parse(source);
+    let mut tables = Tables::from_ast(&root_ast);
+    collect(true, || pass(&mut tables)).1
+}
+
+fn rules(explanations: &[Explanation]) -> Vec<String> {
+    explanations.iter().map(ToString::to_string).collect()
+}
+
+fn lowercase_dependencies(tables: &mut Tables) {
+    for_entries(&tables.get("project").unwrap()[0].borrow(), &mut |_, array| {
+        transform(array, &str::to_lowercase);
+    });
+}
+
+#[test]
+fn test_not_collecting_records_nothing() {
+    let root_ast = parse("[project]\nb = 1\na = 2\n");
+    let mut tables = Tables::from_ast(&root_ast);
+    let (got, explanations) = collect(false, || {
+        reorder_table_keys(&mut tables.get("project").unwrap()[0].borrow_mut(), &["a", "b"]);
+        42
+    });
+    assert_eq!(got, 42);
+    assert!(root_ast.to_string().find("a = 2") < root_ast.to_string().find("b = 1"));
+    assert!(explanations.is_empty());
+}
+
+#[test]
+fn test_pass_without_changes_records_nothing() {
+    assert!(explained("[project]\nname = \"a\"\n", |_| {}).is_empty());
+}
+
+#[test]
+fn test_rename_key_keeps_value() {
+    let got = explained("[tox]\nenvlist = [\"py\"]\n", |tables| {
+        rename_keys(
+            &mut tables.get("tox").unwrap()[0].borrow_mut(),
+            &[("envlist", "env_list")],
+        );
+    });
+    assert_eq!(rules(&got), ["tox.env_list: table/rename-key"]);
+    assert_eq!((got[0].before.as_str(), got[0].after.as_str()), ("envlist", "env_list"));
+}
+
+#[test]
+fn test_reorder_keys_names_table() {
+    let got = explained("[project]\nversion = \"1\"\nname = \"a\"\n", |tables| {
+        reorder_table_keys(
+            &mut tables.get("project").unwrap()[0].borrow_mut(),
+            &["name", "version"],
+        );
+    });
+    assert_eq!(rules(&got), ["project: table/reorder-keys"]);
+    assert_eq!(
+        (got[0].before.as_str(), got[0].after.as_str()),
+        ("version, name", "name, version")
+    );
+}
+
+#[test]
+fn test_array_element_rewrite_points_at_index() {
+    let start = indoc! {r#"
+        [project]
+        dependencies = ["click", "Requests"]
+    "#};
+    let got = explained(start, lowercase_dependencies);
+    assert_eq!(rules(&got), ["project.dependencies[1]: string/rewrite"]);
+    assert_eq!(
+        (got[0].before.as_str(), got[0].after.as_str()),
+        ("Requests", "requests")
+    );
+}
+
+#[test]
+fn test_with_rule_names_generic_changes() {
+    let got = explained("[project]\ndependencies = [\"B\"]\n", |tables| {
+        with_rule("demo/lowercase", || lowercase_dependencies(tables));
+    });
+    assert_eq!(rules(&got), ["project.dependencies[0]: demo/lowercase"]);
+}
+
+#[test]
+fn test_transformation_records_its_own_steps() {
+    let got = explained("[project]\ndependencies = [\"a\", \"B\"]\n", |tables| {
+        for_entries(&tables.get("project").unwrap()[0].borrow(), &mut |_, array| {
+            transform(array, &|value| {
+                let lowered = value.to_lowercase();
+                if lowered != value {
+                    record_here("demo/lowercase-name", value, &lowered);
+                }
+                lowered
+            });
+        });
+    });
+    assert_eq!(rules(&got), ["project.dependencies[1]: demo/lowercase-name"]);
+}
+
+#[test]
+fn test_sort_records_array_order() {
+    let got = explained("[project]\nkeywords = [\"b\", \"a\"]\n", |tables| {
+        for_entries(&tables.get("project").unwrap()[0].borrow(), &mut |_, array| {
+            sort_strings::<String, _, _>(array, |value| value, &|lhs, rhs| lhs.cmp(rhs));
+        });
+    });
+    assert_eq!(rules(&got), ["project.keywords: array/sort"]);
+    assert_eq!(
+        (got[0].before.as_str(), got[0].after.as_str()),
+        ("\"b\", \"a\"", "\"a\", \"b\"")
+    );
+}
+
+#[test]
+fn test_reorder_tables_records_header_order() {
+    let root_ast = parse("[tool.ruff]\na = 1\n\n[project]\nname = \"a\"\n");
+    let tables = Tables::from_ast(&root_ast);
+    let ((), got) = collect(true, || {
+        tables.reorder(&root_ast, &["", "project", "tool"], &["tool"], "\n", "");
+    });
+    assert_eq!(rules(&got), [": tables/reorder"]);
+    assert_eq!(
+        (got[0].before.as_str(), got[0].after.as_str()),
+        ("tool.ruff, project", "project, tool.ruff")
+    );
+}
+
+#[test]
+fn test_record_edits_groups_by_key() {
+    let before = "[a]\nb=1\nc=2\n\n[d]\ne=3\n";
+    let after = "[a]\nb = 1\nc = 2\n\n[d]\ne = 3\n";
+    let ((), got) = collect(true, || record_edits("demo/spacing", before, after));
+    assert_eq!(
+        rules(&got),
+        ["a.b: demo/spacing", "a.c: demo/spacing", "d.e: demo/spacing"]
+    );
+    assert_eq!((got[0].before.as_str(), got[0].after.as_str()), ("=", " = "));
+}
@@ -0,0 +1,52 @@
+use crate::formatted::{Formatted, TextEdit};
+
+fn edit(range: std::ops::Range<usize>, new_text: &str) -> TextEdit {
+    TextEdit {
+        range,
+        new_text: new_text.to_string(),
+    }
+}
+
+#[test]
+fn test_between_keeps_unchanged_tokens_out_of_edits() {
+    let edits = TextEdit::between("a=1\n", "a = 1\n");
+    assert_eq!(edits, [edit(1..1, " "), edit(2..2, " ")]);
+}
+
+#[test]
+fn test_between_merges_adjacent_changes() {
+    let edits = TextEdit::between("name = 'My_Package'\n", "name = \"my-package\"\n");
+    assert_eq!(edits, [edit(7..19, "\"my-package\"")]);
+}
+
+#[test]
+fn test_between_identical_has_no_edits() {
+    assert!(TextEdit::between("[a]\nb = 1\n", "[a]\nb = 1\n").is_empty());
+}
+
+#[test]
+fn test_between_round_trips() {
+    let cases = [
+        ("", "a = 1\n"),
+        ("a = 1\n", ""),
+        ("b = 2\na = 1\n", "a = 1\nb = 2\n"),
+        ("x = [\"é\",\"ü\"]", "x = [ \"é\", \"ü\" ]\n"),
+        (
+            "[tool.ruff]\nline-length=120\n# keep\n",
+            "[tool.ruff]\nline-length = 120\n# keep\n",
+        ),
+    ];
+    for (before, after) in cases {
+        assert_eq!(TextEdit::apply(before, &TextEdit::between(before, after)), after);
+    }
+}
+
+#[test]
+fn test_formatted_edits_against_source() {
+    let formatted = Formatted {
+        content: String::from("[a]\nb = 1\n"),
+        diagnostics: vec![],
+        explanations: vec![],
+    };
+    assert_eq!(formatted.edits("[a]\nb=1\n"), [edit(5..5, " "), edit(6..6, " ")]);
+}
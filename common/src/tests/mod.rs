@@ -3,9 +3,17 @@ use tombi_syntax::SyntaxNode;
 pub use crate::test_util::format_toml_str;
 
 pub mod array_tests;
+#[cfg(feature = "cli")]
+pub mod cli_tests;
 pub mod create_tests;
+pub mod diagnostic_tests;
 pub mod disabled_tests;
+pub mod explain_tests;
+pub mod formatted_tests;
 pub mod pep508_tests;
+pub mod range_tests;
+pub mod rule_file_tests;
+pub mod rules_tests;
 pub mod string_tests;
 pub mod table_tests;
 pub mod util_tests;
@@ -1,4 +1,4 @@
-use crate::pep508::{MarkerExpr, Requirement, is_valid_version};
+use crate::pep508::{MarkerExpr, Requirement, Resolved, Version, VersionOp, is_valid_version, normalize_requirement};
 
 fn format_requirement_helper(start: &str, keep_full_version: bool) -> String {
     Requirement::new(start)
@@ -374,6 +374,126 @@ fn test_marker_expression_compatible_release() {
     insta::assert_snapshot!(result, @"python_version~='3.8'");
 }
 
+fn normalize_marker_helper(input: &str) -> String {
+    MarkerExpr::new(input).unwrap().normalize().to_string()
+}
+
+#[test]
+fn test_marker_normalize_sorts_operands() {
+    let one = normalize_marker_helper("python_version<'3.11' and sys_platform=='win32'");
+    let other = normalize_marker_helper(r#"sys_platform == "win32" and python_version < "3.11""#);
+    insta::assert_snapshot!(one, @"python_version<'3.11' and sys_platform=='win32'");
+    assert_eq!(one, other);
+}
+
+#[test]
+fn test_marker_normalize_flattens_and_drops_redundant_parentheses() {
+    let result = normalize_marker_helper("(os_name=='nt' and (python_version<'3.9' and os_name=='nt'))");
+    insta::assert_snapshot!(result, @"os_name=='nt' and python_version<'3.9'");
+}
+
+#[test]
+fn test_marker_normalize_keeps_parentheses_of_or_inside_and() {
+    let result = normalize_marker_helper("python_version>'3.8' and (sys_platform=='win32' or (os_name=='nt'))");
+    insta::assert_snapshot!(result, @"(os_name=='nt' or sys_platform=='win32') and python_version>'3.8'");
+}
+
+#[test]
+fn test_marker_normalize_renames_legacy_variables() {
+    let result = normalize_marker_helper("python_implementation=='CPython' or os.name=='posix'");
+    insta::assert_snapshot!(result, @"os_name=='posix' or platform_python_implementation=='CPython'");
+}
+
+#[test]
+fn test_requirement_equivalent_markers_compare_equal() {
+    let one = Requirement::new("pkg; python_version<'3.11' and sys_platform=='win32'").unwrap();
+    let other = Requirement::new(r#"pkg ; (sys_platform == "win32") and python_version < "3.11""#).unwrap();
+    assert_ne!(one, other);
+    assert_eq!(one.normalize(false), other.normalize(false));
+}
+
+fn resolve_helper(input: &str) -> Option<String> {
+    let decide = |left: &str, op: &str, right: &str| match (left, op, right) {
+        ("python_version", "<", "3.8") => Some(false),
+        ("python_version", ">=", "3.9") => Some(true),
+        _ => None,
+    };
+    Requirement::new(input)
+        .unwrap()
+        .resolve_marker(&decide)
+        .map(|req| req.to_string())
+}
+
+#[test]
+fn test_resolve_marker_drops_requirement_that_never_applies() {
+    assert_eq!(resolve_helper("tomli>=1; python_version < '3.8'"), None);
+    assert_eq!(resolve_helper("tomli; python_version<'3.8' and os_name=='nt'"), None);
+}
+
+#[test]
+fn test_resolve_marker_strips_clauses_that_always_hold() {
+    assert_eq!(resolve_helper("pkg; python_version>='3.9'").as_deref(), Some("pkg"));
+    assert_eq!(
+        resolve_helper("pkg; python_version>='3.9' and (os_name=='nt' or python_version<'3.8')").as_deref(),
+        Some("pkg; os_name=='nt'")
+    );
+    assert_eq!(
+        resolve_helper("pkg; python_version<'3.8' or sys_platform=='win32'").as_deref(),
+        Some("pkg; sys_platform=='win32'")
+    );
+}
+
+#[test]
+fn test_marker_resolve_keeps_undecided_marker() {
+    let marker = MarkerExpr::new("os_name=='nt'").unwrap();
+    assert_eq!(marker.clone().resolve(&|_, _, _| None), Resolved::Marker(marker));
+}
+
+#[test]
+fn test_requirement_merge_joins_specifiers() {
+    let first = Requirement::new("requests>=2").unwrap();
+    let second = Requirement::new("Requests<3").unwrap();
+    assert_eq!(first.identity(), second.identity());
+    assert_eq!(first.merge(&second).unwrap().to_string(), "requests>=2,<3");
+}
+
+#[test]
+fn test_requirement_merge_rejects_unsatisfiable_specifiers() {
+    let first = Requirement::new("pkg==1").unwrap();
+    let second = Requirement::new("pkg==2").unwrap();
+    assert_eq!(
+        first.merge(&second).unwrap_err(),
+        "conflicting version pins for pkg, no version satisfies ==1,==2"
+    );
+    let first = Requirement::new("pkg~=1.4").unwrap();
+    let second = Requirement::new("pkg<1.4").unwrap();
+    assert_eq!(
+        first.merge(&second).unwrap_err(),
+        "conflicting version pins for pkg, no version satisfies ~=1.4,<1.4"
+    );
+}
+
+#[test]
+fn test_requirement_identity_includes_extras_and_marker() {
+    let plain = Requirement::new("pkg>=1").unwrap();
+    assert_ne!(plain.identity(), Requirement::new("pkg[cli]>=1").unwrap().identity());
+    assert_ne!(
+        plain.identity(),
+        Requirement::new("pkg>=1; os_name=='nt'").unwrap().identity()
+    );
+}
+
+#[test]
+fn test_requirement_merge_rejects_conflicting_urls() {
+    let first = Requirement::new("pkg @ https://a.example/pkg.whl").unwrap();
+    let same = Requirement::new("pkg @ https://a.example/pkg.whl").unwrap();
+    let other = Requirement::new("pkg @ https://b.example/pkg.whl").unwrap();
+    let versions = Requirement::new("pkg>=1").unwrap();
+    assert_eq!(first.clone().merge(&same).unwrap(), first);
+    assert_eq!(first.clone().merge(&other).unwrap_err(), "conflicting URL pins for pkg");
+    assert_eq!(first.merge(&versions).unwrap_err(), "conflicting URL pins for pkg");
+}
+
 #[test]
 fn test_marker_expression_errors_unclosed_string() {
     assert!(MarkerExpr::new("os_name == 'linux").is_err());
@@ -441,6 +561,18 @@ fn test_requirement_empty_marker() {
     assert_eq!(req.to_string(), "pkg>=1");
 }
 
+#[test]
+fn test_normalize_requirement_lowercases_name() {
+    let result = normalize_requirement("My_Package >= 1.0", false, true).unwrap();
+    insta::assert_snapshot!(result, @"my-package>=1");
+}
+
+#[test]
+fn test_normalize_requirement_keeps_name_case() {
+    let result = normalize_requirement("My_Package >= 1.0", false, false).unwrap();
+    insta::assert_snapshot!(result, @"My-Package>=1");
+}
+
 #[test]
 fn test_invalid_epoch_falls_back() {
     let req = Requirement::new("pkg>=abc!1.0").unwrap().normalize(false);
@@ -545,3 +677,127 @@ fn test_is_valid_version_rejects_trailing_separator() {
 fn test_is_valid_version_rejects_wildcard() {
     assert!(!is_valid_version("1.0.*"));
 }
+
+#[test]
+fn test_version_ordering_follows_pep440() {
+    let ordered = [
+        "1.0.dev0",
+        "1.0a1.dev0",
+        "1.0a1",
+        "1.0b2",
+        "1.0rc1",
+        "1.0",
+        "1.0+abc",
+        "1.0+5",
+        "1.0.post1.dev0",
+        "1.0.post1",
+        "1.0-2",
+        "1.0rev3",
+        "1.0.r4",
+        "1.1",
+        "1!0.1",
+    ];
+    for pair in ordered.windows(2) {
+        assert!(
+            Version::new(pair[0]) < Version::new(pair[1]),
+            "{} < {}",
+            pair[0],
+            pair[1]
+        );
+    }
+}
+
+#[test]
+fn test_version_equality_ignores_spelling() {
+    assert_eq!(Version::new("1.0"), Version::new("1.0.0"));
+    assert_eq!(Version::new("1.0alpha1"), Version::new("1.0a1"));
+    assert_eq!(Version::new("1.0-1"), Version::new("1.0.post1"));
+    assert_eq!(Version::new("1.0r1"), Version::new("1.0.post1"));
+    assert_eq!(Version::new("1.0rev"), Version::new("1.0.post0"));
+    assert_eq!(Version::new("1.0-1").to_string(), "1.0.post1");
+    assert_ne!(Version::new("1.0"), Version::new("1.0+local"));
+}
+
+fn contains(spec: &str, version: &str) -> bool {
+    VersionOp::new(spec).unwrap().contains(&Version::new(version))
+}
+
+#[test]
+fn test_version_op_contains() {
+    assert!(contains("<2", "1.9"));
+    assert!(!contains("<2", "2.0rc1"));
+    assert!(contains("<2rc2", "2.0rc1"));
+    assert!(contains(">2", "2.1"));
+    assert!(!contains(">2", "2.post1"));
+    assert!(!contains(">2", "2+local"));
+    assert!(contains("==1.4.*", "1.4.5"));
+    assert!(contains("==1.4.*", "1.4.dev0"));
+    assert!(!contains("==1.4.*", "1.5"));
+    assert!(contains("~=2.2", "2.5"));
+    assert!(!contains("~=2.2", "2.1"));
+    assert!(!contains("~=2.2", "3.0"));
+    assert!(contains("==1.0", "1.0+local"));
+    assert!(!contains("==1.0+local", "1.0"));
+    assert!(!contains("!=1.0", "1.0.0"));
+    assert!(contains(">=1.0", "1.0"));
+    assert!(contains("<=1.0", "1.0+local"));
+}
+
+fn simplify_helper(start: &str) -> Result<String, String> {
+    Requirement::new(start)
+        .unwrap()
+        .normalize(false)
+        .simplify_specifiers()
+        .map(|req| req.to_string())
+}
+
+#[test]
+fn test_simplify_specifiers_drops_redundant() {
+    assert_eq!(simplify_helper("pkg>=1.2,>=1.4").unwrap(), "pkg>=1.4");
+    assert_eq!(simplify_helper("pkg>=2,<3,!=1.5").unwrap(), "pkg>=2,<3");
+    assert_eq!(simplify_helper("pkg>=1,>1,<=3,<3").unwrap(), "pkg>1,<3");
+    assert_eq!(simplify_helper("pkg==1.5,>=1,<2,!=1.7").unwrap(), "pkg==1.5");
+    assert_eq!(simplify_helper("pkg!=2.5,!=2.5,>=2").unwrap(), "pkg!=2.5,>=2");
+    assert_eq!(simplify_helper("pkg~=1.4,~=1.4,!=1.4.*").unwrap(), "pkg~=1.4,!=1.4.*");
+    assert_eq!(simplify_helper("pkg~=1.4,!=2.1,!=1.3,!=1.7").unwrap(), "pkg~=1.4,!=1.7");
+    assert_eq!(
+        simplify_helper("pkg==1.4.*,!=1.5,!=1.4.2").unwrap(),
+        "pkg==1.4.*,!=1.4.2"
+    );
+    assert_eq!(simplify_helper("pkg==1.4.*,<1.4.1").unwrap(), "pkg==1.4.*,<1.4.1");
+    assert_eq!(simplify_helper("pkg~=1.4.2,<1.5").unwrap(), "pkg~=1.4.2,<1.5");
+    assert_eq!(
+        simplify_helper("pkg>=1.2; python_version<'3.11'").unwrap(),
+        "pkg>=1.2; python_version<'3.11'"
+    );
+}
+
+#[test]
+fn test_simplify_specifiers_reports_unsatisfiable() {
+    assert_eq!(simplify_helper("pkg>=3,<2").unwrap_err(), "no version satisfies >=3,<2");
+    assert_eq!(simplify_helper("pkg>1,<=1").unwrap_err(), "no version satisfies >1,<=1");
+    assert_eq!(
+        simplify_helper("pkg==1.5,!=1.5").unwrap_err(),
+        "no version satisfies ==1.5,!=1.5"
+    );
+    assert_eq!(
+        simplify_helper("pkg~=1.4,<1.4").unwrap_err(),
+        "no version satisfies ~=1.4,<1.4"
+    );
+    assert_eq!(
+        simplify_helper("pkg~=1.4,>=2").unwrap_err(),
+        "no version satisfies ~=1.4,>=2"
+    );
+    assert_eq!(
+        simplify_helper("pkg==1.4.*,>=2").unwrap_err(),
+        "no version satisfies ==1.4.*,>=2"
+    );
+    assert_eq!(
+        simplify_helper("pkg==1.4.*,<1.4").unwrap_err(),
+        "no version satisfies ==1.4.*,<1.4"
+    );
+    assert_eq!(
+        simplify_helper("pkg==1.4.*,~=1.5.1").unwrap_err(),
+        "no version satisfies ==1.4.*,~=1.5.1"
+    );
+}
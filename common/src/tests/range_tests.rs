@@ -0,0 +1,135 @@
+use std::cell::RefCell;
+
+use indoc::indoc;
+
+use crate::diagnostic::Diagnostic;
+use crate::formatted::{Formatted, TextEdit};
+use crate::range::{Selection, format_range};
+
+fn formatted(content: String) -> Formatted {
+    Formatted {
+        content,
+        diagnostics: vec![],
+        explanations: vec![],
+    }
+}
+
+/// Formats by spacing out `=` and records what it was handed.
+fn run(source: &str, selection: &Selection) -> (String, String) {
+    let seen = RefCell::new(String::new());
+    let edits = format_range(source, selection, &["tool"], |run| {
+        *seen.borrow_mut() = run.to_string();
+        Ok::<_, ()>(formatted(run.replace(" = ", "=").replace('=', " = ")))
+    })
+    .unwrap()
+    .edits;
+    (TextEdit::apply(source, &edits), seen.into_inner())
+}
+
+#[test]
+fn test_format_range_only_touches_selected_table() {
+    let source = indoc! {r#"
+        [build-system]
+        requires=["a"]
+
+        [project]
+        name="a"
+
+        [tool.ruff]
+        line-length=1
+    "#};
+    let (got, seen) = run(source, &Selection::Lines(5..=5));
+    assert_eq!(seen, "[project]\nname=\"a\"\n\n");
+    assert_eq!(
+        got,
+        "[build-system]\nrequires=[\"a\"]\n\n[project]\nname = \"a\"\n\n[tool.ruff]\nline-length=1\n"
+    );
+}
+
+#[test]
+fn test_format_range_widens_to_whole_group() {
+    let source = indoc! {r#"
+        [project]
+        name="a"
+
+        [tool.ruff]
+        line-length=1
+
+        [project.urls]
+        home="b"
+
+        [tool.mypy]
+        strict=true
+    "#};
+    let (got, seen) = run(source, &Selection::Lines(8..=8));
+    assert!(seen.starts_with("[project]") && seen.ends_with("home=\"b\"\n\n"));
+    assert!(got.contains("line-length = 1") && got.ends_with("[tool.mypy]\nstrict=true\n"));
+}
+
+#[test]
+fn test_format_range_comment_above_header_selects_table() {
+    let source = "a=1\n\n# about b\n[b]\nc=2\n";
+    let (got, seen) = run(source, &Selection::Bytes(5..7));
+    assert_eq!(seen, "# about b\n[b]\nc=2\n");
+    assert_eq!(got, "a=1\n\n# about b\n[b]\nc = 2\n");
+}
+
+#[test]
+fn test_format_range_root_key_values_form_a_section() {
+    let (got, seen) = run("a=1\n[b]\nc=2\n", &Selection::Bytes(0..0));
+    assert_eq!(seen, "a=1\n");
+    assert_eq!(got, "a = 1\n[b]\nc=2\n");
+}
+
+#[test]
+fn test_format_range_unchanged_run_has_no_edits() {
+    let got = format_range("[a]\nb = 1\n", &Selection::Lines(1..=2), &["tool"], |run| {
+        Ok::<_, ()>(formatted(run.to_string()))
+    })
+    .unwrap();
+    assert!(got.edits.is_empty());
+}
+
+#[test]
+fn test_format_range_moves_diagnostics_to_the_document() {
+    let source = "a=1\n\n[b]\nc=\"x\"\n";
+    let got = format_range(source, &Selection::Lines(4..=4), &["tool"], |run| {
+        let offset = run.find('x').unwrap();
+        Ok::<_, ()>(Formatted {
+            diagnostics: vec![Diagnostic {
+                offset,
+                line: 2,
+                column: 4,
+                key_path: String::from("b.c"),
+                value: String::from("x"),
+                message: String::from("bad"),
+            }],
+            ..formatted(run.to_string())
+        })
+    })
+    .unwrap();
+    let diagnostic = &got.diagnostics[0];
+    assert_eq!(diagnostic.offset, source.find('x').unwrap());
+    assert_eq!((diagnostic.line, diagnostic.column), (4, 4));
+}
+
+#[test]
+fn test_format_range_propagates_rejection() {
+    let got = format_range("[a]\nb = 1\n", &Selection::Lines(1..=1), &["tool"], |_| Err("rejected"));
+    assert_eq!(got.unwrap_err(), "rejected");
+}
+
+#[test]
+fn test_text_edit_apply_in_any_order() {
+    let edits = [
+        TextEdit {
+            range: 4..5,
+            new_text: String::from("E"),
+        },
+        TextEdit {
+            range: 0..1,
+            new_text: String::from("A"),
+        },
+    ];
+    assert_eq!(TextEdit::apply("abcdef", &edits), "AbcdEf");
+}
@@ -0,0 +1,138 @@
+use indoc::indoc;
+
+use super::format_toml;
+use crate::rule_file::{InlineTableRule, RuleFile};
+use crate::table::Tables;
+use crate::test_util::parse;
+
+fn fix(rules: &str, start: &str) -> String {
+    let rule_file = RuleFile::parse(rules).unwrap();
+    let root_ast = parse(start);
+    let tables = Tables::from_ast(&root_ast);
+    rule_file.fix(&tables);
+    format_toml(&root_ast, 120)
+}
+
+#[test]
+fn test_rule_file_parse_reads_every_field() {
+    let rules = RuleFile::parse(indoc! {r#"
+        table = "tool.acme"
+        key_order = ["name", "targets"]
+        sort_arrays = ["targets"]
+        dedupe_arrays = ["targets"]
+        case_sensitive = true
+
+        [[inline_tables]]
+        discriminator = "region"
+        key_order = ["region", "replicas"]
+    "#})
+    .unwrap();
+    assert_eq!(rules.table, "tool.acme");
+    assert_eq!(rules.key_order, ["name", "targets"]);
+    assert_eq!(rules.sort_arrays, ["targets"]);
+    assert_eq!(rules.dedupe_arrays, ["targets"]);
+    assert!(rules.case_sensitive);
+    assert_eq!(
+        rules.inline_tables,
+        [InlineTableRule {
+            discriminator: String::from("region"),
+            key_order: vec![String::from("region"), String::from("replicas")],
+        }]
+    );
+    assert_eq!(rules.table_key_order(), ["", "name", "targets"]);
+}
+
+#[test]
+fn test_rule_file_parse_defaults_optional_fields() {
+    let rules = RuleFile::parse(r#"table = "tool.acme""#).unwrap();
+    assert!(rules.key_order.is_empty());
+    assert!(rules.sort_arrays.is_empty());
+    assert!(!rules.case_sensitive);
+    assert!(rules.inline_tables.is_empty());
+}
+
+#[test]
+fn test_rule_file_parse_rejects_bad_input() {
+    assert_eq!(RuleFile::parse("key_order = []").unwrap_err(), "table is required");
+    assert_eq!(
+        RuleFile::parse("table = \"tool.acme\"\nsort = []").unwrap_err(),
+        "unknown key sort"
+    );
+    assert_eq!(
+        RuleFile::parse("table = \"tool.acme\"\nkey_order = 1").unwrap_err(),
+        "key_order must be an array of strings, got 1"
+    );
+    assert_eq!(
+        RuleFile::parse("table = \"tool.acme\"\n[[inline_tables]]\nkey_order = []").unwrap_err(),
+        "inline_tables.discriminator is required"
+    );
+}
+
+#[test]
+fn test_rule_file_fix_orders_keys_and_sorts_arrays() {
+    let rules = indoc! {r#"
+        table = "tool.acme"
+        key_order = ["name", "targets"]
+        sort_arrays = ["targets", "env.paths"]
+        dedupe_arrays = ["targets"]
+    "#};
+    let start = indoc! {r#"
+        [tool.acme]
+        env.paths = ["b", "a"]
+        targets = ["Prod", "dev", "prod"]
+        name = "app"
+        args = ["z", "a"]
+    "#};
+    insta::assert_snapshot!(fix(rules, start), @r#"
+    [tool.acme]
+    name = "app"
+    targets = [ "dev", "Prod" ]
+    args = [ "z", "a" ]
+    env.paths = [ "a", "b" ]
+    "#);
+}
+
+#[test]
+fn test_rule_file_fix_case_sensitive_keeps_case_variants() {
+    let rules = indoc! {r#"
+        table = "tool.acme"
+        sort_arrays = ["targets"]
+        dedupe_arrays = ["targets"]
+        case_sensitive = true
+    "#};
+    let start = indoc! {r#"
+        [tool.acme]
+        targets = ["prod", "Prod", "dev", "prod"]
+    "#};
+    insta::assert_snapshot!(fix(rules, start), @r#"
+    [tool.acme]
+    targets = [ "dev", "Prod", "prod" ]
+    "#);
+}
+
+#[test]
+fn test_rule_file_fix_orders_inline_tables() {
+    let rules = indoc! {r#"
+        table = "tool.acme"
+
+        [[inline_tables]]
+        discriminator = "region"
+        key_order = ["region", "replicas"]
+    "#};
+    let start = indoc! {r#"
+        [tool.acme]
+        deploy = [{ replicas = 2, region = "eu" }]
+        other = { replicas = 2, zone = "a" }
+    "#};
+    insta::assert_snapshot!(fix(rules, start), @r#"
+    [tool.acme]
+    deploy = [ { region = "eu", replicas = 2 } ]
+    other = { replicas = 2, zone = "a" }
+    "#);
+}
+
+#[test]
+fn test_rule_file_fix_ignores_missing_table() {
+    let start = "[tool.other]\nb = 1\na = 2\n";
+    assert_eq!(fix(r#"table = "tool.acme""#, start), start);
+}
@@ -0,0 +1,59 @@
+use crate::rules::Rules;
+
+fn list(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| (*item).to_string()).collect()
+}
+
+#[test]
+fn test_empty_lists_enable_everything() {
+    let rules = Rules::new(&[], &[]);
+    assert!(rules.enabled("uv"));
+    assert!(rules.enabled("project.classifiers.generate"));
+}
+
+#[test]
+fn test_ignore_covers_sub_rules_but_not_prefixes_of_names() {
+    let ignore = list(&["tox"]);
+    let rules = Rules::new(&[], &ignore);
+    assert!(!rules.enabled("tox"));
+    assert!(!rules.enabled("tox.alias-normalization"));
+    assert!(rules.enabled("towncrier"));
+    assert!(rules.enabled("tox-toml-fmt"));
+}
+
+#[test]
+fn test_select_limits_to_listed_rules() {
+    let select = list(&["project", "uv"]);
+    let rules = Rules::new(&select, &[]);
+    assert!(rules.enabled("project.classifiers.generate"));
+    assert!(rules.enabled("uv"));
+    assert!(!rules.enabled("ruff"));
+}
+
+#[test]
+fn test_most_specific_selector_wins() {
+    let (select, ignore) = (list(&["project.classifiers.generate"]), list(&["project"]));
+    let rules = Rules::new(&select, &ignore);
+    assert!(rules.enabled("project.classifiers.generate"));
+    assert!(!rules.enabled("project"));
+
+    let (select, ignore) = (list(&["project"]), list(&["project.classifiers.generate"]));
+    let rules = Rules::new(&select, &ignore);
+    assert!(rules.enabled("project"));
+    assert!(!rules.enabled("project.classifiers.generate"));
+}
+
+#[test]
+fn test_same_selector_in_both_lists_is_ignored() {
+    let names = list(&["uv"]);
+    assert!(!Rules::new(&names, &names).enabled("uv"));
+}
+
+#[test]
+fn test_unknown_names_are_listed() {
+    let known = ["uv", "uv.sort-arrays", "project.classifiers.generate"];
+    let select = list(&["uv", "project"]);
+    let ignore = list(&["uv.sort-array", "project.classifiers", "ruff"]);
+    let rules = Rules::new(&select, &ignore);
+    assert_eq!(rules.unknown(&known), [("ignore", "uv.sort-array"), ("ignore", "ruff")]);
+}
@@ -4,7 +4,7 @@ use super::format_toml;
 use crate::table::{
     InlineTableSchema, Tables, apply_table_formatting, collapse_sub_table, collapse_sub_tables, collect_all_sub_tables,
     expand_sub_table, expand_sub_tables, find_key, for_entries, get_table_name, normalize_table_spacing,
-    reorder_inline_table_keys, reorder_table_keys,
+    reorder_inline_table_keys, reorder_table_keys, sort_inline_table_keys,
 };
 
 fn parse(source: &str) -> tombi_syntax::SyntaxNode {
@@ -2248,7 +2248,7 @@ fn reorder_inline_helper(start: &str, schemas: &[InlineTableSchema]) -> String {
     root_ast.to_string()
 }
 
-const TEST_SCHEMAS: &[InlineTableSchema] = &[
+const TEST_SCHEMAS: &[InlineTableSchema<'static>] = &[
     InlineTableSchema {
         discriminator: "replace",
         key_order: &["replace", "name", "default"],
@@ -2637,6 +2637,43 @@ fn test_reorder_inline_table_keys_array_own_line_comment_issue_387() {
     assert_eq!(again, result);
 }
 
+fn sort_inline_helper(start: &str) -> String {
+    let root_ast = parse(start);
+    let inline_tables: Vec<_> = root_ast
+        .descendants()
+        .filter(|n| n.kind() == tombi_syntax::SyntaxKind::INLINE_TABLE)
+        .collect();
+    for node in inline_tables {
+        sort_inline_table_keys(&node, |key| (key != "first", key.to_string()));
+    }
+    root_ast.to_string()
+}
+
+#[test]
+fn test_sort_inline_table_keys_by_key() {
+    let result = sort_inline_helper(indoc! {r#"
+        val = { b = 1, first = 2, B = 3, "a" = 4 }
+    "#});
+    insta::assert_snapshot!(result, @r#"val = { first = 2, B = 3, "a" = 4, b = 1 }"#);
+}
+
+#[test]
+fn test_sort_inline_table_keys_keeps_comments() {
+    let start = indoc! {r#"
+        val = { b = 1, # about b
+          a = 2 }
+    "#};
+    let result = sort_inline_helper(start);
+    crate::test_util::assert_valid_toml(&result);
+    insta::assert_snapshot!(result, @r#"
+    val = {
+      a = 2,
+      b = 1 # about b
+    }
+    "#);
+    assert_eq!(sort_inline_helper(&result), result);
+}
+
 fn reorder_keys_render(start: &str, table_name: &str, order: &[&str]) -> String {
     let root_ast = parse(start);
     let tables = Tables::from_ast(&root_ast);
@@ -2772,6 +2809,32 @@ fn test_reorder_with_key_order_ranks_sub_tables_by_list() {
     "#);
 }
 
+#[test]
+fn test_reorder_with_key_order_ranks_sub_tables_of_unlisted_table() {
+    let start = indoc! {r#"
+        [tool.acme.zeta]
+        a = 1
+        [tool.acme]
+        b = 2
+        [tool.acme.alpha]
+        c = 3
+    "#};
+    let root_ast = parse(start);
+    let tables = Tables::from_ast(&root_ast);
+    let key_order = |table: &str| (table == "tool.acme").then(|| vec![String::from("zeta"), String::from("alpha")]);
+    tables.reorder_with_key_order(&root_ast, &[""], &["tool"], "\n", "", &key_order);
+    insta::assert_snapshot!(format_toml(&root_ast, 120), @r#"
+    [tool.acme]
+    b = 2
+
+    [tool.acme.zeta]
+    a = 1
+
+    [tool.acme.alpha]
+    c = 3
+    "#);
+}
+
 #[test]
 fn test_collapse_array_of_tables_skips_multi_position_parent() {
     let toml = indoc! {r#"
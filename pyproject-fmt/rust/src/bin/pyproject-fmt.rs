@@ -0,0 +1,7 @@
+use std::process::ExitCode;
+
+use _pyproject_fmt::cli::PyprojectFmt;
+
+fn main() -> ExitCode {
+    ExitCode::from(common::cli::run::<PyprojectFmt>(std::env::args_os()))
+}
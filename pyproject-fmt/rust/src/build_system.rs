@@ -1,12 +1,13 @@
-use common::array::{remove_strings, sort_strings, transform};
-use common::pep508::Requirement;
+use common::array::{remove_strings, sort_strings, transform_with_node};
+use common::diagnostic::Diagnostics;
+use common::pep508::{normalize_requirement, Requirement};
 use common::string::{get_string_token, load_text};
 use common::table::{for_entries, reorder_table_keys, Tables};
 use lexical_sort::{lexical_cmp, natural_lexical_cmp};
 use tombi_syntax::SyntaxKind::{BASIC_STRING, LITERAL_STRING};
 use tombi_syntax::SyntaxNode;
 
-pub fn fix(tables: &Tables, keep_full_version: bool) {
+pub fn fix(tables: &Tables, keep_full_version: bool, lowercase_name: bool, diagnostics: &Diagnostics) {
     let table_element = tables.get("build-system");
     if table_element.is_none() {
         return;
@@ -32,17 +33,24 @@ pub fn fix(tables: &Tables, keep_full_version: bool) {
         );
     for_entries(table, &mut |key, entry| match key.as_str() {
         "requires" => {
-            transform(entry, &|s| {
-                Requirement::new(s).unwrap().normalize(keep_full_version).to_string()
-            });
+            transform_with_node(
+                entry,
+                &|node, s| match normalize_requirement(s, keep_full_version, lowercase_name) {
+                    Ok(normalized) => normalized,
+                    Err(err) => {
+                        diagnostics.report_node(node, "build-system.requires", s, &err);
+                        s.to_string()
+                    }
+                },
+            );
             if drop_wheel && requires_has_setuptools(entry) {
-                remove_strings(entry, |s| {
+                remove_strings(entry, |_, s| {
                     Requirement::new(s).is_ok_and(|r| r.canonical_name() == "wheel" && r.is_name_only())
                 });
             }
             sort_strings::<String, _, _>(
                 entry,
-                |s| Requirement::new(s.as_str()).unwrap().canonical_name(),
+                |s| Requirement::new(s.as_str()).map_or_else(|_| s.to_lowercase(), |r| r.canonical_name()),
                 &|lhs, rhs| natural_lexical_cmp(lhs, rhs),
             );
         }
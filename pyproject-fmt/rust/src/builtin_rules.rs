@@ -0,0 +1,57 @@
+use std::sync::LazyLock;
+
+use common::rule_file::RuleFile;
+use common::table::Tables;
+
+/// Tools handled by a rule file alone, by the rule name that selects them; see `rules/` for the files.
+const RULE_FILES: &[(&str, &str)] = &[
+    ("autopep8", include_str!("../rules/autopep8.toml")),
+    ("black", include_str!("../rules/black.toml")),
+    ("bumpversion", include_str!("../rules/bumpversion.toml")),
+    ("check-manifest", include_str!("../rules/check-manifest.toml")),
+    ("codespell", include_str!("../rules/codespell.toml")),
+    ("commitizen", include_str!("../rules/commitizen.toml")),
+    ("deptry", include_str!("../rules/deptry.toml")),
+    ("djlint", include_str!("../rules/djlint.toml")),
+    ("docformatter", include_str!("../rules/docformatter.toml")),
+    ("interrogate", include_str!("../rules/interrogate.toml")),
+    ("maturin", include_str!("../rules/maturin.toml")),
+    ("pyproject-fmt", include_str!("../rules/pyproject-fmt.toml")),
+    ("pyrefly", include_str!("../rules/pyrefly.toml")),
+    ("pytest", include_str!("../rules/pytest.toml")),
+    ("semantic-release", include_str!("../rules/semantic-release.toml")),
+    ("ty", include_str!("../rules/ty.toml")),
+    ("vulture", include_str!("../rules/vulture.toml")),
+    ("yapf", include_str!("../rules/yapf.toml")),
+];
+
+static PARSED: LazyLock<Vec<(&str, RuleFile)>> = LazyLock::new(|| {
+    RULE_FILES
+        .iter()
+        .map(|(name, text)| {
+            let rules = RuleFile::parse(text).unwrap_or_else(|err| panic!("built-in rule file {name}: {err}"));
+            (*name, rules)
+        })
+        .collect()
+});
+
+/// The built-in rule file selected by `name`, e.g. `black`.
+pub fn get(name: &str) -> &'static RuleFile {
+    PARSED
+        .iter()
+        .find(|(rule, _)| *rule == name)
+        .map(|(_, rules)| rules)
+        .unwrap_or_else(|| panic!("no built-in rule file {name}"))
+}
+
+pub fn fix(name: &str, tables: &mut Tables) {
+    get(name).fix(tables);
+}
+
+/// The key order of the built-in rule file for `table`, e.g. `tool.black`.
+pub fn key_order(table: &str) -> Option<Vec<String>> {
+    PARSED
+        .iter()
+        .find(|(_, rules)| rules.table == table)
+        .map(|(_, rules)| rules.key_order.clone())
+}
@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use clap::ArgAction;
+use common::cli::{
+    config_bool, config_list, config_string, list_argument, CommaList, ConfigValue, FormatArgs, Formatter,
+};
+use common::formatted::{Formatted, FormattedRange};
+use common::range::Selection;
+
+use crate::{format_range, format_toml, Settings};
+
+/// The `pyproject-fmt` binary, driven by [`common::cli::run`].
+pub struct PyprojectFmt;
+
+#[derive(Debug, Clone, clap::Args)]
+#[command(next_help_heading = "formatting behavior")]
+pub struct PyprojectArgs {
+    /// keep full dependency versions - do not remove redundant .0 from versions
+    #[arg(long)]
+    pub keep_full_version: bool,
+    /// do not generate Python version classifiers based on requires-python
+    #[arg(long = "no-generate-python-version-classifiers", action = ArgAction::SetFalse)]
+    pub generate_python_version_classifiers: bool,
+    /// latest Python version the project supports (e.g. 3.14)
+    #[arg(long, value_name = "minor.major", default_value = "3.14", value_parser = version_argument)]
+    pub max_supported_python: (u8, u8),
+    /// comma-separated list of arrays to sort, by full dotted path (e.g. 'tool.acme-deploy.targets')
+    #[arg(long, default_value = "", value_parser = list_argument)]
+    pub sort_arrays: CommaList,
+    /// comma-separated list of rule files for tools without built-in rules
+    #[arg(long, default_value = "", value_parser = list_argument)]
+    pub rule_files: CommaList,
+    /// environments pinned to the start of [tool.tox]'s env_list (comma separated)
+    #[arg(long = "pin-env", default_value = "", value_parser = list_argument)]
+    pub pin_envs: CommaList,
+    /// rewrite [tool.tox]'s legacy_tox_ini string as native [tool.tox.*] tables
+    #[arg(long)]
+    pub migrate_legacy_tox_ini: bool,
+    /// report [tool.tox] undefined environments, mismatched base_python, unknown keys and values of the wrong type
+    #[arg(long)]
+    pub validate_tox: bool,
+    /// add a [tool.tox] env_list entry for each supported Python version it lacks
+    #[arg(long)]
+    pub generate_tox_python_envs: bool,
+    /// collapse redundant version specifiers and report unsatisfiable ones
+    #[arg(long)]
+    pub simplify_specifiers: bool,
+    /// drop requirements, marker clauses and classifiers that requires-python already decides
+    #[arg(long)]
+    pub prune_python_markers: bool,
+    /// rewrite [dependency-groups] keys and include-group references in normalized form
+    #[arg(long)]
+    pub normalize_dependency_group_names: bool,
+    /// Key order per table, e.g. `{ "tool.acme-deploy" = ["name", "targets"] }`; only settable from config.
+    #[arg(skip)]
+    pub key_order: BTreeMap<String, Vec<String>>,
+}
+
+fn version_argument(got: &str) -> Result<(u8, u8), String> {
+    let Some((major, minor)) = got.split_once('.').filter(|(_, minor)| !minor.contains('.')) else {
+        return Err(format!("invalid version: {got}, must be e.g. 3.14"));
+    };
+    match (major.parse(), minor.parse()) {
+        (Ok(major), Ok(minor)) => Ok((major, minor)),
+        (Err(err), _) | (_, Err(err)) => Err(format!("invalid version: {got} due {err}, must be e.g. 3.14")),
+    }
+}
+
+fn key_order_argument(key: &str, value: &ConfigValue) -> Result<BTreeMap<String, Vec<String>>, String> {
+    let Some(tables) = value.as_table() else {
+        return Err(format!("{key} must be a table of key lists, got {value}"));
+    };
+    tables
+        .iter()
+        .map(|(table, keys)| Ok((table.clone(), config_list(&format!("{key}.{table}"), keys)?)))
+        .collect()
+}
+
+impl Formatter for PyprojectFmt {
+    type Args = PyprojectArgs;
+    const PROG: &'static str = "pyproject-fmt";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+    const FILENAME: &'static str = "pyproject.toml";
+    const OVERRIDE_SECTION: &'static [&'static str] = &["tool", "pyproject-fmt"];
+
+    fn apply_config(
+        args: &mut PyprojectArgs,
+        key: &str,
+        value: &ConfigValue,
+        config_dir: &Path,
+    ) -> Result<bool, String> {
+        match key {
+            "keep_full_version" => args.keep_full_version = config_bool(key, value)?,
+            "generate_python_version_classifiers" => {
+                args.generate_python_version_classifiers = config_bool(key, value)?;
+            }
+            "max_supported_python" => {
+                args.max_supported_python = version_argument(&config_string(key, value)?)?;
+            }
+            "sort_arrays" => args.sort_arrays = config_list(key, value)?,
+            "rule_files" => {
+                args.rule_files = config_list(key, value)?
+                    .iter()
+                    .map(|path| config_dir.join(path).to_string_lossy().into_owned())
+                    .collect();
+            }
+            "pin_envs" => args.pin_envs = config_list(key, value)?,
+            "migrate_legacy_tox_ini" => args.migrate_legacy_tox_ini = config_bool(key, value)?,
+            "validate_tox" => args.validate_tox = config_bool(key, value)?,
+            "generate_tox_python_envs" => args.generate_tox_python_envs = config_bool(key, value)?,
+            "simplify_specifiers" => args.simplify_specifiers = config_bool(key, value)?,
+            "prune_python_markers" => args.prune_python_markers = config_bool(key, value)?,
+            "normalize_dependency_group_names" => args.normalize_dependency_group_names = config_bool(key, value)?,
+            "key_order" => args.key_order = key_order_argument(key, value)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn format(text: &str, shared: &FormatArgs, args: &PyprojectArgs) -> Result<Formatted, String> {
+        format_toml(text, &settings(shared, args)?)
+    }
+
+    fn format_range(
+        text: &str,
+        selection: &Selection,
+        shared: &FormatArgs,
+        args: &PyprojectArgs,
+    ) -> Result<FormattedRange, String> {
+        format_range(text, selection, &settings(shared, args)?)
+    }
+}
+
+fn settings(shared: &FormatArgs, args: &PyprojectArgs) -> Result<Settings, String> {
+    let builder = args
+        .key_order
+        .iter()
+        .fold(Settings::builder(), |builder, (table, keys)| {
+            builder.key_order(table, keys)
+        });
+    Ok(builder
+        .column_width(shared.column_width)
+        .indent(shared.indent)
+        .keep_full_version(args.keep_full_version)
+        .max_supported_python(args.max_supported_python)
+        .generate_python_version_classifiers(args.generate_python_version_classifiers)
+        .table_format(shared.table_format.as_str())
+        .sub_table_spacing(shared.sub_table_spacing.as_str())
+        .separate_root_table(shared.separate_root_table.as_str())
+        .expand_tables(&shared.expand_tables)
+        .collapse_tables(&shared.collapse_tables)
+        .skip_wrap_for_keys(&shared.skip_wrap_for_keys)
+        .sort_arrays(&args.sort_arrays)
+        .rule_files(&args.rule_files)?
+        .pin_envs(&args.pin_envs)
+        .migrate_legacy_tox_ini(args.migrate_legacy_tox_ini)
+        .validate_tox(args.validate_tox)
+        .generate_tox_python_envs(args.generate_tox_python_envs)
+        .simplify_specifiers(args.simplify_specifiers)
+        .prune_python_markers(args.prune_python_markers)
+        .normalize_dependency_group_names(args.normalize_dependency_group_names)
+        .select(&shared.select)
+        .ignore(&shared.ignore)
+        .explain(shared.explain)
+        .build())
+}
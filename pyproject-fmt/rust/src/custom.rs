@@ -0,0 +1,47 @@
+use std::collections::BTreeMap;
+
+use common::array::sort_strings;
+use common::table::{for_entries, reorder_table_keys, Tables};
+use lexical_sort::natural_lexical_cmp;
+
+/// Applies the `key_order` from `[tool.pyproject-fmt]`, meant for tables no built-in pass knows. It runs after the
+/// built-in passes, so a configured order also overrides theirs.
+pub fn fix_key_order(tables: &Tables, key_order: &BTreeMap<String, Vec<String>>) {
+    for (name, keys) in key_order {
+        let Some(table_refs) = tables.get(name) else {
+            continue;
+        };
+        let order: Vec<&str> = std::iter::once("").chain(keys.iter().map(String::as_str)).collect();
+        for table_ref in table_refs {
+            reorder_table_keys(&mut table_ref.borrow_mut(), &order);
+        }
+    }
+}
+
+/// Sorts each array named by its full dotted path, whether it sits in its own table or under a dotted key.
+pub fn fix_sort_arrays(tables: &Tables, sort_arrays: &[String]) {
+    for (name, positions) in &tables.header_to_pos {
+        let keys: Vec<&str> = sort_arrays
+            .iter()
+            .filter_map(|path| {
+                if name.is_empty() {
+                    Some(path.as_str())
+                } else {
+                    path.strip_prefix(name.as_str())?.strip_prefix('.')
+                }
+            })
+            .collect();
+        if keys.is_empty() {
+            continue;
+        }
+        for &position in positions {
+            for_entries(&tables.table_set[position].borrow(), &mut |key, entry| {
+                if keys.contains(&key.as_str()) {
+                    sort_strings::<String, _, _>(entry, |s| s.to_lowercase(), &|lhs, rhs| {
+                        natural_lexical_cmp(lhs, rhs)
+                    });
+                }
+            });
+        }
+    }
+}
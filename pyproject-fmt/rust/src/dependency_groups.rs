@@ -1,14 +1,30 @@
 use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::LazyLock;
 
 use lexical_sort::natural_lexical_cmp;
-use tombi_syntax::SyntaxKind::{BASIC_STRING, INLINE_TABLE};
+use regex::Regex;
+use tombi_syntax::SyntaxKind::{BASIC_STRING, INLINE_TABLE, KEYS, KEY_VALUE};
+use tombi_syntax::{SyntaxElement, SyntaxNode};
 
-use common::array::{sort, transform};
-use common::pep508::Requirement;
-use common::string::{get_string_token, load_text};
+use common::array::{sort, transform_with_node};
+use common::create::make_key;
+use common::diagnostic::Diagnostics;
+use common::explain::{record, with_rule};
+use common::pep508::{normalize_requirement, Requirement};
+use common::string::{get_string_token, load_text, update_content};
 use common::table::{collapse_sub_tables, find_key, for_entries, reorder_table_keys, Tables};
 
-pub fn fix(tables: &mut Tables, keep_full_version: bool) {
+use crate::project::merge_requirements;
+
+pub fn fix(
+    tables: &mut Tables,
+    keep_full_version: bool,
+    lowercase_name: bool,
+    merge: bool,
+    normalize_names: bool,
+    diagnostics: &Diagnostics,
+) {
     collapse_sub_tables(tables, "dependency-groups");
     let table_element = tables.get("dependency-groups");
     if table_element.is_none() {
@@ -16,22 +32,32 @@ pub fn fix(tables: &mut Tables, keep_full_version: bool) {
     }
 
     let table = &mut table_element.unwrap().first().unwrap().borrow_mut();
-    for_entries(table, &mut |_key, entry| {
-        transform(entry, &|s| {
-            Requirement::new(s).unwrap().normalize(keep_full_version).to_string()
-        });
+    for_entries(table, &mut |key, entry| {
+        let key_path = format!("dependency-groups.{key}");
+        transform_with_node(
+            entry,
+            &|node, s| match normalize_requirement(s, keep_full_version, lowercase_name) {
+                Ok(normalized) => normalized,
+                Err(err) => {
+                    diagnostics.report_node(node, &key_path, s, &err);
+                    s.to_string()
+                }
+            },
+        );
+        if merge {
+            merge_requirements(entry, &key_path, diagnostics);
+        }
 
         sort::<(u8, String, String), _, _>(
             entry,
             |node| match node.kind() {
                 BASIC_STRING => get_string_token(node).map(|token| {
                     let val = load_text(token.text(), BASIC_STRING);
-                    let package_name = Requirement::new(val.as_str()).unwrap().canonical_name();
+                    let package_name =
+                        Requirement::new(val.as_str()).map_or_else(|_| val.to_lowercase(), |r| r.canonical_name());
                     (0, package_name, val)
                 }),
-                INLINE_TABLE => find_key(node, "include-group").and_then(|n| {
-                    get_string_token(&n).map(|token| (1, load_text(token.text(), BASIC_STRING), String::new()))
-                }),
+                INLINE_TABLE => include_group(node).map(|name| (1, name, String::new())),
                 _ => None,
             },
             &|lhs, rhs| {
@@ -47,5 +73,153 @@ pub fn fix(tables: &mut Tables, keep_full_version: bool) {
         );
     });
 
+    let groups = collect_groups(table);
+    check_groups(&groups, diagnostics);
+    if normalize_names {
+        normalize_group_names(table, &groups);
+    }
+
     reorder_table_keys(table, &["", "dev", "test", "type", "docs"]);
 }
+
+/// Groups by their normalized name: the key as written and the names each includes, in order. Keys that normalize to
+/// the same name share an entry, listed in file order.
+type Groups = BTreeMap<String, (Vec<String>, Vec<String>)>;
+
+/// PEP 735 compares group names the way PEP 503 compares package names.
+fn canonical_group_name(name: &str) -> String {
+    static RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[-_.]+").unwrap());
+    RE.replace_all(&name.to_lowercase(), "-").to_string()
+}
+
+fn include_group(node: &SyntaxNode) -> Option<String> {
+    let value = find_key(node, "include-group")?;
+    get_string_token(&value).map(|token| load_text(token.text(), BASIC_STRING))
+}
+
+fn collect_groups(table: &[SyntaxElement]) -> Groups {
+    let mut groups = Groups::new();
+    for_entries(table, &mut |key, entry| {
+        let key = key.trim_matches(['"', '\'']).to_string();
+        let includes = entry
+            .children()
+            .filter(|child| child.kind() == INLINE_TABLE)
+            .filter_map(|child| include_group(&child));
+        let (keys, all_includes) = groups.entry(canonical_group_name(&key)).or_default();
+        keys.push(key);
+        all_includes.extend(includes);
+    });
+    groups
+}
+
+/// Reports keys that name the same group, `include-group` references to groups that do not exist, and groups that
+/// end up including themselves.
+fn check_groups(groups: &Groups, diagnostics: &Diagnostics) {
+    for (keys, includes) in groups.values() {
+        for key in &keys[1..] {
+            diagnostics.report(
+                &format!("dependency-groups.{key}"),
+                key,
+                &format!("{key} and {} name the same group", keys[0]),
+            );
+        }
+        for include in includes {
+            if !groups.contains_key(&canonical_group_name(include)) {
+                diagnostics.report(
+                    &format!("dependency-groups.{}", keys[0]),
+                    include,
+                    &format!("includes unknown group {include}"),
+                );
+            }
+        }
+    }
+    let mut done = HashMap::new();
+    for name in groups.keys() {
+        find_cycles(name, groups, &mut done, &mut Vec::new(), diagnostics);
+    }
+}
+
+/// Depth-first walk over the includes; `done` maps a group to whether its walk finished, so each include that closes
+/// a cycle is reported once.
+fn find_cycles<'a>(
+    name: &'a str,
+    groups: &'a Groups,
+    done: &mut HashMap<&'a str, bool>,
+    path: &mut Vec<&'a str>,
+    diagnostics: &Diagnostics,
+) {
+    if done.contains_key(name) {
+        return;
+    }
+    done.insert(name, false);
+    path.push(name);
+    let (keys, includes) = &groups[name];
+    for include in includes {
+        let Some((target, _)) = groups.get_key_value(&canonical_group_name(include)) else {
+            continue;
+        };
+        match done.get(target.as_str()) {
+            Some(false) => {
+                let from = path.iter().position(|on_path| *on_path == target.as_str()).unwrap_or(0);
+                let cycle: Vec<&str> = path[from..]
+                    .iter()
+                    .copied()
+                    .chain([target.as_str()])
+                    .map(|group| groups[group].0[0].as_str())
+                    .collect();
+                diagnostics.report(
+                    &format!("dependency-groups.{}", keys[0]),
+                    include,
+                    &format!("include cycle {}", cycle.join(" -> ")),
+                );
+            }
+            Some(true) => {}
+            None => find_cycles(target, groups, done, path, diagnostics),
+        }
+    }
+    path.pop();
+    done.insert(name, true);
+}
+
+/// Rewrites group keys and the `include-group` references to existing groups in their normalized form, the way
+/// `optional-dependencies` extras are. Keys that share a normalized name are left alone, renaming would clash.
+fn normalize_group_names(table: &[SyntaxElement], groups: &Groups) {
+    let unique = |name: &str| {
+        groups
+            .get(&canonical_group_name(name))
+            .is_some_and(|(keys, _)| keys.len() == 1)
+    };
+    for element in table {
+        if element.kind() != KEY_VALUE {
+            continue;
+        }
+        let entry_node = element.as_node().unwrap();
+        for child in entry_node.children_with_tokens() {
+            if child.kind() == KEYS {
+                let key_node = child.as_node().unwrap();
+                let key_text = key_node.text().to_string().trim().trim_matches(['"', '\'']).to_string();
+                let normalized = canonical_group_name(&key_text);
+                if key_text != normalized && unique(&key_text) {
+                    record(key_node, "dependency-groups/normalize-name", &key_text, &normalized);
+                    let new_key = make_key(&normalized);
+                    let count = key_node.children_with_tokens().count();
+                    key_node.splice_children(0..count, new_key.as_node().unwrap().children_with_tokens().collect());
+                }
+            } else if let Some(array) = child.as_node() {
+                for inline_table in array.children().filter(|node| node.kind() == INLINE_TABLE) {
+                    if let Some(value) = find_key(&inline_table, "include-group") {
+                        with_rule("dependency-groups/normalize-name", || {
+                            update_content(&value, |name| {
+                                if unique(name) {
+                                    canonical_group_name(name)
+                                } else {
+                                    name.to_string()
+                                }
+                            });
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,59 +1,65 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
 use std::string::String;
 
+#[cfg(feature = "python")]
 use pyo3::exceptions::PyValueError;
+#[cfg(feature = "python")]
 use pyo3::prelude::{PyModule, PyModuleMethods};
+#[cfg(feature = "python")]
 use pyo3::{pyclass, pyfunction, pymethods, pymodule, wrap_pyfunction, Bound, PyResult, Python};
 
 use crate::global::reorder_tables;
 use common::array::ensure_all_arrays_multiline;
+#[cfg(feature = "python")]
+use common::diagnostic::Diagnostic;
+use common::diagnostic::Diagnostics;
+use common::explain::record_edits;
+#[cfg(feature = "python")]
+use common::explain::Explanation;
+use common::formatted::{Formatted, FormattedRange};
+use common::range::Selection;
+#[cfg(feature = "python")]
+use common::rule_file::InlineTableRule;
+use common::rule_file::RuleFile;
+use common::rules::Rules;
 use common::table::{apply_table_formatting, Tables};
 use tombi_config::TomlVersion;
 
 mod build_system;
 mod dependency_groups;
 mod project;
+mod python_markers;
+mod specifiers;
 
-mod autopep8;
 mod bandit;
-mod black;
-mod bumpversion;
-mod check_manifest;
+mod builtin_rules;
 mod cibuildwheel;
-mod codespell;
-mod commitizen;
+#[cfg(any(test, feature = "cli"))]
+pub mod cli;
 mod coverage;
-mod deptry;
-mod djlint;
-mod docformatter;
+mod custom;
 mod global;
 mod hatch;
-mod interrogate;
 mod isort;
-mod maturin;
 mod mypy;
 mod pdm;
 mod pixi;
 mod poetry;
 mod pylint;
-mod pyproject_fmt;
-mod pyrefly;
 mod pyright;
-mod pytest;
 mod ruff;
 mod scikit_build;
-mod semantic_release;
 mod setuptools;
 #[cfg(test)]
 mod tests;
 mod towncrier;
 mod tox;
-mod ty;
 mod uv;
-mod vulture;
-mod yapf;
 
-#[pyclass(frozen, get_all)]
+/// Options for [`format_toml`]. Build one with [`Settings::builder`]; [`Settings::default`] matches the
+/// `pyproject-fmt` command line defaults.
+#[cfg_attr(feature = "python", pyclass(frozen, get_all))]
 pub struct Settings {
     column_width: usize,
     indent: usize,
@@ -67,13 +73,27 @@ pub struct Settings {
     expand_tables: Vec<String>,
     collapse_tables: Vec<String>,
     skip_wrap_for_keys: Vec<String>,
+    key_order: BTreeMap<String, Vec<String>>,
+    sort_arrays: Vec<String>,
+    rule_files: Vec<RuleFile>,
+    pin_envs: Vec<String>,
+    migrate_legacy_tox_ini: bool,
+    validate_tox: bool,
+    generate_tox_python_envs: bool,
+    simplify_specifiers: bool,
+    prune_python_markers: bool,
+    normalize_dependency_group_names: bool,
+    select: Vec<String>,
+    ignore: Vec<String>,
+    explain: bool,
 }
 
+#[cfg(feature = "python")]
 #[pymethods]
 impl Settings {
     #[new]
     #[allow(clippy::too_many_arguments)]
-    #[pyo3(signature = (*, column_width, indent, keep_full_version, max_supported_python, min_supported_python, generate_python_version_classifiers, table_format, sub_table_spacing, separate_root_table, expand_tables, collapse_tables, skip_wrap_for_keys))]
+    #[pyo3(signature = (*, column_width, indent, keep_full_version, max_supported_python, min_supported_python, generate_python_version_classifiers, table_format, sub_table_spacing, separate_root_table, expand_tables, collapse_tables, skip_wrap_for_keys, key_order = BTreeMap::new(), sort_arrays = vec![], rule_files = vec![], pin_envs = vec![], migrate_legacy_tox_ini = false, validate_tox = false, generate_tox_python_envs = false, simplify_specifiers = false, prune_python_markers = false, normalize_dependency_group_names = false, select = vec![], ignore = vec![], explain = false))]
     fn new(
         column_width: usize,
         indent: usize,
@@ -87,8 +107,21 @@ impl Settings {
         expand_tables: Vec<String>,
         collapse_tables: Vec<String>,
         skip_wrap_for_keys: Vec<String>,
-    ) -> Self {
-        Self {
+        key_order: BTreeMap<String, Vec<String>>,
+        sort_arrays: Vec<String>,
+        rule_files: Vec<String>,
+        pin_envs: Vec<String>,
+        migrate_legacy_tox_ini: bool,
+        validate_tox: bool,
+        generate_tox_python_envs: bool,
+        simplify_specifiers: bool,
+        prune_python_markers: bool,
+        normalize_dependency_group_names: bool,
+        select: Vec<String>,
+        ignore: Vec<String>,
+        explain: bool,
+    ) -> PyResult<Self> {
+        Ok(Self {
             column_width,
             indent,
             keep_full_version,
@@ -101,10 +134,234 @@ impl Settings {
             expand_tables,
             collapse_tables,
             skip_wrap_for_keys,
+            key_order,
+            sort_arrays,
+            rule_files: load_rule_files(&rule_files).map_err(PyValueError::new_err)?,
+            pin_envs,
+            migrate_legacy_tox_ini,
+            validate_tox,
+            generate_tox_python_envs,
+            simplify_specifiers,
+            prune_python_markers,
+            normalize_dependency_group_names,
+            select,
+            ignore,
+            explain,
+        })
+    }
+}
+
+impl Settings {
+    /// Starts from the command line defaults, see [`SettingsBuilder`].
+    pub fn builder() -> SettingsBuilder {
+        SettingsBuilder::default()
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            column_width: 120,
+            indent: 2,
+            keep_full_version: false,
+            max_supported_python: (3, 14),
+            min_supported_python: (3, 10),
+            generate_python_version_classifiers: true,
+            table_format: String::from("short"),
+            sub_table_spacing: String::new(),
+            separate_root_table: String::from("\n"),
+            expand_tables: vec![],
+            collapse_tables: vec![],
+            skip_wrap_for_keys: vec![],
+            key_order: BTreeMap::new(),
+            sort_arrays: vec![],
+            rule_files: vec![],
+            pin_envs: vec![],
+            migrate_legacy_tox_ini: false,
+            validate_tox: false,
+            generate_tox_python_envs: false,
+            simplify_specifiers: false,
+            prune_python_markers: false,
+            normalize_dependency_group_names: false,
+            select: vec![],
+            ignore: vec![],
+            explain: false,
         }
     }
 }
 
+/// Builds [`Settings`], starting from the `pyproject-fmt` command line defaults.
+#[derive(Default)]
+#[must_use]
+pub struct SettingsBuilder {
+    settings: Settings,
+}
+
+impl SettingsBuilder {
+    /// Column after which arrays and inline tables are split over lines and long strings are wrapped.
+    pub const fn column_width(mut self, column_width: usize) -> Self {
+        self.settings.column_width = column_width;
+        self
+    }
+
+    /// Spaces per indentation level.
+    pub const fn indent(mut self, indent: usize) -> Self {
+        self.settings.indent = indent;
+        self
+    }
+
+    /// Keep redundant `.0` suffixes in dependency versions.
+    pub const fn keep_full_version(mut self, keep_full_version: bool) -> Self {
+        self.settings.keep_full_version = keep_full_version;
+        self
+    }
+
+    /// Latest Python version the project supports, as `(major, minor)`.
+    pub const fn max_supported_python(mut self, version: (u8, u8)) -> Self {
+        self.settings.max_supported_python = version;
+        self
+    }
+
+    /// Oldest Python version assumed when `requires-python` is not set, as `(major, minor)`.
+    pub const fn min_supported_python(mut self, version: (u8, u8)) -> Self {
+        self.settings.min_supported_python = version;
+        self
+    }
+
+    /// Generate `Programming Language :: Python` classifiers from `requires-python`.
+    pub const fn generate_python_version_classifiers(mut self, generate: bool) -> Self {
+        self.settings.generate_python_version_classifiers = generate;
+        self
+    }
+
+    /// `"short"` collapses sub-tables into dotted keys, `"long"` expands them to `[table.subtable]` headers.
+    pub fn table_format(mut self, table_format: impl Into<String>) -> Self {
+        self.settings.table_format = table_format.into();
+        self
+    }
+
+    /// Extra newlines between sub-tables in the same group.
+    pub fn sub_table_spacing(mut self, spacing: impl Into<String>) -> Self {
+        self.settings.sub_table_spacing = spacing.into();
+        self
+    }
+
+    /// Extra newlines between root table groups.
+    pub fn separate_root_table(mut self, spacing: impl Into<String>) -> Self {
+        self.settings.separate_root_table = spacing.into();
+        self
+    }
+
+    /// Tables always expanded, whatever the table format.
+    pub fn expand_tables(mut self, tables: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.settings.expand_tables = tables.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Tables always collapsed, whatever the table format or expanded tables.
+    pub fn collapse_tables(mut self, tables: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.settings.collapse_tables = tables.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Key patterns whose strings are never wrapped, with wildcards like `*.parse`.
+    pub fn skip_wrap_for_keys(mut self, keys: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.settings.skip_wrap_for_keys = keys.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Key order for `table`, e.g. `tool.acme-deploy`, used for its keys and for its sub-tables when expanded.
+    /// Unlisted keys follow alphabetically.
+    pub fn key_order(mut self, table: impl Into<String>, keys: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.settings
+            .key_order
+            .insert(table.into(), keys.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Arrays of strings to sort, by full dotted path such as `tool.acme-deploy.targets`.
+    pub fn sort_arrays(mut self, paths: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.settings.sort_arrays = paths.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Loads the rule files (see [`common::rule_file`]) at `paths` for tools without built-in rules, applied after the
+    /// built-in ones. Relative paths resolve against the working directory.
+    ///
+    /// # Errors
+    ///
+    /// Will return a message naming the first file that can't be read or isn't a valid rule file.
+    pub fn rule_files(mut self, paths: impl IntoIterator<Item = impl AsRef<str>>) -> Result<Self, String> {
+        self.settings.rule_files = load_rule_files(paths)?;
+        Ok(self)
+    }
+
+    /// Environments pinned to the start of `[tool.tox]`'s `env_list`, in this order.
+    pub fn pin_envs(mut self, envs: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.settings.pin_envs = envs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Rewrite `[tool.tox]`'s `legacy_tox_ini` string as native `[tool.tox.*]` tables.
+    pub const fn migrate_legacy_tox_ini(mut self, value: bool) -> Self {
+        self.settings.migrate_legacy_tox_ini = value;
+        self
+    }
+
+    /// Report `[tool.tox]` references to undefined environments, unknown keys and values of the wrong type.
+    pub const fn validate_tox(mut self, value: bool) -> Self {
+        self.settings.validate_tox = value;
+        self
+    }
+
+    /// Add a `[tool.tox]` `env_list` entry for each supported Python version it lacks.
+    pub const fn generate_tox_python_envs(mut self, value: bool) -> Self {
+        self.settings.generate_tox_python_envs = value;
+        self
+    }
+
+    /// Collapse redundant version specifiers, `>=1.2,>=1.4` to `>=1.4`, and report unsatisfiable ones.
+    pub const fn simplify_specifiers(mut self, value: bool) -> Self {
+        self.settings.simplify_specifiers = value;
+        self
+    }
+
+    /// Drop requirements, marker clauses and classifiers that `requires-python` already decides.
+    pub const fn prune_python_markers(mut self, value: bool) -> Self {
+        self.settings.prune_python_markers = value;
+        self
+    }
+
+    /// Rewrite `[dependency-groups]` keys and their `include-group` references in normalized form, `Dev_Tools` to
+    /// `dev-tools`.
+    pub const fn normalize_dependency_group_names(mut self, value: bool) -> Self {
+        self.settings.normalize_dependency_group_names = value;
+        self
+    }
+
+    /// Rules to run, Ruff-style: `["project", "uv"]` runs only those passes. Empty runs every rule.
+    pub fn select(mut self, rules: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.settings.select = rules.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Rules to skip, e.g. `tox.alias-normalization`; the most specific of `select` and `ignore` wins.
+    pub fn ignore(mut self, rules: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.settings.ignore = rules.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Record the rule behind each change in [`Formatted::explanations`].
+    pub const fn explain(mut self, explain: bool) -> Self {
+        self.settings.explain = explain;
+        self
+    }
+
+    pub fn build(self) -> Settings {
+        self.settings
+    }
+}
+
 pub struct TableFormatConfig {
     pub default_collapse: bool,
     pub expand_tables: HashSet<String>,
@@ -149,24 +406,138 @@ async fn format_with_tombi(content: &str, column_width: usize, indent: usize) ->
     formatter.format(content).await.unwrap_or_else(|_| content.to_string())
 }
 
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(name = "format_toml")]
-fn format_toml_py(py: Python<'_>, content: &str, opt: &Settings) -> PyResult<String> {
+fn format_toml_py(py: Python<'_>, content: &str, opt: &Settings) -> PyResult<Formatted> {
     py.detach(|| format_toml(content, opt)).map_err(PyValueError::new_err)
 }
 
+/// Entries the formatter cannot parse, such as an invalid PEP 508 requirement, are left as written and reported in
+/// [`Formatted::diagnostics`] while the rest of the file is still formatted.
+///
 /// # Errors
 ///
 /// Will return a message describing why the content was rejected, e.g. an invalid `project.version`.
-pub fn format_toml(content: &str, opt: &Settings) -> Result<String, String> {
-    common::disabled::try_with_disabled_keys(content, |content| format_core(content, opt))
+pub fn format_toml(content: &str, opt: &Settings) -> Result<Formatted, String> {
+    let diagnostics = Diagnostics::default();
+    let (formatted, explanations) = common::explain::collect(opt.explain, || {
+        common::disabled::try_with_disabled_keys(content, |content| format_core(content, opt, &diagnostics))
+    });
+    Ok(Formatted {
+        content: formatted?,
+        diagnostics: diagnostics.locate(content),
+        explanations,
+    })
+}
+
+/// Formats only the tables `selection` overlaps, widened to whole table groups (see [`common::range`]), and returns
+/// the edits to apply with the diagnostics of those tables; the rest of the document is left as written.
+///
+/// # Errors
+///
+/// Will return a message describing why the affected tables were rejected, e.g. an invalid `project.version`.
+pub fn format_range(content: &str, selection: &Selection, opt: &Settings) -> Result<FormattedRange, String> {
+    common::range::format_range(content, selection, &["tool"], |content| format_toml(content, opt))
 }
 
-fn format_core(content: &str, opt: &Settings) -> Result<String, String> {
+fn load_rule_files(paths: impl IntoIterator<Item = impl AsRef<str>>) -> Result<Vec<RuleFile>, String> {
+    paths
+        .into_iter()
+        .map(|path| RuleFile::load(Path::new(path.as_ref())))
+        .collect()
+}
+
+/// Every rule `select` and `ignore` can name; an entry covering none of them is reported.
+const RULES: &[&str] = &[
+    "autopep8",
+    "bandit",
+    "black",
+    "build-system",
+    "bumpversion",
+    "check-manifest",
+    "cibuildwheel",
+    "codespell",
+    "commitizen",
+    "coverage",
+    "custom.key-order",
+    "custom.rule-files",
+    "custom.sort-arrays",
+    "dependency-groups",
+    "deptry",
+    "djlint",
+    "docformatter",
+    "hatch",
+    "interrogate",
+    "isort",
+    "maturin",
+    "mypy",
+    "pdm",
+    "pep508.lowercase-name",
+    "pep508.merge-requirements",
+    "pixi",
+    "poetry",
+    "project",
+    "project.classifiers.generate",
+    "pylint",
+    "pyproject-fmt",
+    "pyrefly",
+    "pyright",
+    "pytest",
+    "requirements.python-markers",
+    "requirements.simplify",
+    "ruff",
+    "scikit-build",
+    "semantic-release",
+    "setuptools",
+    "strings.wrap",
+    "tables.layout",
+    "tables.reorder",
+    "towncrier",
+    "tox",
+    "tox.alias-normalization",
+    "tox.base-python",
+    "tox.env-list-sort",
+    "tox.envs",
+    "tox.legacy-ini",
+    "tox.python-envs",
+    "tox.references",
+    "tox.root",
+    "tox.schema",
+    "ty",
+    "uv",
+    "uv.sort-arrays",
+    "vulture",
+    "yapf",
+];
+
+fn format_core(content: &str, opt: &Settings, diagnostics: &Diagnostics) -> Result<String, String> {
     let root_ast = parse(content);
     common::string::normalize_key_quotes(&root_ast);
     let mut tables = Tables::from_ast(&root_ast);
     let table_config = TableFormatConfig::from_settings(opt);
+    let rules = Rules::new(&opt.select, &opt.ignore);
+    for (list, name) in rules.unknown(RULES) {
+        diagnostics.report(
+            &format!("tool.pyproject-fmt.{list}"),
+            name,
+            &format!("unknown rule {name}"),
+        );
+    }
+    let run = |rule: &str, tables: &mut Tables, fix: &dyn Fn(&mut Tables)| {
+        if rules.enabled(rule) {
+            fix(tables);
+        }
+    };
+    let builtin = |rule: &str, tables: &mut Tables| run(rule, tables, &|tables| builtin_rules::fix(rule, tables));
+    let lowercase_name = rules.enabled("pep508.lowercase-name");
+    let merge = rules.enabled("pep508.merge-requirements");
+
+    if opt.migrate_legacy_tox_ini {
+        run("tox.legacy-ini", &mut tables, &|tables| {
+            tox::migrate_legacy_ini(tables, diagnostics)
+        });
+    }
 
     let mut prefixes: Vec<String> = vec![String::from("build-system"), String::from("project")];
     for key in tables.header_to_pos.keys() {
@@ -178,68 +549,141 @@ fn format_core(content: &str, opt: &Settings) -> Result<String, String> {
         }
     }
     let prefix_refs: Vec<&str> = prefixes.iter().map(|s| s.as_str()).collect();
-    apply_table_formatting(
-        &mut tables,
-        |name| table_config.should_collapse(name),
-        &prefix_refs,
-        opt.column_width,
-    );
+    run("tables.layout", &mut tables, &|tables| {
+        apply_table_formatting(
+            tables,
+            |name| table_config.should_collapse(name),
+            &prefix_refs,
+            opt.column_width,
+        );
+    });
 
     let indent_string = " ".repeat(opt.indent);
-    build_system::fix(&tables, opt.keep_full_version);
-    project::fix(
-        &mut tables,
-        opt.keep_full_version,
-        opt.max_supported_python,
-        opt.min_supported_python,
-        opt.generate_python_version_classifiers,
-        &table_config,
-    )?;
-    dependency_groups::fix(&mut tables, opt.keep_full_version);
-    ruff::fix(&mut tables);
-    uv::fix(&mut tables);
-    pixi::fix(&mut tables);
-    commitizen::fix(&mut tables);
-    poetry::fix(&mut tables);
-    mypy::fix(&mut tables);
-    setuptools::fix(&mut tables);
-    pytest::fix(&mut tables);
-    black::fix(&mut tables);
-    hatch::fix(&mut tables);
-    isort::fix(&mut tables);
-    pyright::fix(&mut tables);
-    pdm::fix(&mut tables);
-    cibuildwheel::fix(&mut tables);
-    tox::fix(&mut tables);
-    bandit::fix(&mut tables);
-    maturin::fix(&mut tables);
-    codespell::fix(&mut tables);
-    towncrier::fix(&mut tables);
-    pylint::fix(&mut tables);
-    djlint::fix(&mut tables);
-    yapf::fix(&mut tables);
-    check_manifest::fix(&mut tables);
-    pyrefly::fix(&mut tables);
-    semantic_release::fix(&mut tables);
-    scikit_build::fix(&mut tables);
-    bumpversion::fix(&mut tables);
-    interrogate::fix(&mut tables);
-    docformatter::fix(&mut tables);
-    vulture::fix(&mut tables);
-    autopep8::fix(&mut tables);
-    deptry::fix(&mut tables);
-    ty::fix(&mut tables);
-    coverage::fix(&mut tables);
-    pyproject_fmt::fix(&mut tables);
-    reorder_tables(&root_ast, &tables, &opt.separate_root_table, &opt.sub_table_spacing);
+    run("build-system", &mut tables, &|tables| {
+        build_system::fix(tables, opt.keep_full_version, lowercase_name, diagnostics);
+    });
+    if rules.enabled("project") {
+        project::fix(
+            &mut tables,
+            opt.keep_full_version,
+            lowercase_name,
+            merge,
+            opt.max_supported_python,
+            opt.min_supported_python,
+            opt.generate_python_version_classifiers && rules.enabled("project.classifiers.generate"),
+            &table_config,
+            diagnostics,
+        )?;
+    }
+    run("dependency-groups", &mut tables, &|tables| {
+        dependency_groups::fix(
+            tables,
+            opt.keep_full_version,
+            lowercase_name,
+            merge,
+            opt.normalize_dependency_group_names,
+            diagnostics,
+        );
+    });
+    if opt.simplify_specifiers {
+        run("requirements.simplify", &mut tables, &|tables| {
+            specifiers::fix(tables, diagnostics)
+        });
+    }
+    if opt.prune_python_markers {
+        run("requirements.python-markers", &mut tables, &|tables| {
+            python_markers::fix(tables, diagnostics);
+        });
+    }
+    run("ruff", &mut tables, &ruff::fix);
+    run("uv", &mut tables, &|tables| {
+        uv::fix(tables, rules.enabled("uv.sort-arrays"))
+    });
+    run("pixi", &mut tables, &pixi::fix);
+    builtin("commitizen", &mut tables);
+    run("poetry", &mut tables, &poetry::fix);
+    run("mypy", &mut tables, &mypy::fix);
+    run("setuptools", &mut tables, &setuptools::fix);
+    builtin("pytest", &mut tables);
+    builtin("black", &mut tables);
+    run("hatch", &mut tables, &hatch::fix);
+    run("isort", &mut tables, &isort::fix);
+    run("pyright", &mut tables, &pyright::fix);
+    run("pdm", &mut tables, &pdm::fix);
+    run("cibuildwheel", &mut tables, &cibuildwheel::fix);
+    run("tox", &mut tables, &|tables| {
+        let python_envs = if opt.generate_tox_python_envs {
+            project::supported_pythons(tables, opt.max_supported_python, opt.min_supported_python)
+        } else {
+            vec![]
+        };
+        tox::fix(tables, &rules, &opt.pin_envs, &python_envs, diagnostics);
+    });
+    if opt.validate_tox {
+        tox::validate(&mut tables, &rules, diagnostics);
+    }
+    run("bandit", &mut tables, &bandit::fix);
+    builtin("maturin", &mut tables);
+    builtin("codespell", &mut tables);
+    run("towncrier", &mut tables, &towncrier::fix);
+    run("pylint", &mut tables, &pylint::fix);
+    builtin("djlint", &mut tables);
+    builtin("yapf", &mut tables);
+    builtin("check-manifest", &mut tables);
+    builtin("pyrefly", &mut tables);
+    builtin("semantic-release", &mut tables);
+    run("scikit-build", &mut tables, &scikit_build::fix);
+    builtin("bumpversion", &mut tables);
+    builtin("interrogate", &mut tables);
+    builtin("docformatter", &mut tables);
+    builtin("vulture", &mut tables);
+    builtin("autopep8", &mut tables);
+    builtin("deptry", &mut tables);
+    builtin("ty", &mut tables);
+    run("coverage", &mut tables, &coverage::fix);
+    builtin("pyproject-fmt", &mut tables);
+    run("custom.rule-files", &mut tables, &|tables| {
+        for rule_file in &opt.rule_files {
+            rule_file.fix(tables);
+        }
+    });
+    run("custom.key-order", &mut tables, &|tables| {
+        custom::fix_key_order(tables, &opt.key_order);
+    });
+    run("custom.sort-arrays", &mut tables, &|tables| {
+        custom::fix_sort_arrays(tables, &opt.sort_arrays);
+    });
+    if rules.enabled("tables.reorder") {
+        let mut key_order: BTreeMap<String, Vec<String>> = opt
+            .rule_files
+            .iter()
+            .map(|rule_file| (rule_file.table.clone(), rule_file.key_order.clone()))
+            .collect();
+        key_order.extend(opt.key_order.clone());
+        reorder_tables(
+            &root_ast,
+            &tables,
+            &key_order,
+            &opt.separate_root_table,
+            &opt.sub_table_spacing,
+        );
+    }
     // Must follow reorder_tables: only then have AoT entries collapsed to inline arrays of inline tables
     // (e.g. [[tool.poetry.source]] → source = [{...}]) and become INLINE_TABLE descendants of root_ast.
-    poetry::reorder_inline_tables(&root_ast);
-    mypy::reorder_inline_tables(&root_ast);
-    setuptools::reorder_inline_tables(&root_ast);
-    tox::reorder_inline_tables(&root_ast);
+    for (rule, reorder_inline_tables) in [
+        ("poetry", poetry::reorder_inline_tables as fn(&tombi_syntax::SyntaxNode)),
+        ("mypy", mypy::reorder_inline_tables),
+        ("setuptools", setuptools::reorder_inline_tables),
+        ("tox", tox::reorder_inline_tables),
+    ] {
+        if rules.enabled(rule) {
+            reorder_inline_tables(&root_ast);
+        }
+    }
     ensure_all_arrays_multiline(&root_ast, opt.column_width);
-    common::string::wrap_all_long_strings(&root_ast, opt.column_width, &indent_string, &opt.skip_wrap_for_keys);
+    run("strings.wrap", &mut tables, &|_| {
+        common::string::wrap_all_long_strings(&root_ast, opt.column_width, &indent_string, &opt.skip_wrap_for_keys);
+    });
 
     let modified_content = root_ast.to_string();
 
@@ -248,23 +692,33 @@ fn format_core(content: &str, opt: &Settings) -> Result<String, String> {
         .build()
         .unwrap();
     let formatted = rt.block_on(format_with_tombi(&modified_content, opt.column_width, opt.indent));
+    record_edits("tombi/reformat", &modified_content, &formatted);
 
     let formatted_ast = parse(&formatted);
     common::array::align_array_comments(&formatted_ast);
-    let formatted = formatted_ast.to_string();
+    let aligned = formatted_ast.to_string();
+    record_edits("layout/align-comments", &formatted, &aligned);
 
     let sub_spacing = (opt.table_format == "long").then_some(opt.sub_table_spacing.as_str());
-    let result = common::table::normalize_table_spacing(&formatted, &["tool"], &opt.separate_root_table, sub_spacing);
-    Ok(common::util::limit_blank_lines(&result, 2))
+    let result = common::table::normalize_table_spacing(&aligned, &["tool"], &opt.separate_root_table, sub_spacing);
+    let result = common::util::limit_blank_lines(&result, 2);
+    record_edits("layout/table-spacing", &aligned, &result);
+    Ok(result)
 }
 
 /// # Errors
 ///
 /// Will return `PyErr` if an error is raised during formatting.
+#[cfg(feature = "python")]
 #[pymodule(gil_used = false)]
 #[pyo3(name = "_lib")]
 pub fn _lib(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(format_toml_py, m)?)?;
     m.add_class::<Settings>()?;
+    m.add_class::<Formatted>()?;
+    m.add_class::<Diagnostic>()?;
+    m.add_class::<Explanation>()?;
+    m.add_class::<RuleFile>()?;
+    m.add_class::<InlineTableRule>()?;
     Ok(())
 }
@@ -207,7 +207,7 @@ fn fix_expanded_overrides(tables: &mut Tables) {
 // Discriminators avoid collisions: `disable_error_code` and `enable_error_code` are mypy-specific in pyproject.toml,
 // while `module` alone could match unrelated inline tables. Several discriminators map to the same OVERRIDES_KEY_ORDER,
 // so an entry with only `module` + `ignore_missing_imports` is still recognized.
-pub const INLINE_TABLE_SCHEMAS: &[InlineTableSchema] = &[
+pub const INLINE_TABLE_SCHEMAS: &[InlineTableSchema<'static>] = &[
     InlineTableSchema {
         discriminator: "disable_error_code",
         key_order: OVERRIDES_KEY_ORDER,
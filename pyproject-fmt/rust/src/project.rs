@@ -11,12 +11,16 @@ use tombi_syntax::SyntaxKind::{
 };
 use tombi_syntax::{SyntaxElement, SyntaxNode};
 
-use common::array::{dedupe_strings, ensure_trailing_comma, sort, sort_strings, transform};
+use common::array::{
+    dedupe_strings, ensure_trailing_comma, merge_strings, sort, sort_strings, transform, transform_with_node,
+};
 use common::create::{
     make_array, make_array_entry, make_comma, make_entry_of_string, make_key, make_newline,
     make_table_array_with_entries, make_whitespace_n,
 };
-use common::pep508::{is_valid_version, Requirement};
+use common::diagnostic::Diagnostics;
+use common::explain::{record_path, with_rule};
+use common::pep508::{is_valid_version, normalize_requirement, Requirement, VersionOp};
 use common::string::{get_string_token, get_string_value, load_text, update_content};
 use common::table::{for_entries, reorder_table_keys, Tables};
 
@@ -47,16 +51,34 @@ pub const KEY_ORDER: &[&str] = &[
     "entry-points",
 ];
 
-fn normalize_and_sort_requirements(entry: &SyntaxNode, keep_full_version: bool) {
-    transform(entry, &|s| {
-        Requirement::new(s).unwrap().normalize(keep_full_version).to_string()
-    });
+fn normalize_and_sort_requirements(
+    entry: &SyntaxNode,
+    keep_full_version: bool,
+    lowercase_name: bool,
+    merge: bool,
+    key_path: &str,
+    diagnostics: &Diagnostics,
+) {
+    transform_with_node(
+        entry,
+        &|node, s| match normalize_requirement(s, keep_full_version, lowercase_name) {
+            Ok(normalized) => normalized,
+            Err(err) => {
+                diagnostics.report_node(node, key_path, s, &err);
+                s.to_string()
+            }
+        },
+    );
+    if merge {
+        merge_requirements(entry, key_path, diagnostics);
+    }
     sort::<(String, String), _, _>(
         entry,
         |node| {
             get_string_token(node).map(|token| {
                 let val = load_text(token.text(), node.kind());
-                let package_name = Requirement::new(val.as_str()).unwrap().canonical_name();
+                let package_name =
+                    Requirement::new(val.as_str()).map_or_else(|_| val.to_lowercase(), |r| r.canonical_name());
                 (package_name, val)
             })
         },
@@ -70,16 +92,43 @@ fn normalize_and_sort_requirements(entry: &SyntaxNode, keep_full_version: bool)
     );
 }
 
+/// Merges the requirements that differ only in their version specifiers, `requests>=2` and `requests<3` become
+/// `requests>=2,<3`. Requirements pinning different URLs or versions no release satisfies together are reported and
+/// kept apart.
+pub fn merge_requirements(entry: &SyntaxNode, key_path: &str, diagnostics: &Diagnostics) {
+    with_rule("pep508/merge-requirements", || {
+        merge_strings(
+            entry,
+            |s| Requirement::new(s).ok().map(|req| req.identity()),
+            |node, values| {
+                let mut requirements = values.iter().filter_map(|value| Requirement::new(value).ok());
+                let first = requirements.next()?;
+                match requirements.try_fold(first, |merged, req| merged.merge(&req)) {
+                    Ok(merged) => Some(merged.to_string()),
+                    Err(err) => {
+                        diagnostics.report_node(node, key_path, &values[0], &err);
+                        None
+                    }
+                }
+            },
+        );
+    });
+}
+
 /// # Errors
 ///
 /// Will return the offending value if `project.version` is not a valid PEP 440 version.
+#[allow(clippy::too_many_arguments)]
 pub fn fix(
     tables: &mut Tables,
     keep_full_version: bool,
+    lowercase_name: bool,
+    merge: bool,
     max_supported_python: (u8, u8),
     min_supported_python: (u8, u8),
     generate_python_version_classifiers: bool,
     table_config: &TableFormatConfig,
+    diagnostics: &Diagnostics,
 ) -> Result<(), String> {
     let key_order = &["name", "email"];
 
@@ -102,7 +151,15 @@ pub fn fix(
     let mut invalid_version = None;
     for_entries(table, &mut |key, entry| match key.split('.').next().unwrap() {
         "name" => {
-            update_content(entry, |s| Requirement::new(s).unwrap().canonical_name());
+            with_rule("project/normalize-name", || {
+                update_content(entry, |s| match Requirement::new(s) {
+                    Ok(req) => req.canonical_name(),
+                    Err(err) => {
+                        diagnostics.report_node(entry, "project.name", s, &err);
+                        s.to_string()
+                    }
+                });
+            });
         }
         "version" => {
             if let Some(raw) = get_string_value(entry) {
@@ -114,37 +171,50 @@ pub fn fix(
         "license" => {
             static LICENSE_RE: LazyLock<Regex> =
                 LazyLock::new(|| Regex::new(r"(?i)([^-])\b(and|or|with)\b([^-])").unwrap());
-            update_content(entry, |s| {
-                LICENSE_RE
-                    .replace_all(s, |caps: &regex::Captures| {
-                        format!("{}{}{}", &caps[1], caps[2].to_uppercase(), &caps[3])
-                    })
-                    .to_string()
+            with_rule("project/normalize-license", || {
+                update_content(entry, |s| {
+                    LICENSE_RE
+                        .replace_all(s, |caps: &regex::Captures| {
+                            format!("{}{}{}", &caps[1], caps[2].to_uppercase(), &caps[3])
+                        })
+                        .to_string()
+                });
             });
         }
         "description" => {
-            update_content(entry, |s| {
-                RE.replace_all(
-                    &s.trim()
-                        .lines()
-                        .map(|part| {
-                            part.split_whitespace()
-                                .filter(|part| !part.trim().is_empty())
-                                .collect::<Vec<&str>>()
-                                .join(" ")
-                        })
-                        .collect::<Vec<String>>()
-                        .join(" "),
-                    ".$1",
-                )
-                .to_string()
+            with_rule("project/normalize-description", || {
+                update_content(entry, |s| {
+                    RE.replace_all(
+                        &s.trim()
+                            .lines()
+                            .map(|part| {
+                                part.split_whitespace()
+                                    .filter(|part| !part.trim().is_empty())
+                                    .collect::<Vec<&str>>()
+                                    .join(" ")
+                            })
+                            .collect::<Vec<String>>()
+                            .join(" "),
+                        ".$1",
+                    )
+                    .to_string()
+                });
             });
         }
         "requires-python" => {
-            update_content(entry, |s| s.split_whitespace().collect());
+            with_rule("project/normalize-requires-python", || {
+                update_content(entry, |s| s.split_whitespace().collect());
+            });
         }
         "dependencies" | "optional-dependencies" => {
-            normalize_and_sort_requirements(entry, keep_full_version);
+            normalize_and_sort_requirements(
+                entry,
+                keep_full_version,
+                lowercase_name,
+                merge,
+                &format!("project.{key}"),
+                diagnostics,
+            );
         }
         "dynamic" => {
             sort_strings::<String, _, _>(entry, |s| s.to_lowercase(), &|lhs, rhs| natural_lexical_cmp(lhs, rhs));
@@ -154,9 +224,11 @@ pub fn fix(
             sort_strings::<String, _, _>(entry, |s| s.to_lowercase(), &|lhs, rhs| natural_lexical_cmp(lhs, rhs));
         }
         "import-names" | "import-namespaces" => {
-            transform(entry, &|s| {
-                static RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s*;\s*").unwrap());
-                RE.replace_all(s, "; ").trim_end().to_string()
+            with_rule("project/normalize-import-names", || {
+                transform(entry, &|s| {
+                    static RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s*;\s*").unwrap());
+                    RE.replace_all(s, "; ").trim_end().to_string()
+                });
             });
             sort_strings::<String, _, _>(entry, |s| s.to_lowercase(), &|lhs, rhs| natural_lexical_cmp(lhs, rhs));
         }
@@ -192,8 +264,16 @@ pub fn fix(
     if let Some(opt_deps_tables) = tables.get("project.optional-dependencies") {
         for table_ref in opt_deps_tables {
             let opt_deps_table = &mut table_ref.borrow_mut();
-            for_entries(opt_deps_table, &mut |_key, entry| {
-                normalize_and_sort_requirements(entry, keep_full_version);
+            for_entries(opt_deps_table, &mut |key, entry| {
+                let key_path = format!("project.optional-dependencies.{key}");
+                normalize_and_sort_requirements(
+                    entry,
+                    keep_full_version,
+                    lowercase_name,
+                    merge,
+                    &key_path,
+                    diagnostics,
+                );
             });
         }
     }
@@ -354,6 +434,16 @@ fn generate_classifiers_to_entry(
             }
             let mut to_add: Vec<_> = must_have.difference(existing).map(|s| s.as_str()).collect();
             to_add.sort();
+            if !delete.is_empty() || !to_add.is_empty() {
+                let mut deleted: Vec<&str> = delete.iter().map(|s| s.as_str()).collect();
+                deleted.sort_unstable();
+                record_path(
+                    "project.classifiers",
+                    "project/generate-classifiers",
+                    &deleted.join(", "),
+                    &to_add.join(", "),
+                );
+            }
             if !to_add.is_empty() {
                 let mut trail_at = 0;
                 for (at, v) in to_insert.iter().rev().enumerate() {
@@ -384,6 +474,40 @@ fn generate_classifiers_to_entry(
     }
 }
 
+/// The Python versions the project supports: those `requires-python` allows within the supported range, the same
+/// versions that get a classifier.
+pub fn supported_pythons(
+    tables: &Tables,
+    max_supported_python: (u8, u8),
+    min_supported_python: (u8, u8),
+) -> Vec<(u8, u8)> {
+    let (min, max, omit) = match tables.get("project") {
+        Some(cells) => {
+            let (min, max, omit, _) =
+                get_python_requires_with_classifier(&cells[0].borrow(), max_supported_python, min_supported_python);
+            (min, max, omit)
+        }
+        None => (min_supported_python, max_supported_python, vec![]),
+    };
+    (min.1..=max.1)
+        .filter(|minor| !omit.contains(minor))
+        .map(|minor| (3, minor))
+        .collect()
+}
+
+/// The specifiers of `requires-python`, which decide the Python versions the project allows. `None` when the project
+/// does not declare `requires-python` or it does not parse.
+pub fn requires_python(tables: &Tables) -> Option<Vec<VersionOp>> {
+    let cells = tables.get("project")?;
+    let mut value = None;
+    for_entries(&cells[0].borrow(), &mut |key, entry| {
+        if key == "requires-python" {
+            value = get_string_value(entry);
+        }
+    });
+    value?.split(',').map(|spec| VersionOp::new(spec).ok()).collect()
+}
+
 type MaxMinPythonWithClassifier = ((u8, u8), (u8, u8), Vec<u8>, Option<HashSet<String>>);
 
 fn get_python_requires_with_classifier(
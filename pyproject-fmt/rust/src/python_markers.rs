@@ -0,0 +1,131 @@
+use common::array::{remove_strings, transform_with_node};
+use common::diagnostic::Diagnostics;
+use common::explain::with_rule;
+use common::pep508::{Requirement, Version, VersionOp};
+use common::table::{for_entries, Tables};
+
+use crate::project::requires_python;
+use crate::specifiers::for_requirement_arrays;
+
+/// Settles the `python_version` and `python_full_version` markers of every requirement against `requires-python`:
+/// drops the requirements whose marker never holds and the clauses that always do, and the Python version classifiers
+/// outside the range. Every removal is reported. Nothing changes when `requires-python` is not declared.
+pub fn fix(tables: &Tables, diagnostics: &Diagnostics) {
+    let Some(allowed) = requires_python(tables) else {
+        return;
+    };
+    let allowed = &allowed;
+    let decide = |variable: &str, op: &str, value: &str| {
+        let full = match variable {
+            "python_version" => false,
+            "python_full_version" => true,
+            _ => return None,
+        };
+        let spec = VersionOp::new(&format!("{op}{value}")).ok()?;
+        let mut outcomes = pythons(allowed, &spec.version).into_iter().map(|python| {
+            let python = if full {
+                python
+            } else {
+                Version::new(&format!("3.{}", python.release[1]))
+            };
+            spec.contains(&python)
+        });
+        let first = outcomes.next()?;
+        outcomes.all(|outcome| outcome == first).then_some(first)
+    };
+
+    for_requirement_arrays(tables, &mut |key_path, entry| {
+        with_rule("pep508/prune-python-marker", || {
+            remove_strings(entry, |node, s| {
+                let dead = Requirement::new(s).is_ok_and(|requirement| requirement.resolve_marker(&decide).is_none());
+                if dead {
+                    diagnostics.report_node(node, key_path, s, "removed, its marker never holds for requires-python");
+                }
+                dead
+            });
+            transform_with_node(entry, &|node, s| {
+                let Some(resolved) = Requirement::new(s)
+                    .ok()
+                    .and_then(|requirement| requirement.resolve_marker(&decide))
+                else {
+                    return s.to_string();
+                };
+                let resolved = resolved.to_string();
+                if resolved != s {
+                    diagnostics.report_node(
+                        node,
+                        key_path,
+                        s,
+                        "dropped the marker clauses requires-python always satisfies",
+                    );
+                }
+                resolved
+            });
+        });
+    });
+
+    if let Some(cells) = tables.get("project") {
+        for_entries(&cells[0].borrow(), &mut |key, entry| {
+            if key != "classifiers" {
+                return;
+            }
+            with_rule("project/prune-classifiers", || {
+                remove_strings(entry, |node, s| {
+                    let outside = python_classifier_minor(s).is_some_and(|minor| {
+                        !pythons(allowed, &Version::new(&format!("3.{minor}")))
+                            .iter()
+                            .any(|python| python.release[1] == minor)
+                    });
+                    if outside {
+                        diagnostics.report_node(
+                            node,
+                            "project.classifiers",
+                            s,
+                            "removed, requires-python excludes this version",
+                        );
+                    }
+                    outside
+                });
+            });
+        });
+    }
+}
+
+/// The Python 3 releases `allowed` accepts, enough of them that `spec` compares against every release the same as
+/// against one of them: the first and a late patch release of each minor version up to one past the newest mentioned,
+/// plus the neighbours of every patch release mentioned.
+fn pythons(allowed: &[VersionOp], spec: &Version) -> Vec<Version> {
+    let mentioned: Vec<&Version> = allowed
+        .iter()
+        .map(|bound| &bound.version)
+        .chain([spec])
+        .filter(|version| version.release.first() == Some(&3))
+        .collect();
+    let last = mentioned
+        .iter()
+        .filter_map(|version| version.release.get(1))
+        .max()
+        .map_or(0, |minor| minor + 1);
+    (0..=last)
+        .flat_map(|minor| {
+            let mut patches = vec![0, 999];
+            for version in &mentioned {
+                if let Some(&patch) = version.release.get(2).filter(|_| version.release[1] == minor) {
+                    patches.extend([patch.saturating_sub(1), patch, patch + 1]);
+                }
+            }
+            patches
+                .into_iter()
+                .map(move |patch| Version::new(&format!("3.{minor}.{patch}")))
+        })
+        .filter(|python| allowed.iter().all(|bound| bound.contains(python)))
+        .collect()
+}
+
+/// The minor version of a `Programming Language :: Python :: 3.X` classifier.
+fn python_classifier_minor(classifier: &str) -> Option<u64> {
+    classifier
+        .strip_prefix("Programming Language :: Python :: 3.")?
+        .parse()
+        .ok()
+}
@@ -0,0 +1,54 @@
+use tombi_syntax::SyntaxNode;
+
+use common::array::transform_with_node;
+use common::diagnostic::Diagnostics;
+use common::explain::with_rule;
+use common::pep508::Requirement;
+use common::table::{for_entries, Tables};
+
+/// The requirement arrays of each table by key, every key when the list is empty.
+const REQUIREMENT_ARRAYS: &[(&str, &[&str])] = &[
+    ("build-system", &["requires"]),
+    ("project", &["dependencies", "optional-dependencies"]),
+    ("project.optional-dependencies", &[]),
+    ("dependency-groups", &[]),
+];
+
+/// Collapses redundant version specifier sets, see [`Requirement::simplify_specifiers`], and reports the sets no
+/// version satisfies. Requirements that do not parse were reported by the table's own pass and are left alone.
+pub fn fix(tables: &Tables, diagnostics: &Diagnostics) {
+    for_requirement_arrays(tables, &mut |key_path, entry| {
+        with_rule("pep508/simplify-specifiers", || {
+            transform_with_node(entry, &|node, s| {
+                let Ok(requirement) = Requirement::new(s) else {
+                    return s.to_string();
+                };
+                match requirement.clone().simplify_specifiers() {
+                    Ok(simplified) if simplified != requirement => simplified.to_string(),
+                    Ok(_) => s.to_string(),
+                    Err(err) => {
+                        diagnostics.report_node(node, key_path, s, &err);
+                        s.to_string()
+                    }
+                }
+            });
+        });
+    });
+}
+
+/// Calls `f` with the key path and value of every array of PEP 508 requirements.
+pub fn for_requirement_arrays(tables: &Tables, f: &mut dyn FnMut(&str, &SyntaxNode)) {
+    for (table_name, keys) in REQUIREMENT_ARRAYS {
+        let Some(cells) = tables.get(table_name) else {
+            continue;
+        };
+        for cell in cells {
+            for_entries(&cell.borrow(), &mut |key, entry| {
+                let first = key.split('.').next().unwrap_or(&key);
+                if keys.is_empty() || keys.contains(&first) {
+                    f(&format!("{table_name}.{key}"), entry);
+                }
+            });
+        }
+    }
+}
@@ -20,20 +20,11 @@ fn evaluate(start: &str) -> String {
 }
 
 fn default_settings() -> Settings {
-    Settings {
-        column_width: 120,
-        indent: 2,
-        keep_full_version: false,
-        max_supported_python: (3, 9),
-        min_supported_python: (3, 9),
-        generate_python_version_classifiers: false,
-        table_format: String::from("short"),
-        sub_table_spacing: String::new(),
-        separate_root_table: String::from("\n"),
-        expand_tables: vec![],
-        collapse_tables: vec![],
-        skip_wrap_for_keys: vec![],
-    }
+    Settings::builder()
+        .max_supported_python((3, 9))
+        .min_supported_python((3, 9))
+        .generate_python_version_classifiers(false)
+        .build()
 }
 
 fn long_settings() -> Settings {
@@ -44,7 +35,7 @@ fn long_settings() -> Settings {
 }
 
 fn evaluate_long(start: &str) -> String {
-    let result = format_toml(start, &long_settings()).unwrap();
+    let result = format_toml(start, &long_settings()).unwrap().content;
     assert_valid_toml(&result);
     result
 }
@@ -1,4 +1,5 @@
 use common::array::ensure_all_arrays_multiline;
+use common::diagnostic::Diagnostics;
 use common::table::Tables;
 use indoc::indoc;
 
@@ -9,7 +10,7 @@ fn format_build_systems_core(start: &str, keep_full_version: bool) -> String {
     let root_ast = parse(start);
     let count = root_ast.children_with_tokens().count();
     let tables = Tables::from_ast(&root_ast);
-    fix(&tables, keep_full_version);
+    fix(&tables, keep_full_version, true, &Diagnostics::default());
     let entries = collect_entries(&tables);
     root_ast.splice_children(0..count, entries);
     ensure_all_arrays_multiline(&root_ast, 120);
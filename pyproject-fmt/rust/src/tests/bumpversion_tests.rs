@@ -2,14 +2,14 @@ use common::array::ensure_all_arrays_multiline;
 use common::table::{apply_table_formatting, Tables};
 
 use super::{assert_valid_toml, collect_entries, format_syntax, parse};
-use crate::bumpversion::fix;
+use crate::builtin_rules;
 
 fn evaluate(start: &str) -> String {
     let root_ast = parse(start);
     let count = root_ast.children_with_tokens().count();
     let mut tables = Tables::from_ast(&root_ast);
     apply_table_formatting(&mut tables, |_| true, &["tool.bumpversion"], 120);
-    fix(&mut tables);
+    builtin_rules::fix("bumpversion", &mut tables);
     let entries = collect_entries(&tables);
     root_ast.splice_children(0..count, entries);
     ensure_all_arrays_multiline(&root_ast, 120);
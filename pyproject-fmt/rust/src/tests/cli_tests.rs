@@ -0,0 +1,101 @@
+use std::fs;
+
+use common::cli::run;
+
+use crate::cli::PyprojectFmt;
+
+#[test]
+fn test_cli_applies_tool_section_over_flags() {
+    let dir = std::env::temp_dir().join(format!("pyproject-fmt-cli-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("pyproject.toml");
+    fs::write(
+        &file,
+        "[project]\nname = \"A_b\"\nrequires-python = \">=3.12\"\n\n[tool.pyproject-fmt]\nmax_supported_python = \"3.13\"\n",
+    )
+    .unwrap();
+    let args = [
+        "pyproject-fmt",
+        "--max-supported-python",
+        "3.14",
+        file.to_str().unwrap(),
+    ];
+    assert_eq!(run::<PyprojectFmt>(args), 1);
+    let got = fs::read_to_string(&file).unwrap();
+    assert!(got.contains("name = \"a-b\""), "{got}");
+    assert!(got.contains("Programming Language :: Python :: 3.13"), "{got}");
+    assert!(!got.contains("Programming Language :: Python :: 3.14"), "{got}");
+    assert_eq!(run::<PyprojectFmt>(args), 0);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cli_reads_key_order_and_sort_arrays_from_tool_section() {
+    let dir = std::env::temp_dir().join(format!("pyproject-fmt-cli-custom-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("pyproject.toml");
+    let start = indoc::indoc! {r#"
+        [tool.acme-deploy]
+        targets = ["prod", "dev"]
+        name = "app"
+
+        [tool.pyproject-fmt]
+        key_order = { "tool.acme-deploy" = ["name", "targets"] }
+        sort_arrays = ["tool.acme-deploy.targets"]
+    "#};
+    fs::write(&file, start).unwrap();
+    assert_eq!(run::<PyprojectFmt>(["pyproject-fmt", file.to_str().unwrap()]), 1);
+    let got = fs::read_to_string(&file).unwrap();
+    assert!(
+        got.contains("[tool.acme-deploy]\nname = \"app\"\ntargets = [ \"dev\", \"prod\" ]\n"),
+        "{got}"
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cli_reads_pin_envs_from_tool_section() {
+    let dir = std::env::temp_dir().join(format!("pyproject-fmt-cli-pin-envs-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("pyproject.toml");
+    let start = indoc::indoc! {r#"
+        [tool.pyproject-fmt]
+        pin_envs = ["type"]
+
+        [tool.tox]
+        env_list = ["py312", "type"]
+    "#};
+    fs::write(&file, start).unwrap();
+    assert_eq!(run::<PyprojectFmt>(["pyproject-fmt", file.to_str().unwrap()]), 1);
+    let got = fs::read_to_string(&file).unwrap();
+    assert!(got.contains("env_list = [ \"type\", \"py312\" ]"), "{got}");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cli_resolves_rule_files_against_the_config_file() {
+    let dir = std::env::temp_dir().join(format!("pyproject-fmt-cli-rule-files-{}", std::process::id()));
+    fs::create_dir_all(dir.join("rules")).unwrap();
+    fs::write(
+        dir.join("rules").join("acme-deploy.toml"),
+        "table = \"tool.acme-deploy\"\nkey_order = [\"name\", \"targets\"]\n",
+    )
+    .unwrap();
+    let file = dir.join("pyproject.toml");
+    let start = indoc::indoc! {r#"
+        [tool.acme-deploy]
+        targets = ["prod"]
+        name = "app"
+
+        [tool.pyproject-fmt]
+        rule_files = ["rules/acme-deploy.toml"]
+    "#};
+    fs::write(&file, start).unwrap();
+    assert_eq!(run::<PyprojectFmt>(["pyproject-fmt", file.to_str().unwrap()]), 1);
+    let got = fs::read_to_string(&file).unwrap();
+    assert!(
+        got.contains("[tool.acme-deploy]\nname = \"app\"\ntargets = [ \"prod\" ]\n"),
+        "{got}"
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,4 +1,5 @@
 use common::array::ensure_all_arrays_multiline;
+use common::diagnostic::Diagnostics;
 use common::table::Tables;
 use indoc::indoc;
 
@@ -6,16 +7,39 @@ use super::{assert_valid_toml, collect_entries, format_syntax, parse};
 use crate::dependency_groups::fix;
 
 fn format_dependency_groups_helper(start: &str, keep_full_version: bool) -> String {
+    check_dependency_groups_helper(start, keep_full_version, false).0
+}
+
+/// The formatted table and the sorted `(key_path, message)` pairs reported for it.
+fn check_dependency_groups_helper(
+    start: &str,
+    keep_full_version: bool,
+    normalize_names: bool,
+) -> (String, Vec<(String, String)>) {
     let root_ast = parse(start);
     let count = root_ast.children_with_tokens().count();
     let mut tables = Tables::from_ast(&root_ast);
-    fix(&mut tables, keep_full_version);
+    let diagnostics = Diagnostics::default();
+    fix(
+        &mut tables,
+        keep_full_version,
+        true,
+        true,
+        normalize_names,
+        &diagnostics,
+    );
     let entries = collect_entries(&tables);
     root_ast.splice_children(0..count, entries);
     ensure_all_arrays_multiline(&root_ast, 120);
     let result = format_syntax(root_ast, 120);
     assert_valid_toml(&result);
-    result
+    let mut reported: Vec<(String, String)> = diagnostics
+        .locate(start)
+        .into_iter()
+        .map(|diagnostic| (diagnostic.key_path, diagnostic.message))
+        .collect();
+    reported.sort();
+    (result, reported)
 }
 
 #[test]
@@ -142,7 +166,7 @@ fn test_format_dependency_groups_duplicate_package_names() {
     let res = format_dependency_groups_helper(start, false);
     insta::assert_snapshot!(res, @r#"
     [dependency-groups]
-    test = [ "other", "pkg>=1", "pkg>=2" ]
+    test = [ "other", "pkg>=2,>=1" ]
     "#);
 }
 
@@ -374,8 +398,7 @@ fn test_dependency_groups_tertiary_sort_same_package_same_canonical() {
     insta::assert_snapshot!(result, @r#"
     [dependency-groups]
     test = [
-      "pkg>=1.0",
-      "pkg>=2.0",
+      "pkg>=2.0,>=1.0",
     ]
     "#);
 }
@@ -471,3 +494,91 @@ fn test_dependency_groups_packages_before_include_groups() {
     base = [ "x" ]
     "#);
 }
+
+#[test]
+fn test_dependency_groups_reports_unknown_includes_and_cycles() {
+    let start = indoc! {r#"
+        [dependency-groups]
+        dev = [{include-group = "test"}]
+        test = ["pytest", {include-group = "Dev"}]
+        docs = [{include-group = "missing"}]
+        "#};
+    let (_, reported) = check_dependency_groups_helper(start, false, false);
+    assert_eq!(
+        reported,
+        [
+            (
+                "dependency-groups.docs".to_string(),
+                "includes unknown group missing".to_string()
+            ),
+            (
+                "dependency-groups.test".to_string(),
+                "include cycle dev -> test -> dev".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_dependency_groups_reports_self_include() {
+    let start = indoc! {r#"
+        [dependency-groups]
+        test = ["pytest", {include-group = "test"}]
+        "#};
+    let (_, reported) = check_dependency_groups_helper(start, false, false);
+    assert_eq!(
+        reported,
+        [(
+            "dependency-groups.test".to_string(),
+            "include cycle test -> test".to_string()
+        )]
+    );
+}
+
+#[test]
+fn test_dependency_groups_reports_names_that_normalize_alike() {
+    let start = indoc! {r#"
+        [dependency-groups]
+        dev = ["a"]
+        Dev = ["b"]
+        "#};
+    let (result, reported) = check_dependency_groups_helper(start, false, true);
+    assert_eq!(
+        reported,
+        [(
+            "dependency-groups.Dev".to_string(),
+            "Dev and dev name the same group".to_string()
+        )]
+    );
+    assert!(result.contains("Dev = "), "{result}");
+}
+
+#[test]
+fn test_dependency_groups_normalize_names() {
+    let start = indoc! {r#"
+        [dependency-groups]
+        Dev_Tools = ["a", {include-group = "Test.Suite"}]
+        Test__Suite = ["b"]
+        "#};
+    let (result, reported) = check_dependency_groups_helper(start, false, true);
+    insta::assert_snapshot!(result, @r#"
+    [dependency-groups]
+    dev-tools = [ "a", { include-group = "test-suite" } ]
+    test-suite = [ "b" ]
+    "#);
+    assert!(reported.is_empty());
+}
+
+#[test]
+fn test_dependency_groups_keep_names_by_default() {
+    let start = indoc! {r#"
+        [dependency-groups]
+        Dev_Tools = ["a"]
+        "#};
+    let (result, reported) = check_dependency_groups_helper(start, false, false);
+    insta::assert_snapshot!(result, @r#"
+    [dependency-groups]
+    Dev_Tools = [ "a" ]
+    "#);
+    assert!(reported.is_empty());
+}
@@ -5,24 +5,15 @@ use super::assert_valid_toml;
 use crate::{format_toml, Settings};
 
 fn settings() -> Settings {
-    Settings {
-        column_width: 120,
-        indent: 2,
-        keep_full_version: false,
-        max_supported_python: (3, 9),
-        min_supported_python: (3, 9),
-        generate_python_version_classifiers: false,
-        table_format: String::from("short"),
-        sub_table_spacing: String::new(),
-        separate_root_table: String::from("\n"),
-        expand_tables: vec![],
-        collapse_tables: vec![],
-        skip_wrap_for_keys: vec![],
-    }
+    Settings::builder()
+        .max_supported_python((3, 9))
+        .min_supported_python((3, 9))
+        .generate_python_version_classifiers(false)
+        .build()
 }
 
 fn evaluate(start: &str) -> String {
-    let result = format_toml(start, &settings()).unwrap();
+    let result = format_toml(start, &settings()).unwrap().content;
     assert_valid_toml(&result);
     assert!(
         !result.contains(MARKER),
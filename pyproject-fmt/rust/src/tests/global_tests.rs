@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use common::array::ensure_all_arrays_multiline;
 use common::table::Tables;
 use indoc::indoc;
@@ -8,7 +10,7 @@ use crate::global::reorder_tables;
 fn reorder_table_helper(start: &str) -> String {
     let root_ast = parse(start);
     let tables = Tables::from_ast(&root_ast);
-    reorder_tables(&root_ast, &tables, "\n", "");
+    reorder_tables(&root_ast, &tables, &BTreeMap::new(), "\n", "");
     ensure_all_arrays_multiline(&root_ast, 120);
     format_syntax(root_ast, 120)
 }
@@ -20,21 +20,12 @@ fn evaluate(start: &str) -> String {
 }
 
 fn evaluate_full(start: &str) -> String {
-    let s = Settings {
-        column_width: 120,
-        indent: 2,
-        keep_full_version: false,
-        max_supported_python: (3, 13),
-        min_supported_python: (3, 9),
-        generate_python_version_classifiers: false,
-        table_format: String::from("short"),
-        sub_table_spacing: String::new(),
-        separate_root_table: String::from("\n"),
-        expand_tables: vec![],
-        collapse_tables: vec![],
-        skip_wrap_for_keys: vec![],
-    };
-    let r = format_toml(start, &s).unwrap();
+    let s = Settings::builder()
+        .max_supported_python((3, 13))
+        .min_supported_python((3, 9))
+        .generate_python_version_classifiers(false)
+        .build();
+    let r = format_toml(start, &s).unwrap().content;
     assert_valid_toml(&r);
     r
 }
@@ -205,21 +196,13 @@ fn test_hatch_no_table_is_noop() {
 }
 
 fn evaluate_long(start: &str) -> String {
-    let s = Settings {
-        column_width: 120,
-        indent: 2,
-        keep_full_version: false,
-        max_supported_python: (3, 13),
-        min_supported_python: (3, 9),
-        generate_python_version_classifiers: false,
-        table_format: String::from("long"),
-        sub_table_spacing: String::new(),
-        separate_root_table: String::from("\n"),
-        expand_tables: vec![],
-        collapse_tables: vec![],
-        skip_wrap_for_keys: vec![],
-    };
-    let r = format_toml(start, &s).unwrap();
+    let s = Settings::builder()
+        .max_supported_python((3, 13))
+        .min_supported_python((3, 9))
+        .generate_python_version_classifiers(false)
+        .table_format("long")
+        .build();
+    let r = format_toml(start, &s).unwrap().content;
     assert_valid_toml(&r);
     r
 }
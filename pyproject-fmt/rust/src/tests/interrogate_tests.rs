@@ -2,7 +2,7 @@ use common::array::ensure_all_arrays_multiline;
 use common::table::{apply_table_formatting, Tables};
 
 use super::{assert_valid_toml, collect_entries, format_syntax, parse};
-use crate::interrogate::fix;
+use crate::builtin_rules;
 use crate::{format_toml, Settings};
 
 fn evaluate(start: &str) -> String {
@@ -10,7 +10,7 @@ fn evaluate(start: &str) -> String {
     let count = root_ast.children_with_tokens().count();
     let mut tables = Tables::from_ast(&root_ast);
     apply_table_formatting(&mut tables, |_| true, &["tool.interrogate"], 120);
-    fix(&mut tables);
+    builtin_rules::fix("interrogate", &mut tables);
     let entries = collect_entries(&tables);
     root_ast.splice_children(0..count, entries);
     ensure_all_arrays_multiline(&root_ast, 120);
@@ -20,20 +20,11 @@ fn evaluate(start: &str) -> String {
 }
 
 fn default_settings() -> Settings {
-    Settings {
-        column_width: 120,
-        indent: 2,
-        keep_full_version: false,
-        max_supported_python: (3, 9),
-        min_supported_python: (3, 9),
-        generate_python_version_classifiers: false,
-        table_format: String::from("short"),
-        sub_table_spacing: String::new(),
-        separate_root_table: String::from("\n"),
-        expand_tables: vec![],
-        collapse_tables: vec![],
-        skip_wrap_for_keys: vec![],
-    }
+    Settings::builder()
+        .max_supported_python((3, 9))
+        .min_supported_python((3, 9))
+        .generate_python_version_classifiers(false)
+        .build()
 }
 
 fn long_settings() -> Settings {
@@ -44,7 +35,7 @@ fn long_settings() -> Settings {
 }
 
 fn evaluate_long(start: &str) -> String {
-    let result = format_toml(start, &long_settings()).unwrap();
+    let result = format_toml(start, &long_settings()).unwrap().content;
     assert_valid_toml(&result);
     result
 }
@@ -2,23 +2,17 @@ use indoc::indoc;
 use insta::assert_snapshot;
 
 use super::assert_valid_toml;
-use crate::{format_toml, Settings};
+use common::formatted::TextEdit;
+use common::range::Selection;
+
+use crate::{format_range, format_toml, Settings};
 
 fn default_settings() -> Settings {
-    Settings {
-        column_width: 120,
-        indent: 2,
-        keep_full_version: false,
-        max_supported_python: (3, 9),
-        min_supported_python: (3, 9),
-        generate_python_version_classifiers: false,
-        table_format: String::from("short"),
-        sub_table_spacing: String::new(),
-        separate_root_table: String::from("\n"),
-        expand_tables: vec![],
-        collapse_tables: vec![],
-        skip_wrap_for_keys: vec![],
-    }
+    Settings::builder()
+        .max_supported_python((3, 9))
+        .min_supported_python((3, 9))
+        .generate_python_version_classifiers(false)
+        .build()
 }
 
 fn long_format_settings() -> Settings {
@@ -42,7 +36,7 @@ fn format_toml_helper(
         generate_python_version_classifiers,
         ..default_settings()
     };
-    let result = format_toml(start, &settings).unwrap();
+    let result = format_toml(start, &settings).unwrap().content;
     assert_valid_toml(&result);
     result
 }
@@ -123,7 +117,7 @@ fn test_expand_tables_with_project() {
         expand_tables: vec![String::from("project")],
         ..default_settings()
     };
-    let got = format_toml(start, &settings).unwrap();
+    let got = format_toml(start, &settings).unwrap().content;
     assert_snapshot!(got, @r#"
     [project]
     name = "example"
@@ -149,7 +143,7 @@ fn test_collapse_project_authors() {
         collapse_tables: vec![String::from("project.authors")],
         ..long_format_settings()
     };
-    let got = format_toml(start, &settings).unwrap();
+    let got = format_toml(start, &settings).unwrap().content;
     assert_snapshot!(got, @r#"
     [project]
     name = "example"
@@ -170,7 +164,7 @@ fn test_collapse_project_maintainers() {
         collapse_tables: vec![String::from("project.maintainers")],
         ..long_format_settings()
     };
-    let got = format_toml(start, &settings).unwrap();
+    let got = format_toml(start, &settings).unwrap().content;
     assert_snapshot!(got, @r#"
     [project]
     name = "example"
@@ -186,7 +180,7 @@ fn test_table_format_long_with_entry_points() {
         entry-points."console_scripts".mycli = "pkg:main"
         entry-points."console_scripts".othercli = "pkg:other"
         "#};
-    let got = format_toml(start, &long_format_settings()).unwrap();
+    let got = format_toml(start, &long_format_settings()).unwrap().content;
     assert_snapshot!(got, @r#"
     [project]
     name = "example"
@@ -210,7 +204,7 @@ fn test_expand_project_authors() {
         expand_tables: vec![String::from("project.authors")],
         ..default_settings()
     };
-    let got = format_toml(start, &settings).unwrap();
+    let got = format_toml(start, &settings).unwrap().content;
     assert_snapshot!(got, @r#"
     [project]
     name = "example"
@@ -239,7 +233,7 @@ fn test_expand_project_maintainers() {
         expand_tables: vec![String::from("project.maintainers")],
         ..default_settings()
     };
-    let got = format_toml(start, &settings).unwrap();
+    let got = format_toml(start, &settings).unwrap().content;
     assert_snapshot!(got, @r#"
     [project]
     name = "example"
@@ -267,7 +261,7 @@ fn test_expand_single_author() {
         expand_tables: vec![String::from("project.authors")],
         ..default_settings()
     };
-    let got = format_toml(start, &settings).unwrap();
+    let got = format_toml(start, &settings).unwrap().content;
     assert_snapshot!(got, @r#"
     [project]
     name = "example"
@@ -290,7 +284,7 @@ fn test_collapse_authors_with_url_field() {
         name = "Alice"
         email = "alice@example.com"
         "#};
-    let got = format_toml(start, &default_settings()).unwrap();
+    let got = format_toml(start, &default_settings()).unwrap().content;
     assert_snapshot!(got, @r#"
     [project]
     name = "test"
@@ -308,7 +302,7 @@ fn test_collapse_empty_authors() {
         [[project.authors]]
         [[project.authors]]
         "#};
-    let got = format_toml(start, &default_settings()).unwrap();
+    let got = format_toml(start, &default_settings()).unwrap().content;
     assert_snapshot!(got, @r#"
     [project]
     name = "test"
@@ -322,7 +316,7 @@ fn test_collapse_empty_authors() {
 #[test]
 fn test_collapse_authors_without_trailing_newline() {
     let start = "[project]\nname = \"test\"\n[[project.authors]]\nname = \"Alice\"\nemail = \"alice@example.com\"";
-    let got = format_toml(start, &default_settings()).unwrap();
+    let got = format_toml(start, &default_settings()).unwrap().content;
     assert!(got.contains("authors = ["));
     assert!(got.contains("{ name = \"Alice\", email = \"alice@example.com\" }"));
 }
@@ -331,7 +325,7 @@ fn test_collapse_authors_without_trailing_newline() {
 fn test_collapse_authors_compact_parent() {
     let start =
         "[project]\nname=\"test\"\nversion=\"1.0\"\n[[project.authors]]\nname=\"Alice\"\nemail=\"alice@example.com\"";
-    let got = format_toml(start, &default_settings()).unwrap();
+    let got = format_toml(start, &default_settings()).unwrap().content;
     assert!(got.contains("authors = ["));
 }
 
@@ -348,7 +342,7 @@ fn test_expand_authors_already_expanded() {
         expand_tables: vec![String::from("project.authors")],
         ..long_format_settings()
     };
-    let got = format_toml(start, &settings).unwrap();
+    let got = format_toml(start, &settings).unwrap().content;
     assert!(got.contains("[[project.authors]]"));
     assert!(got.contains("name = \"John Doe\""));
 }
@@ -372,7 +366,7 @@ fn test_issue_146_expand_specific_subtable() {
         expand_tables: vec![String::from("project.optional-dependencies")],
         ..default_settings()
     };
-    let got = format_toml(start, &settings).unwrap();
+    let got = format_toml(start, &settings).unwrap().content;
     assert!(
         got.contains("[project.optional-dependencies]"),
         "optional-dependencies should stay expanded"
@@ -397,7 +391,7 @@ fn test_css_specificity_more_specific_wins() {
         collapse_tables: vec![String::from("project")],
         ..long_format_settings()
     };
-    let got = format_toml(start, &settings).unwrap();
+    let got = format_toml(start, &settings).unwrap().content;
     assert!(
         got.contains("[project.urls]"),
         "project.urls should be expanded (specific)"
@@ -495,7 +489,7 @@ fn test_issue_146_deeply_nested_ruff_table() {
         expand_tables: vec![String::from("tool.ruff.lint.flake8-tidy-imports.banned-api")],
         ..default_settings()
     };
-    let got = format_toml(start, &settings).unwrap();
+    let got = format_toml(start, &settings).unwrap().content;
     assert!(
         got.contains("[tool.ruff.lint.flake8-tidy-imports.banned-api]"),
         "deeply nested ruff table should stay expanded. Got:\n{got}"
@@ -509,7 +503,7 @@ fn test_no_duplicate_requires() {
         build-backend = "backend"
         requires = ["c", "d"]
     "#};
-    let got = format_toml(start, &default_settings()).unwrap();
+    let got = format_toml(start, &default_settings()).unwrap().content;
     let count = got.matches("requires").count();
     assert_eq!(count, 1, "requires should appear exactly once, but got:\n{}", got);
 }
@@ -526,7 +520,7 @@ fn test_table_format_long_removes_blank_lines_between_same_group() {
         [project.optional-dependencies]
         dev = ["pytest"]
         "#};
-    let got = format_toml(start, &long_format_settings()).unwrap();
+    let got = format_toml(start, &long_format_settings()).unwrap().content;
     assert_snapshot!(got, @r#"
     [project]
     name = "test"
@@ -549,7 +543,7 @@ fn test_table_format_long_with_tool_tables() {
         [tool.mypy]
         strict = true
         "#};
-    let got = format_toml(start, &long_format_settings()).unwrap();
+    let got = format_toml(start, &long_format_settings()).unwrap().content;
     assert_snapshot!(got, @r#"
     [tool.ruff]
     line-length = 120
@@ -570,7 +564,7 @@ fn test_table_format_long_preserves_blank_lines_between_different_groups() {
         [project]
         name = "test"
         "#};
-    let got = format_toml(start, &long_format_settings()).unwrap();
+    let got = format_toml(start, &long_format_settings()).unwrap().content;
     assert_snapshot!(got, @r#"
     [build-system]
     requires = [ "setuptools" ]
@@ -593,7 +587,7 @@ fn test_extract_table_names_from_array_tables() {
         expand_tables: vec![String::from("project.authors")],
         ..long_format_settings()
     };
-    let got = format_toml(start, &settings).unwrap();
+    let got = format_toml(start, &settings).unwrap().content;
     assert_snapshot!(got, @r#"
     [project]
     name = "test"
@@ -605,7 +599,7 @@ fn test_extract_table_names_from_array_tables() {
 #[test]
 fn test_format_with_trailing_newline_preserved() {
     let start = "[project]\nname = \"test\"\n";
-    let got = format_toml(start, &default_settings()).unwrap();
+    let got = format_toml(start, &default_settings()).unwrap().content;
     assert_snapshot!(got, @r#"
     [project]
     name = "test"
@@ -621,7 +615,7 @@ fn test_tool_prefix_extraction_with_dotted_keys() {
         [tool.coverage.report]
         precision = 2
         "#};
-    let got = format_toml(start, &long_format_settings()).unwrap();
+    let got = format_toml(start, &long_format_settings()).unwrap().content;
     assert_snapshot!(got, @"
     [tool.coverage.run]
     branch = true
@@ -655,7 +649,7 @@ fn test_format_with_non_table_lines_between_headers() {
         [project.urls]
         homepage = "https://example.com"
         "#};
-    let got = format_toml(start, &long_format_settings()).unwrap();
+    let got = format_toml(start, &long_format_settings()).unwrap().content;
     assert_snapshot!(got, @r#"
     [project]
     name = "test"
@@ -665,6 +659,7 @@ fn test_format_with_non_table_lines_between_headers() {
     "#);
 }
 
+#[cfg(feature = "python")]
 #[test]
 fn test_settings_new() {
     let settings = Settings::new(
@@ -694,6 +689,7 @@ fn test_settings_new() {
     assert_eq!(settings.collapse_tables, vec!["project.authors"]);
 }
 
+#[cfg(feature = "python")]
 #[test]
 fn test_table_format_config_from_settings() {
     use crate::TableFormatConfig;
@@ -718,6 +714,7 @@ fn test_table_format_config_from_settings() {
     assert!(config.collapse_tables.contains("project"));
 }
 
+#[cfg(feature = "python")]
 #[test]
 fn test_lib_module_registration() {
     use pyo3::types::PyAnyMethods;
@@ -740,9 +737,9 @@ fn test_idempotent_formatting() {
         description = "This is a long description string that needs to exceed the default column width of one hundred and twenty characters to trigger wrapping."
     "#};
     let settings = default_settings();
-    let first = format_toml(start, &settings).unwrap();
-    let second = format_toml(&first, &settings).unwrap();
-    let third = format_toml(&second, &settings).unwrap();
+    let first = format_toml(start, &settings).unwrap().content;
+    let second = format_toml(&first, &settings).unwrap().content;
+    let third = format_toml(&second, &settings).unwrap().content;
     assert_eq!(first, second, "formatting should be idempotent (first->second)");
     assert_eq!(second, third, "formatting should be idempotent (second->third)");
 }
@@ -757,7 +754,7 @@ fn test_issue_186_single_quote_with_comments() {
         'second',
     ]
     "#};
-    let got = format_toml(start, &default_settings()).unwrap();
+    let got = format_toml(start, &default_settings()).unwrap().content;
     assert_snapshot!(got, @r#"
     [tool.something]
     items = [
@@ -780,7 +777,7 @@ fn test_remove_blank_lines_between_same_group_tables_long_format() {
     [tool.ruff.format]
     quote-style = "double"
     "#};
-    let got = format_toml(start, &long_format_settings()).unwrap();
+    let got = format_toml(start, &long_format_settings()).unwrap().content;
     assert_snapshot!(got, @r#"
     [tool.ruff]
     line-length = 100
@@ -800,7 +797,7 @@ fn test_table_key_without_prefix_match_long_format() {
     [custom.nested]
     other = "data"
     "#};
-    let got = format_toml(start, &long_format_settings()).unwrap();
+    let got = format_toml(start, &long_format_settings()).unwrap().content;
     assert_snapshot!(got, @r#"
     [custom]
     key = "value"
@@ -825,7 +822,7 @@ fn test_sub_table_spacing_blank_line() {
         sub_table_spacing: String::from("\n"),
         ..long_format_settings()
     };
-    let got = format_toml(start, &settings).unwrap();
+    let got = format_toml(start, &settings).unwrap().content;
     assert_snapshot!(got, @r#"
     [tool.ruff]
     line-length = 120
@@ -854,7 +851,7 @@ fn test_sub_table_spacing_with_project_tables() {
         sub_table_spacing: String::from("\n"),
         ..long_format_settings()
     };
-    let got = format_toml(start, &settings).unwrap();
+    let got = format_toml(start, &settings).unwrap().content;
     assert_snapshot!(got, @r#"
     [project]
     name = "test"
@@ -883,7 +880,7 @@ fn test_issue_402_sub_table_spacing_two_blank_lines() {
         sub_table_spacing: String::from("\n\n"),
         ..long_format_settings()
     };
-    let got = format_toml(start, &settings).unwrap();
+    let got = format_toml(start, &settings).unwrap().content;
     assert_snapshot!(got, @r#"
     [tool.uv.sources]
     pkg = { workspace = true }
@@ -910,7 +907,7 @@ fn test_issue_402_separate_root_table_two_blank_lines() {
         separate_root_table: String::from("\n\n"),
         ..default_settings()
     };
-    let got = format_toml(start, &settings).unwrap();
+    let got = format_toml(start, &settings).unwrap().content;
     assert_snapshot!(got, @r#"
     [build-system]
     requires = [ "hatchling" ]
@@ -943,9 +940,9 @@ fn test_issue_217_mixed_quotes_idempotent() {
         min_supported_python: (3, 14),
         ..default_settings()
     };
-    let first = format_toml(start, &settings).unwrap();
+    let first = format_toml(start, &settings).unwrap().content;
     assert_valid_toml(&first);
-    let second = format_toml(&first, &settings).unwrap();
+    let second = format_toml(&first, &settings).unwrap().content;
     assert_eq!(first, second, "formatting should be idempotent");
     assert_snapshot!(first, @r#"
     [project]
@@ -975,9 +972,9 @@ fn test_issue_217_full_pyproject_idempotent() {
         min_supported_python: (3, 10),
         ..default_settings()
     };
-    let first = format_toml(&start, &settings).unwrap();
+    let first = format_toml(&start, &settings).unwrap().content;
     assert_valid_toml(&first);
-    let second = format_toml(&first, &settings).unwrap();
+    let second = format_toml(&first, &settings).unwrap().content;
     assert_eq!(first, second, "formatting should be idempotent");
     assert_snapshot!(first);
 }
@@ -992,7 +989,7 @@ fn test_issue_299_pixi_workspace_collapse_with_keys() {
     [tool.pixi.workspace]
     name = "my-project"
     "#};
-    let got = format_toml(start, &default_settings()).unwrap();
+    let got = format_toml(start, &default_settings()).unwrap().content;
     assert_valid_toml(&got);
     assert_snapshot!(got, @r#"
     [project]
@@ -1013,7 +1010,7 @@ fn test_issue_299_pixi_workspace_collapse_empty() {
 
     [tool.pixi.workspace]
     "#};
-    let got = format_toml(start, &default_settings()).unwrap();
+    let got = format_toml(start, &default_settings()).unwrap().content;
     assert_valid_toml(&got);
     assert_snapshot!(got, @r#"
     [project]
@@ -1031,7 +1028,7 @@ fn test_issue_202_preserve_inline_comment_after_array() {
     [tool.uv]
     lint.per-file-ignores."docs/**/*.py" = [ "INP001" ] # No __init__.py in docs
     "#};
-    let got = format_toml(start, &default_settings()).unwrap();
+    let got = format_toml(start, &default_settings()).unwrap().content;
     assert_snapshot!(got, @r#"
     [tool.uv]
     lint.per-file-ignores."docs/**/*.py" = [ "INP001" ]  # No __init__.py in docs
@@ -1056,7 +1053,7 @@ fn test_issue_376_collapse_with_comments_stays_valid() {
     "#};
     let mut settings = default_settings();
     settings.collapse_tables = vec![String::from("tool.uv.index")];
-    let result = format_toml(start, &settings).unwrap();
+    let result = format_toml(start, &settings).unwrap().content;
     assert_valid_toml(&result);
     insta::assert_snapshot!(result, @r#"
     [[tool.uv.index]]
@@ -1080,7 +1077,7 @@ fn test_wide_array_of_tables_under_implicit_parent() {
         [[tool.demo.labels.file-rules]]
         any-glob-to-any-file = ["src/managers/apt*", "src/managers/dpkg*", "src/managers/opkg*", "tests/*apt*", "tests/*dpkg*", "tests/*opkg*"]
     "#};
-    let result = format_toml(start, &default_settings()).unwrap();
+    let result = format_toml(start, &default_settings()).unwrap().content;
     assert_valid_toml(&result);
     insta::assert_snapshot!(result, @r#"
     [[tool.demo.labels.file-rules]]
@@ -1102,7 +1099,7 @@ fn test_wide_array_of_tables_under_explicit_empty_parent() {
         [[tool.demo.labels.file-rules]]
         any-glob-to-any-file = ["src/managers/apt*", "src/managers/dpkg*", "src/managers/opkg*", "tests/*apt*", "tests/*dpkg*", "tests/*opkg*"]
     "#};
-    let result = format_toml(start, &default_settings()).unwrap();
+    let result = format_toml(start, &default_settings()).unwrap().content;
     assert_valid_toml(&result);
     insta::assert_snapshot!(result, @r#"
     [tool.demo.labels]
@@ -1129,6 +1126,27 @@ fn test_format_toml_rejects_invalid_project_version() {
     assert_snapshot!(error, @"project.version `1.9.xyz` is not a valid PEP 440 version");
 }
 
+#[test]
+fn test_format_toml_reports_invalid_requirement() {
+    let start = indoc! {r#"
+    [project]
+    name = "alpha"
+    dependencies = ["Requests>=2.0,,", "Click"]
+    "#};
+    let got = format_toml(start, &default_settings()).unwrap();
+    assert_snapshot!(got.content, @r#"
+    [project]
+    name = "alpha"
+    dependencies = [ "click", "Requests>=2.0,," ]
+    "#);
+    assert_eq!(got.diagnostics.len(), 1);
+    let diagnostic = &got.diagnostics[0];
+    assert_eq!(diagnostic.key_path, "project.dependencies");
+    assert_eq!(diagnostic.value, "Requests>=2.0,,");
+    assert_eq!((diagnostic.line, diagnostic.column), (3, 18));
+}
+
+#[cfg(feature = "python")]
 #[test]
 fn test_lib_format_toml_returns_formatted_content() {
     use pyo3::types::PyAnyMethods;
@@ -1144,6 +1162,8 @@ fn test_lib_format_toml_returns_formatted_content() {
             .unwrap()
             .call1(("[project]\nname=\"My_Package\"\n", settings))
             .unwrap()
+            .getattr("content")
+            .unwrap()
             .extract::<String>()
             .unwrap();
 
@@ -1154,6 +1174,7 @@ fn test_lib_format_toml_returns_formatted_content() {
     });
 }
 
+#[cfg(feature = "python")]
 #[test]
 fn test_lib_format_toml_raises_on_invalid_version() {
     use pyo3::types::PyAnyMethods;
@@ -1174,3 +1195,691 @@ fn test_lib_format_toml_raises_on_invalid_version() {
         assert_snapshot!(error.value(py).to_string(), @"project.version `1.9.xyz` is not a valid PEP 440 version");
     });
 }
+
+#[test]
+fn test_settings_default_matches_cli() {
+    let settings = Settings::default();
+    assert_eq!((settings.column_width, settings.indent), (120, 2));
+    assert_eq!(settings.max_supported_python, (3, 14));
+    assert_eq!(settings.min_supported_python, (3, 10));
+    assert!(settings.generate_python_version_classifiers);
+    assert!(!settings.keep_full_version);
+    assert_eq!(settings.table_format, "short");
+    assert_eq!(settings.separate_root_table, "\n");
+}
+
+#[test]
+fn test_settings_builder_overrides_defaults() {
+    let settings = Settings::builder()
+        .indent(4)
+        .keep_full_version(true)
+        .generate_python_version_classifiers(false)
+        .table_format("long")
+        .expand_tables(["tool.ruff"])
+        .build();
+    let got = format_toml("[project]\ndependencies=[\"a>=1.0\"]\n", &settings).unwrap();
+    assert_snapshot!(got.content, @r#"
+    [project]
+    dependencies = [ "a>=1.0" ]
+    "#);
+    assert_eq!(settings.expand_tables, ["tool.ruff"]);
+    assert_eq!(settings.column_width, 120);
+}
+
+#[test]
+fn test_format_range_leaves_other_tables_as_written() {
+    let start = indoc! {r#"
+        [build-system]
+        requires=["b","a"]
+
+        [project]
+        name="My_Package"
+        "#};
+    let edits = format_range(start, &Selection::Lines(5..=5), &default_settings())
+        .unwrap()
+        .edits;
+    assert_snapshot!(TextEdit::apply(start, &edits), @r#"
+    [build-system]
+    requires=["b","a"]
+
+    [project]
+    name = "my-package"
+    "#);
+}
+
+#[test]
+fn test_format_range_formatted_selection_has_no_edits() {
+    let start = indoc! {r#"
+        [project]
+        name = "my-package"
+
+        [tool.ruff]
+        line-length=120
+        "#};
+    let selection = Selection::Bytes(0..start.find("[tool").unwrap());
+    assert!(format_range(start, &selection, &default_settings())
+        .unwrap()
+        .edits
+        .is_empty());
+}
+
+#[test]
+fn test_format_range_reports_diagnostics_in_document_positions() {
+    let start = indoc! {r#"
+        [build-system]
+        requires=["b","a"]
+
+        [project]
+        name="alpha"
+        dependencies=["Requests>=2.0,,"]
+        "#};
+    let got = format_range(start, &Selection::Lines(5..=5), &default_settings()).unwrap();
+    assert_eq!(got.diagnostics.len(), 1);
+    let diagnostic = &got.diagnostics[0];
+    assert_eq!(diagnostic.key_path, "project.dependencies");
+    assert_eq!(diagnostic.offset, start.find("Requests").unwrap());
+    assert_eq!((diagnostic.line, diagnostic.column), (6, 16));
+}
+
+#[test]
+fn test_format_toml_edits_leave_unchanged_tokens() {
+    let start = "[project]\nname=\"My_Package\"\n";
+    let got = format_toml(start, &default_settings()).unwrap();
+    let edits = got.edits(start);
+    assert_eq!(TextEdit::apply(start, &edits), got.content);
+    assert!(edits.iter().all(|edit| edit.range.start >= "[project]\nname".len()));
+}
+
+#[test]
+fn test_format_toml_explains_only_when_asked() {
+    let start = "[project]\nname=\"a\"\ndependencies=[\"Requests >= 2.0\"]\n";
+    assert!(format_toml(start, &default_settings()).unwrap().explanations.is_empty());
+
+    let settings = Settings {
+        explain: true,
+        ..default_settings()
+    };
+    let got = format_toml(start, &settings).unwrap();
+    let rules: Vec<String> = got.explanations.iter().map(ToString::to_string).collect();
+    for expected in [
+        "project.dependencies[0]: pep508/normalize-spacing",
+        "project.dependencies[0]: pep508/lowercase-name",
+        "project.dependencies[0]: pep508/strip-trailing-zeros",
+        "project.name: tombi/reformat",
+    ] {
+        assert!(rules.contains(&String::from(expected)), "{rules:?}");
+    }
+    assert_eq!(got.content, format_toml(start, &default_settings()).unwrap().content);
+}
+
+#[test]
+fn test_format_toml_ignore_skips_rule() {
+    let start = "[project]\nname=\"a\"\nrequires-python=\">=3.12\"\n";
+    let generated = format_toml(start, &Settings::default()).unwrap();
+    assert!(generated.content.contains("classifiers"), "{}", generated.content);
+
+    let settings = Settings::builder().ignore(["project.classifiers.generate"]).build();
+    let got = format_toml(start, &settings).unwrap();
+    assert_snapshot!(got.content, @r#"
+    [project]
+    name = "a"
+    requires-python = ">=3.12"
+    "#);
+}
+
+#[test]
+fn test_format_toml_ignore_sub_rules_keeps_names_and_uv_order() {
+    let start = indoc! {r#"
+        [project]
+        name = "a"
+        dependencies = ["My_Package>=1"]
+
+        [tool.uv]
+        constraint-dependencies = ["b", "a"]
+    "#};
+    let settings = Settings::builder()
+        .ignore([
+            "pep508.lowercase-name",
+            "uv.sort-arrays",
+            "project.classifiers.generate",
+        ])
+        .build();
+    let got = format_toml(start, &settings).unwrap();
+    assert_snapshot!(got.content, @r#"
+    [project]
+    name = "a"
+    dependencies = [ "My-Package>=1" ]
+
+    [tool.uv]
+    constraint-dependencies = [ "b", "a" ]
+    "#);
+}
+
+#[test]
+fn test_format_toml_select_runs_only_listed_passes() {
+    let start = indoc! {r#"
+        [project]
+        name="My_Package"
+
+        [tool.ruff]
+        src=["b","a"]
+        "#};
+    let settings = Settings::builder().select(["ruff"]).build();
+    let got = format_toml(start, &settings).unwrap();
+    assert!(got.content.contains("name = \"My_Package\""), "{}", got.content);
+    let settings = Settings::builder().select(["project"]).build();
+    let got = format_toml(start, &settings).unwrap();
+    assert!(got.content.contains("name = \"my-package\""), "{}", got.content);
+    assert!(got.content.contains("src = [ \"b\", \"a\" ]"), "{}", got.content);
+}
+
+#[test]
+fn test_format_toml_reports_unknown_rules() {
+    let settings = Settings::builder()
+        .select(["project", "uv.sort-array"])
+        .ignore(["tox.validate"])
+        .build();
+    let got = format_toml("[project]\nname = \"a\"\n", &settings).unwrap();
+    let reported: Vec<(&str, &str)> = got
+        .diagnostics
+        .iter()
+        .map(|d| (d.key_path.as_str(), d.message.as_str()))
+        .collect();
+    assert_eq!(
+        reported,
+        [
+            ("tool.pyproject-fmt.select", "unknown rule uv.sort-array"),
+            ("tool.pyproject-fmt.ignore", "unknown rule tox.validate"),
+        ]
+    );
+}
+
+#[test]
+fn test_format_toml_orders_tool_tox_envs_with_pins() {
+    let start = indoc! {r#"
+        [tool.tox.env.py312]
+        description = "tests"
+
+        [tool.tox.env.docs]
+        description = "docs"
+
+        [tool.tox]
+        env_list = ["py312", "lint"]
+
+        [tool.tox.env.lint]
+        description = "lint"
+
+        [tool.tox.env_run_base]
+        package = "wheel"
+        "#};
+    let settings = Settings::builder().table_format("long").pin_envs(["lint"]).build();
+    let got = format_toml(start, &settings).unwrap();
+    assert_snapshot!(got.content, @r#"
+    [tool.tox]
+    env_list = [ "lint", "py312" ]
+
+    [tool.tox.env_run_base]
+    package = "wheel"
+
+    [tool.tox.env.lint]
+    description = "lint"
+
+    [tool.tox.env.py312]
+    description = "tests"
+
+    [tool.tox.env.docs]
+    description = "docs"
+    "#);
+}
+
+#[test]
+fn test_format_toml_migrates_legacy_tox_ini() {
+    let start = indoc! {r#"
+        [tool.tox]
+        legacy_tox_ini = """
+        [tox]
+        envlist = py312, lint
+
+        [testenv]
+        deps = pytest
+        commands = pytest {posargs}
+
+        [testenv:lint]
+        skip_install = true
+        commands = ruff check .
+        """
+        "#};
+    let settings = Settings::builder().migrate_legacy_tox_ini(true).build();
+    let got = format_toml(start, &settings).unwrap();
+    assert_valid_toml(&got.content);
+    assert!(got.diagnostics.is_empty(), "{:?}", got.diagnostics);
+    assert!(!got.content.contains("legacy_tox_ini"), "{}", got.content);
+    let parsed: toml::Table = toml::from_str(&got.content).unwrap();
+    let tox = &parsed["tool"]["tox"];
+    assert_eq!(tox["env_list"].as_array().unwrap().len(), 2);
+    assert_eq!(tox["env_run_base"]["deps"][0].as_str(), Some("pytest"));
+    assert_eq!(tox["env"]["lint"]["skip_install"].as_bool(), Some(true));
+    assert_eq!(tox["env"]["lint"]["commands"][0][0].as_str(), Some("ruff"));
+}
+
+#[test]
+fn test_format_toml_keeps_legacy_tox_ini_without_toml_equivalent() {
+    let start = indoc! {r#"
+        [tool.tox]
+        requires = ["tox>=4.22"]
+        legacy_tox_ini = """
+        [tox]
+        requires = tox>=4
+
+        [testenv]
+        deps =
+            py312: pytest-xdist
+        """
+        "#};
+    let settings = Settings::builder().migrate_legacy_tox_ini(true).build();
+    let got = format_toml(start, &settings).unwrap();
+    assert!(got.content.contains("legacy_tox_ini"), "{}", got.content);
+    let messages: Vec<&str> = got.diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(messages.len(), 2, "{messages:?}");
+    assert!(messages[0].contains("factor-conditional deps entry"), "{messages:?}");
+    assert_eq!(messages[1], "not migrated, [tool.tox] already sets requires");
+    assert!(got.diagnostics.iter().all(|d| d.key_path == "tool.tox.legacy_tox_ini"));
+}
+
+#[test]
+fn test_format_toml_validate_tox_reports_unknown_keys() {
+    let start = indoc! {r#"
+        [tool.tox]
+        env_list = ["lint"]
+
+        [tool.tox.env.lint]
+        command = [["ruff", "check"]]
+        skip_install = 1
+        "#};
+    let settings = Settings::builder().validate_tox(true).build();
+    let got = format_toml(start, &settings).unwrap();
+    let mut reported: Vec<(&str, &str)> = got
+        .diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.key_path.as_str(), diagnostic.message.as_str()))
+        .collect();
+    reported.sort_unstable();
+    assert_eq!(
+        reported,
+        [
+            (
+                "tool.tox.env.lint.command",
+                "unknown key command, did you mean commands?"
+            ),
+            ("tool.tox.env.lint.skip_install", "skip_install must be a boolean"),
+        ]
+    );
+}
+
+#[test]
+fn test_format_toml_generates_tox_python_envs() {
+    let start = indoc! {r#"
+        [project]
+        name = "alpha"
+        requires-python = ">=3.11"
+
+        [tool.tox]
+        env_list = ["py312", "lint"]
+        "#};
+    let settings = Settings::builder()
+        .max_supported_python((3, 13))
+        .generate_python_version_classifiers(false)
+        .generate_tox_python_envs(true)
+        .build();
+    let got = format_toml(start, &settings).unwrap();
+    assert!(
+        got.content
+            .contains(r#"env_list = [ "py313", "py312", "py311", "lint" ]"#),
+        "{}",
+        got.content
+    );
+}
+
+#[test]
+fn test_format_toml_generates_tox_python_envs_from_literal_strings() {
+    let start = indoc! {r#"
+        [project]
+        name = "alpha"
+        requires-python = ">=3.11"
+
+        [tool.tox]
+        env_list = ['py312', 'lint']
+        "#};
+    let settings = Settings::builder()
+        .max_supported_python((3, 13))
+        .generate_python_version_classifiers(false)
+        .generate_tox_python_envs(true)
+        .build();
+    let got = format_toml(start, &settings).unwrap();
+    assert!(got.content.contains("py313"), "{}", got.content);
+    assert!(got.content.contains("py311"), "{}", got.content);
+    assert_eq!(got.content.matches("py312").count(), 1, "{}", got.content);
+}
+
+#[test]
+fn test_format_toml_generates_tox_python_envs_keeps_comments() {
+    let start = indoc! {r#"
+        [project]
+        name = "alpha"
+        requires-python = ">=3.12"
+
+        [tool.tox]
+        env_list = [
+          "py312", # oldest supported
+          "lint",
+        ]
+        "#};
+    let settings = Settings::builder()
+        .max_supported_python((3, 13))
+        .generate_python_version_classifiers(false)
+        .generate_tox_python_envs(true)
+        .build();
+    let got = format_toml(start, &settings).unwrap();
+    assert!(got.content.contains("\"py312\", # oldest supported"), "{}", got.content);
+    assert!(got.content.contains("\"py313\""), "{}", got.content);
+}
+
+#[test]
+fn test_format_toml_simplifies_specifiers() {
+    let start = indoc! {r#"
+        [project]
+        name = "alpha"
+        dependencies = ["requests>=2.1,>=2.4", "click>=8,<9,!=7.1", "attrs>=3,<2"]
+        "#};
+    let settings = Settings::builder()
+        .generate_python_version_classifiers(false)
+        .simplify_specifiers(true)
+        .build();
+    let got = format_toml(start, &settings).unwrap();
+    assert_snapshot!(got.content, @r#"
+    [project]
+    name = "alpha"
+    dependencies = [ "attrs>=3,<2", "click>=8,<9", "requests>=2.4" ]
+    "#);
+    assert_eq!(got.diagnostics.len(), 1);
+    assert_eq!(got.diagnostics[0].key_path, "project.dependencies");
+    assert_eq!(got.diagnostics[0].message, "no version satisfies >=3,<2");
+}
+
+#[test]
+fn test_format_toml_prunes_python_markers() {
+    let start = indoc! {r#"
+        [project]
+        name = "alpha"
+        requires-python = ">=3.10"
+        dependencies = [
+          "tomli>=1; python_version < '3.8'",
+          "typing-extensions; python_version >= '3.9' and sys_platform == 'win32'",
+          "exceptiongroup; python_full_version < '3.11.4'",
+        ]
+        "#};
+    let settings = Settings::builder()
+        .generate_python_version_classifiers(false)
+        .prune_python_markers(true)
+        .build();
+    let got = format_toml(start, &settings).unwrap();
+    assert!(!got.content.contains("tomli"), "{}", got.content);
+    assert!(
+        got.content.contains(r#""typing-extensions; sys_platform=='win32'""#),
+        "{}",
+        got.content
+    );
+    assert!(
+        got.content
+            .contains(r#""exceptiongroup; python_full_version<'3.11.4'""#),
+        "{}",
+        got.content
+    );
+    let mut reported: Vec<(&str, &str)> = got
+        .diagnostics
+        .iter()
+        .map(|d| (d.value.as_str(), d.message.as_str()))
+        .collect();
+    reported.sort_unstable();
+    assert_eq!(
+        reported,
+        [
+            (
+                "tomli>=1; python_version<'3.8'",
+                "removed, its marker never holds for requires-python"
+            ),
+            (
+                "typing-extensions; python_version>='3.9' and sys_platform=='win32'",
+                "dropped the marker clauses requires-python always satisfies"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_format_toml_prune_python_markers_needs_requires_python() {
+    let start = indoc! {r#"
+        [project]
+        name = "alpha"
+        dependencies = ["tomli>=1; python_version < '3.8'"]
+        "#};
+    let settings = Settings::builder().prune_python_markers(true).build();
+    let got = format_toml(start, &settings).unwrap();
+    assert!(
+        got.content.contains("tomli>=1; python_version<'3.8'"),
+        "{}",
+        got.content
+    );
+    assert!(got.diagnostics.is_empty());
+}
+
+fn pruned_marker(requires: &str, marker: &str) -> String {
+    let start =
+        format!("[project]\nname = \"alpha\"\nrequires-python = \"{requires}\"\ndependencies = [\"pkg; {marker}\"]\n");
+    let settings = Settings::builder()
+        .generate_python_version_classifiers(false)
+        .prune_python_markers(true)
+        .build();
+    let got = format_toml(&start, &settings).unwrap();
+    let line = got
+        .content
+        .lines()
+        .find(|line| line.contains("\"pkg"))
+        .unwrap_or_else(|| panic!("{}", got.content));
+    line[line.find('"').unwrap() + 1..line.rfind('"').unwrap()].to_string()
+}
+
+#[test]
+fn test_format_toml_prunes_python_markers_compatible_release() {
+    assert_eq!(
+        pruned_marker("~=3.9", "python_version >= '3.10'"),
+        "pkg; python_version>='3.10'"
+    );
+    assert_eq!(pruned_marker("~=3.9", "python_version < '4'"), "pkg");
+}
+
+#[test]
+fn test_format_toml_prunes_python_markers_greater_than() {
+    assert_eq!(
+        pruned_marker(">3.9", "python_version == '3.9'"),
+        "pkg; python_version=='3.9'"
+    );
+    assert_eq!(pruned_marker(">3.9", "python_version >= '3.9'"), "pkg");
+}
+
+#[test]
+fn test_format_toml_prunes_python_markers_not_equal_patch() {
+    assert_eq!(
+        pruned_marker(">=3.10,!=3.11.0", "python_version != '3.11'"),
+        "pkg; python_version!='3.11'"
+    );
+    assert_eq!(
+        pruned_marker(">=3.11,!=3.11.0", "python_full_version > '3.11.0'"),
+        "pkg"
+    );
+}
+
+#[test]
+fn test_format_toml_prunes_classifiers_by_specifier() {
+    let start = indoc! {r#"
+        [project]
+        name = "alpha"
+        requires-python = ">3.9,!=3.11.0"
+        classifiers = [
+          "Programming Language :: Python :: 3.8",
+          "Programming Language :: Python :: 3.9",
+          "Programming Language :: Python :: 3.11",
+        ]
+        "#};
+    let settings = Settings::builder()
+        .generate_python_version_classifiers(false)
+        .prune_python_markers(true)
+        .build();
+    let got = format_toml(start, &settings).unwrap();
+    assert!(!got.content.contains(":: 3.8"), "{}", got.content);
+    assert!(got.content.contains(":: 3.9"), "{}", got.content);
+    assert!(got.content.contains(":: 3.11"), "{}", got.content);
+}
+
+#[test]
+fn test_format_toml_merges_duplicate_requirements() {
+    let start = indoc! {r#"
+        [project]
+        name = "alpha"
+        dependencies = [
+          "requests>=2",
+          "pkg @ https://a.example/pkg.whl",
+          "requests<3",  # upper bound
+          "Requests>=2.1",
+          "pkg @ https://b.example/pkg.whl",
+        ]
+        "#};
+    let settings = Settings::builder().generate_python_version_classifiers(false).build();
+    let got = format_toml(start, &settings).unwrap();
+    assert_snapshot!(got.content, @r#"
+    [project]
+    name = "alpha"
+    dependencies = [
+      "pkg @ https://a.example/pkg.whl",
+      "pkg @ https://b.example/pkg.whl",
+      # upper bound
+      "requests>=2,<3,>=2.1",
+    ]
+    "#);
+    assert_eq!(got.diagnostics.len(), 1);
+    assert_eq!(got.diagnostics[0].key_path, "project.dependencies");
+    assert_eq!(got.diagnostics[0].message, "conflicting URL pins for pkg");
+}
+
+#[test]
+fn test_format_toml_merge_requirements_can_be_ignored() {
+    let start = indoc! {r#"
+        [project]
+        name = "alpha"
+        dependencies = ["requests>=2", "requests<3"]
+
+        [dependency-groups]
+        test = ["pytest>=8", "pytest<9"]
+        "#};
+    let settings = Settings::builder()
+        .generate_python_version_classifiers(false)
+        .ignore(["pep508.merge-requirements"])
+        .build();
+    let got = format_toml(start, &settings).unwrap();
+    assert!(got.diagnostics.is_empty(), "{:?}", got.diagnostics);
+    for kept in ["\"requests>=2\"", "\"requests<3\"", "\"pytest>=8\"", "\"pytest<9\""] {
+        assert!(got.content.contains(kept), "{}", got.content);
+    }
+}
+
+#[test]
+fn test_format_toml_reports_conflicting_version_pins() {
+    let start = indoc! {r#"
+        [project]
+        name = "alpha"
+        dependencies = [
+          "pkg==1",
+          "pkg==2",
+        ]
+        "#};
+    let settings = Settings::builder().generate_python_version_classifiers(false).build();
+    let got = format_toml(start, &settings).unwrap();
+    assert!(got.content.contains(r#""pkg==1""#), "{}", got.content);
+    assert!(got.content.contains(r#""pkg==2""#), "{}", got.content);
+    assert_eq!(got.diagnostics.len(), 1);
+    assert_eq!(got.diagnostics[0].key_path, "project.dependencies");
+    assert_eq!(
+        got.diagnostics[0].message,
+        "conflicting version pins for pkg, no version satisfies ==1,==2"
+    );
+}
+
+#[test]
+fn test_format_toml_custom_key_order_for_expanded_sub_tables() {
+    let start = indoc! {r#"
+        [tool.acme-deploy.zeta]
+        region = "eu"
+
+        [tool.acme-deploy]
+        targets = ["prod", "dev"]
+        name = "app"
+
+        [tool.acme-deploy.alpha]
+        region = "us"
+        "#};
+    let settings = Settings::builder()
+        .table_format("long")
+        .key_order("tool.acme-deploy", ["name", "targets", "zeta", "alpha"])
+        .sort_arrays(["tool.acme-deploy.targets"])
+        .build();
+    let got = format_toml(start, &settings).unwrap();
+    assert_snapshot!(got.content, @r#"
+    [tool.acme-deploy]
+    name = "app"
+    targets = [ "dev", "prod" ]
+
+    [tool.acme-deploy.zeta]
+    region = "eu"
+
+    [tool.acme-deploy.alpha]
+    region = "us"
+    "#);
+}
+
+#[test]
+fn test_format_toml_applies_user_rule_files() {
+    let dir = std::env::temp_dir().join(format!("pyproject-fmt-rule-files-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let rule_file = dir.join("acme-deploy.toml");
+    std::fs::write(
+        &rule_file,
+        indoc! {r#"
+            table = "tool.acme-deploy"
+            key_order = ["name", "targets"]
+            sort_arrays = ["targets"]
+        "#},
+    )
+    .unwrap();
+    let start = indoc! {r#"
+        [tool.acme-deploy]
+        targets = ["prod", "dev"]
+        name = "app"
+        "#};
+    let settings = Settings::builder()
+        .rule_files([rule_file.to_str().unwrap()])
+        .unwrap()
+        .build();
+    let got = format_toml(start, &settings).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_snapshot!(got.content, @r#"
+    [tool.acme-deploy]
+    name = "app"
+    targets = [ "dev", "prod" ]
+    "#);
+}
+
+#[test]
+fn test_settings_rejects_missing_rule_file() {
+    let err = Settings::builder().rule_files(["does-not-exist.toml"]).err().unwrap();
+    assert!(err.starts_with("rule file does-not-exist.toml: "), "{err}");
+}
@@ -9,6 +9,7 @@ mod build_systems_tests;
 mod bumpversion_tests;
 mod check_manifest_tests;
 mod cibuildwheel_tests;
+mod cli_tests;
 mod codespell_tests;
 mod commitizen_tests;
 mod coverage_tests;
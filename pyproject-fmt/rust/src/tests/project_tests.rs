@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 use common::array::ensure_all_arrays_multiline;
+use common::diagnostic::Diagnostics;
 use common::table::{apply_table_formatting, Tables};
 use indoc::indoc;
 
@@ -50,10 +51,13 @@ fn run_project_fix(
     fix(
         &mut tables,
         keep_full_version,
+        true,
+        true,
         max_supported_python,
         (3, 9),
         generate_python_version_classifiers,
         &table_config,
+        &Diagnostics::default(),
     )?;
 
     let entries = collect_entries(&tables);
@@ -1051,7 +1055,18 @@ fn test_project_with_table_format_expand() {
         &["project"],
         120,
     );
-    fix(&mut tables, false, (3, 12), (3, 9), true, &table_config).unwrap();
+    fix(
+        &mut tables,
+        false,
+        true,
+        true,
+        (3, 12),
+        (3, 9),
+        true,
+        &table_config,
+        &Diagnostics::default(),
+    )
+    .unwrap();
     let intermediate = root_ast.to_string();
     let result = format_toml_str(&intermediate, 120);
     insta::assert_snapshot!(result, @r#"
@@ -1090,7 +1105,18 @@ fn test_project_with_collapse_specific_table() {
         &["project"],
         120,
     );
-    fix(&mut tables, false, (3, 11), (3, 9), true, &table_config).unwrap();
+    fix(
+        &mut tables,
+        false,
+        true,
+        true,
+        (3, 11),
+        (3, 9),
+        true,
+        &table_config,
+        &Diagnostics::default(),
+    )
+    .unwrap();
     let intermediate = root_ast.to_string();
     let result = format_toml_str(&intermediate, 120);
     insta::assert_snapshot!(result, @r#"
@@ -1126,7 +1152,18 @@ fn test_project_with_expand_specific_table() {
         &["project"],
         120,
     );
-    fix(&mut tables, false, (3, 11), (3, 9), true, &table_config).unwrap();
+    fix(
+        &mut tables,
+        false,
+        true,
+        true,
+        (3, 11),
+        (3, 9),
+        true,
+        &table_config,
+        &Diagnostics::default(),
+    )
+    .unwrap();
     let intermediate = root_ast.to_string();
     let result = format_toml_str(&intermediate, 120);
     insta::assert_snapshot!(result, @r#"
@@ -1243,8 +1280,8 @@ fn test_project_dependencies_complex_markers() {
       "Programming Language :: Python :: 3.12",
     ]
     dependencies = [
-      "other>=2; (python_version>='3.10' or sys_platform!='win32')",
-      "pkg>=1; python_version<'3.10' and platform_system=='Linux'",
+      "other>=2; python_version>='3.10' or sys_platform!='win32'",
+      "pkg>=1; platform_system=='Linux' and python_version<'3.10'",
     ]
     "#);
 }
@@ -1363,7 +1400,7 @@ fn test_project_dependencies_duplicate_handling() {
       "Programming Language :: Python :: 3.10",
       "Programming Language :: Python :: 3.11",
     ]
-    dependencies = [ "requests>=2.28", "requests>=2.30" ]
+    dependencies = [ "requests>=2.28,>=2.30" ]
     "#);
 }
 
@@ -2146,7 +2183,18 @@ fn test_project_expand_authors_to_array_of_tables() {
         &["project"],
         120,
     );
-    fix(&mut tables, false, (3, 12), (3, 9), false, &table_config).unwrap();
+    fix(
+        &mut tables,
+        false,
+        true,
+        true,
+        (3, 12),
+        (3, 9),
+        false,
+        &table_config,
+        &Diagnostics::default(),
+    )
+    .unwrap();
     let intermediate = root_ast.to_string();
     let result = format_toml_str(&intermediate, 120);
     insta::assert_snapshot!(result, @r#"
@@ -2178,7 +2226,18 @@ fn test_project_expand_maintainers_to_array_of_tables() {
         &["project"],
         120,
     );
-    fix(&mut tables, false, (3, 12), (3, 9), false, &table_config).unwrap();
+    fix(
+        &mut tables,
+        false,
+        true,
+        true,
+        (3, 12),
+        (3, 9),
+        false,
+        &table_config,
+        &Diagnostics::default(),
+    )
+    .unwrap();
     let intermediate = root_ast.to_string();
     let result = format_toml_str(&intermediate, 120);
     insta::assert_snapshot!(result, @r#"
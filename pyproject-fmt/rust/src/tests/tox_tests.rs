@@ -1,15 +1,21 @@
 use common::array::ensure_all_arrays_multiline;
+use common::diagnostic::Diagnostics;
+use common::rules::Rules;
 use common::table::{apply_table_formatting, Tables};
 
 use super::{assert_valid_toml, collect_entries, format_syntax, parse};
-use crate::tox::fix;
+use crate::tox::{fix, validate};
 
 fn evaluate(start: &str) -> String {
+    evaluate_with_diagnostics(start, &Diagnostics::default())
+}
+
+fn evaluate_with_diagnostics(start: &str, diagnostics: &Diagnostics) -> String {
     let root_ast = parse(start);
     let count = root_ast.children_with_tokens().count();
     let mut tables = Tables::from_ast(&root_ast);
     apply_table_formatting(&mut tables, |_| true, &["tool.tox"], 120);
-    fix(&mut tables);
+    fix(&mut tables, &Rules::new(&[], &[]), &[], &[], diagnostics);
     let entries = collect_entries(&tables);
     root_ast.splice_children(0..count, entries);
     ensure_all_arrays_multiline(&root_ast, 120);
@@ -132,3 +138,34 @@ fn test_tox_no_table_is_noop() {
     name = "demo"
     "#);
 }
+
+#[test]
+fn test_tox_reports_undefined_references() {
+    let start = indoc::indoc! {r#"
+    [tool.tox]
+    env_list = [ "py312", "lint" ]
+    labels = { check = [ "lint", "tpye" ] }
+
+    [tool.tox.env.lint]
+    depends = [ "py312" ]
+    deps = [ { replace = "ref", env = "docs", key = "deps" } ]
+    "#};
+    let diagnostics = Diagnostics::default();
+    evaluate_with_diagnostics(start, &diagnostics);
+    assert!(diagnostics.locate(start).is_empty());
+    let diagnostics = Diagnostics::default();
+    let mut tables = Tables::from_ast(&parse(start));
+    validate(&mut tables, &Rules::new(&[], &[]), &diagnostics);
+    let reported: Vec<(String, String)> = diagnostics
+        .locate(start)
+        .into_iter()
+        .map(|diagnostic| (diagnostic.key_path, diagnostic.value))
+        .collect();
+    assert_eq!(
+        reported,
+        [
+            (String::from("tool.tox.labels.check"), String::from("tpye")),
+            (String::from("tool.tox.env.lint.deps"), String::from("docs")),
+        ]
+    );
+}
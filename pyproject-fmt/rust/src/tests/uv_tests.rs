@@ -24,7 +24,7 @@ fn evaluate_core(start: &str, collapse: bool) -> String {
     let count = root_ast.children_with_tokens().count();
     let mut tables = Tables::from_ast(&root_ast);
     apply_table_formatting(&mut tables, |_| collapse, &["tool.uv"], 120);
-    fix(&mut tables);
+    fix(&mut tables, true);
     let entries = collect_entries(&tables);
     root_ast.splice_children(0..count, entries);
     ensure_all_arrays_multiline(&root_ast, 120);
@@ -1,22 +1,191 @@
-use common::table::Tables;
-use tombi_syntax::SyntaxNode;
+use std::cell::RefCell;
+
+use common::diagnostic::Diagnostics;
+use common::rules::Rules;
+use common::string::get_string_value;
+use common::table::{for_entries, Tables};
+use tombi_syntax::SyntaxKind::{DANGLING_COMMENT_GROUP, KEYS, KEY_VALUE, KEY_VALUE_GROUP, LINE_BREAK};
+use tombi_syntax::{SyntaxElement, SyntaxNode};
 
 // Delegates to the shared tox-toml-fmt rules; the `"tool.tox"` prefix resolves tables under that namespace instead of
 // the root, so `[tool.tox]` in pyproject.toml formats identically to a standalone tox.toml.
-const TOOL_TOX: &str = "tool.tox";
+pub const TOOL_TOX: &str = "tool.tox";
+const LEGACY_TOX_INI: &str = "legacy_tox_ini";
+
+/// `python_envs` are the Python versions `env_list` gets an environment for, empty to leave `env_list` as written.
+pub fn fix(
+    tables: &mut Tables,
+    rules: &Rules<'_>,
+    pin_envs: &[String],
+    python_envs: &[(u8, u8)],
+    diagnostics: &Diagnostics,
+) {
+    if tables.get(TOOL_TOX).is_none() {
+        return;
+    }
+    let lowercase_name = rules.enabled("pep508.lowercase-name");
+    if rules.enabled("tox.alias-normalization") {
+        _tox_toml_fmt::global::normalize_aliases_with_prefix(tables, TOOL_TOX);
+    }
+    if rules.enabled("tox.root") {
+        _tox_toml_fmt::global::fix_root_with_prefix(tables, TOOL_TOX, lowercase_name, diagnostics);
+    }
+    if rules.enabled("tox.envs") {
+        _tox_toml_fmt::global::fix_envs_with_prefix(tables, TOOL_TOX, lowercase_name, diagnostics);
+    }
+    if !python_envs.is_empty() && rules.enabled("tox.python-envs") {
+        _tox_toml_fmt::global::add_python_envs_with_prefix(tables, TOOL_TOX, python_envs);
+    }
+    if rules.enabled("tox.env-list-sort") {
+        _tox_toml_fmt::global::sort_env_list_with_prefix(tables, pin_envs, TOOL_TOX);
+    }
+}
 
-pub fn fix(tables: &mut Tables) {
+/// Reports references to undefined environments, `base_python` values contradicting the environment name, unknown
+/// `[tool.tox]` keys and values of the wrong type, see `_tox_toml_fmt::global::check_references`,
+/// `_tox_toml_fmt::global::check_base_python` and `_tox_toml_fmt::global::check_schema`.
+pub fn validate(tables: &mut Tables, rules: &Rules<'_>, diagnostics: &Diagnostics) {
     if tables.get(TOOL_TOX).is_none() {
         return;
     }
-    _tox_toml_fmt::global::normalize_aliases_with_prefix(tables, TOOL_TOX);
-    _tox_toml_fmt::global::fix_root_with_prefix(tables, TOOL_TOX);
-    _tox_toml_fmt::global::fix_envs_with_prefix(tables, TOOL_TOX);
-    // pin_envs is a tox-toml-fmt Setting with no pyproject-fmt CLI surface, so pass none, matching what standalone
-    // tox.toml users get without a pin_envs override.
-    _tox_toml_fmt::global::sort_env_list_with_prefix(tables, &[], TOOL_TOX);
+    if rules.enabled("tox.references") {
+        _tox_toml_fmt::global::check_references_with_prefix(tables, TOOL_TOX, diagnostics);
+    }
+    if rules.enabled("tox.base-python") {
+        _tox_toml_fmt::global::check_base_python_with_prefix(tables, TOOL_TOX, diagnostics);
+    }
+    if rules.enabled("tox.schema") {
+        _tox_toml_fmt::global::check_schema_with_prefix(tables, TOOL_TOX, diagnostics);
+    }
+}
+
+/// The order of the `[tool.tox.*]` sub-tables, the same as the table order of a standalone tox.toml.
+pub fn table_order(tables: &Tables) -> Vec<String> {
+    _tox_toml_fmt::global::table_order_with_prefix(tables, TOOL_TOX)
 }
 
 pub fn reorder_inline_tables(root_ast: &SyntaxNode) {
     _tox_toml_fmt::global::reorder_inline_tables(root_ast);
 }
+
+/// Rewrites the `legacy_tox_ini` string of `[tool.tox]` as native `[tool.tox.*]` tables, which [`fix`] then formats.
+/// When part of the INI has no TOML form, or the native tables already set what it sets, the string is left as written
+/// and each problem reported.
+pub fn migrate_legacy_ini(tables: &mut Tables, diagnostics: &Diagnostics) {
+    let Some(cell) = tables.get(TOOL_TOX).and_then(|cells| cells.into_iter().next()) else {
+        return;
+    };
+    let mut ini = None;
+    let mut native = Vec::new();
+    for_entries(&cell.borrow(), &mut |key, entry| {
+        if key == LEGACY_TOX_INI {
+            ini = get_string_value(entry);
+        } else {
+            native.push(key);
+        }
+    });
+    let Some(ini) = ini else {
+        return;
+    };
+    native.extend(
+        tables
+            .header_to_pos
+            .keys()
+            .filter_map(|name| name.strip_prefix(TOOL_TOX)?.strip_prefix('.'))
+            .map(String::from),
+    );
+
+    let (toml, mut problems) = _tox_toml_fmt::ini::ini_to_toml_with_todos(&ini);
+    let split = toml
+        .lines()
+        .position(|line| line.starts_with('['))
+        .unwrap_or(usize::MAX);
+    let root: String = toml.lines().take(split).map(|line| format!("{line}\n")).collect();
+    let sub_tables: String = toml
+        .lines()
+        .skip(split)
+        .map(|line| match line.strip_prefix('[') {
+            Some(name) => format!("[{TOOL_TOX}.{name}\n"),
+            None => format!("{line}\n"),
+        })
+        .collect();
+    let root_entries = entries(&crate::parse(&root));
+    let migrated = Tables::from_ast(&crate::parse(&sub_tables));
+
+    let mut paths = Vec::new();
+    for_entries(&root_entries, &mut |key, _| paths.push(key));
+    paths.extend(
+        migrated
+            .header_to_pos
+            .keys()
+            .filter_map(|name| name.strip_prefix(TOOL_TOX)?.strip_prefix('.'))
+            .map(String::from),
+    );
+    for path in &paths {
+        if let Some(existing) = native.iter().find(|existing| overlaps(existing, path)) {
+            problems.push(format!("[{TOOL_TOX}] already sets {existing}"));
+        }
+    }
+    if !problems.is_empty() {
+        let key_path = format!("{TOOL_TOX}.{LEGACY_TOX_INI}");
+        for problem in problems {
+            diagnostics.report(&key_path, LEGACY_TOX_INI, &format!("not migrated, {problem}"));
+        }
+        return;
+    }
+
+    {
+        let mut table = cell.borrow_mut();
+        let Some(start) = table
+            .iter()
+            .position(|element| key_of(element).as_deref() == Some(LEGACY_TOX_INI))
+        else {
+            return;
+        };
+        let end = if table.get(start + 1).is_some_and(|next| next.kind() == LINE_BREAK) {
+            start + 2
+        } else {
+            start + 1
+        };
+        table.splice(start..end, root_entries);
+    }
+    for (name, positions) in migrated.header_to_pos {
+        if name.is_empty() {
+            continue;
+        }
+        for pos in positions {
+            let table = migrated.table_set[pos].take();
+            tables
+                .header_to_pos
+                .entry(name.clone())
+                .or_default()
+                .push(tables.table_set.len());
+            tables.table_set.push(RefCell::new(table));
+        }
+    }
+}
+
+/// Whether two dotted key paths set the same value, or one sets a table the other sets a value inside.
+fn overlaps(lhs: &str, rhs: &str) -> bool {
+    lhs == rhs || lhs.starts_with(&format!("{rhs}.")) || rhs.starts_with(&format!("{lhs}."))
+}
+
+fn entries(root: &SyntaxNode) -> Vec<SyntaxElement> {
+    let mut entries = Vec::new();
+    for child in root.children_with_tokens() {
+        if [KEY_VALUE_GROUP, DANGLING_COMMENT_GROUP].contains(&child.kind()) {
+            entries.extend(child.as_node().unwrap().children_with_tokens());
+        } else {
+            entries.push(child);
+        }
+    }
+    entries
+}
+
+fn key_of(element: &SyntaxElement) -> Option<String> {
+    if element.kind() != KEY_VALUE {
+        return None;
+    }
+    let keys = element.as_node()?.children().find(|child| child.kind() == KEYS)?;
+    Some(keys.text().to_string().trim().to_string())
+}
@@ -142,41 +142,46 @@ fn has_key_value_entries(table: &[tombi_syntax::SyntaxElement]) -> bool {
     table.iter().any(|e| e.kind() == KEY_VALUE)
 }
 
+/// `sort_arrays` sorts the package and path lists, such as `constraint-dependencies` and `workspace.members`.
 #[allow(clippy::too_many_lines)]
-pub fn fix(tables: &mut Tables) {
+pub fn fix(tables: &mut Tables, sort_arrays: bool) {
     if let Some(table_elements) = tables.get("tool.uv") {
         let table = &mut table_elements.first().unwrap().borrow_mut();
-        for_entries(table, &mut |key, entry| match key.as_str() {
-            "allow-insecure-host"
-            | "build-constraint-dependencies"
-            | "constraint-dependencies"
-            | "dev-dependencies"
-            | "environments"
-            | "exclude-dependencies"
-            | "no-binary-package"
-            | "no-build-isolation-package"
-            | "no-build-package"
-            | "no-proxy"
-            | "no-sources-package"
-            | "override-dependencies"
-            | "reinstall-package"
-            | "required-environments"
-            | "upgrade-package"
-            | "workspace.exclude"
-            | "workspace.members"
-            | "pip.allow-insecure-host"
-            | "pip.extra"
-            | "pip.no-binary-package"
-            | "pip.no-build-isolation-package"
-            | "pip.no-build-package"
-            | "pip.no-emit-package"
-            | "pip.only-binary-package"
-            | "pip.reinstall-package"
-            | "pip.upgrade-package" => {
-                sort_strings::<String, _, _>(entry, |s| s.to_lowercase(), &|lhs, rhs| natural_lexical_cmp(lhs, rhs));
-            }
-            _ => {}
-        });
+        if sort_arrays {
+            for_entries(table, &mut |key, entry| match key.as_str() {
+                "allow-insecure-host"
+                | "build-constraint-dependencies"
+                | "constraint-dependencies"
+                | "dev-dependencies"
+                | "environments"
+                | "exclude-dependencies"
+                | "no-binary-package"
+                | "no-build-isolation-package"
+                | "no-build-package"
+                | "no-proxy"
+                | "no-sources-package"
+                | "override-dependencies"
+                | "reinstall-package"
+                | "required-environments"
+                | "upgrade-package"
+                | "workspace.exclude"
+                | "workspace.members"
+                | "pip.allow-insecure-host"
+                | "pip.extra"
+                | "pip.no-binary-package"
+                | "pip.no-build-isolation-package"
+                | "pip.no-build-package"
+                | "pip.no-emit-package"
+                | "pip.only-binary-package"
+                | "pip.reinstall-package"
+                | "pip.upgrade-package" => {
+                    sort_strings::<String, _, _>(entry, |s| s.to_lowercase(), &|lhs, rhs| {
+                        natural_lexical_cmp(lhs, rhs)
+                    });
+                }
+                _ => {}
+            });
+        }
         reorder_table_keys(table, KEY_ORDER);
     }
 
@@ -192,22 +197,24 @@ pub fn fix(tables: &mut Tables) {
     if let Some(pip_elements) = tables.get("tool.uv.pip") {
         let pip_table = &mut pip_elements.first().unwrap().borrow_mut();
         if has_key_value_entries(pip_table) {
-            for_entries(pip_table, &mut |key, entry| match key.as_str() {
-                "allow-insecure-host"
-                | "extra"
-                | "no-binary-package"
-                | "no-build-isolation-package"
-                | "no-build-package"
-                | "no-emit-package"
-                | "only-binary-package"
-                | "reinstall-package"
-                | "upgrade-package" => {
-                    sort_strings::<String, _, _>(entry, |s| s.to_lowercase(), &|lhs, rhs| {
-                        natural_lexical_cmp(lhs, rhs)
-                    });
-                }
-                _ => {}
-            });
+            if sort_arrays {
+                for_entries(pip_table, &mut |key, entry| match key.as_str() {
+                    "allow-insecure-host"
+                    | "extra"
+                    | "no-binary-package"
+                    | "no-build-isolation-package"
+                    | "no-build-package"
+                    | "no-emit-package"
+                    | "only-binary-package"
+                    | "reinstall-package"
+                    | "upgrade-package" => {
+                        sort_strings::<String, _, _>(entry, |s| s.to_lowercase(), &|lhs, rhs| {
+                            natural_lexical_cmp(lhs, rhs)
+                        });
+                    }
+                    _ => {}
+                });
+            }
             reorder_table_keys(pip_table, PIP_KEY_ORDER);
         }
     }
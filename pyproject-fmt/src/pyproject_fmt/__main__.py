@@ -6,11 +6,13 @@ from argparse import ArgumentParser, ArgumentTypeError
 from typing import TYPE_CHECKING
 
 from pyproject_fmt._lib import Settings, format_toml
-from toml_fmt_common import ArgumentGroup, FmtNamespace, TOMLFormatter, build_cli, run
+from toml_fmt_common import ArgumentGroup, FmtNamespace, TOMLFormatter, build_cli, list_argument, run
 
 if TYPE_CHECKING:
     from collections.abc import Sequence
 
+    from pyproject_fmt._lib import Formatted
+
 
 class PyProjectFmtNamespace(FmtNamespace):
     """Formatting arguments."""
@@ -18,6 +20,16 @@ class PyProjectFmtNamespace(FmtNamespace):
     keep_full_version: bool
     max_supported_python: tuple[int, int]
     generate_python_version_classifiers: bool
+    sort_arrays: list[str]
+    key_order: dict[str, list[str]]
+    rule_files: list[str]
+    pin_envs: list[str]
+    migrate_legacy_tox_ini: bool
+    validate_tox: bool
+    generate_tox_python_envs: bool
+    simplify_specifiers: bool
+    prune_python_markers: bool
+    normalize_dependency_group_names: bool
 
 
 class PyProjectFormatter(TOMLFormatter[PyProjectFmtNamespace]):
@@ -25,7 +37,7 @@ class PyProjectFormatter(TOMLFormatter[PyProjectFmtNamespace]):
 
     def __init__(self) -> None:
         """Create a formatter."""
-        super().__init__(PyProjectFmtNamespace())
+        super().__init__(PyProjectFmtNamespace(key_order={}))  # only settable from config, so no flag sets it
 
     @property
     def prog(self) -> str:
@@ -71,19 +83,55 @@ class PyProjectFormatter(TOMLFormatter[PyProjectFmtNamespace]):
             default=(3, 14),
             help="latest Python version the project supports (e.g. 3.14)",
         )
+        parser.add_argument(
+            "--sort-arrays",
+            type=list_argument,
+            default=[],
+            help="comma-separated list of arrays to sort, by full dotted path (e.g. 'tool.acme-deploy.targets')",
+        )
+        parser.add_argument(
+            "--rule-files",
+            type=list_argument,
+            default=[],
+            help="comma-separated list of rule files for tools without built-in rules",
+        )
+        parser.add_argument(
+            "--pin-env",
+            type=list_argument,
+            default=[],
+            dest="pin_envs",
+            help="environments pinned to the start of [tool.tox]'s env_list (comma separated)",
+        )
+        msg = "rewrite [tool.tox]'s legacy_tox_ini string as native [tool.tox.*] tables"
+        parser.add_argument("--migrate-legacy-tox-ini", action="store_true", help=msg)
+        msg = "report [tool.tox] undefined environments, mismatched base_python, unknown keys and wrongly typed values"
+        parser.add_argument("--validate-tox", action="store_true", help=msg)
+        msg = "add a [tool.tox] env_list entry for each supported Python version it lacks"
+        parser.add_argument("--generate-tox-python-envs", action="store_true", help=msg)
+        msg = "collapse redundant version specifiers and report unsatisfiable ones"
+        parser.add_argument("--simplify-specifiers", action="store_true", help=msg)
+        msg = "drop requirements, marker clauses and classifiers that requires-python already decides"
+        parser.add_argument("--prune-python-markers", action="store_true", help=msg)
+        msg = "rewrite [dependency-groups] keys and include-group references in normalized form"
+        parser.add_argument("--normalize-dependency-group-names", action="store_true", help=msg)
 
     @property
     def override_cli_from_section(self) -> tuple[str, ...]:
         """Path where config overrides live."""
         return "tool", "pyproject-fmt"
 
-    def format(self, text: str, opt: PyProjectFmtNamespace) -> str:  # ruff: ignore[no-self-use]
+    @property
+    def path_options(self) -> tuple[str, ...]:
+        """Options holding file paths, relative to the config file that sets them."""
+        return ("rule_files",)
+
+    def format(self, text: str, opt: PyProjectFmtNamespace) -> Formatted:  # ruff: ignore[no-self-use]
         """
         Perform the formatting.
 
         :param text: content to operate on
         :param opt: formatter config
-        :return: formatted text
+        :return: formatted text with the diagnostics for entries left as written
         """
         settings = Settings(
             column_width=opt.column_width,
@@ -98,6 +146,19 @@ class PyProjectFormatter(TOMLFormatter[PyProjectFmtNamespace]):
             expand_tables=opt.expand_tables,
             collapse_tables=opt.collapse_tables,
             skip_wrap_for_keys=opt.skip_wrap_for_keys,
+            key_order=opt.key_order,
+            sort_arrays=opt.sort_arrays,
+            rule_files=opt.rule_files,
+            pin_envs=opt.pin_envs,
+            migrate_legacy_tox_ini=opt.migrate_legacy_tox_ini,
+            validate_tox=opt.validate_tox,
+            generate_tox_python_envs=opt.generate_tox_python_envs,
+            simplify_specifiers=opt.simplify_specifiers,
+            prune_python_markers=opt.prune_python_markers,
+            normalize_dependency_group_names=opt.normalize_dependency_group_names,
+            select=opt.select,
+            ignore=opt.ignore,
+            explain=opt.explain,
         )
         return format_toml(text, settings)
 
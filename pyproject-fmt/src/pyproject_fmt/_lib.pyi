@@ -1,4 +1,4 @@
-from collections.abc import Sequence
+from collections.abc import Mapping, Sequence
 
 class Settings:
     def __init__(
@@ -16,6 +16,19 @@ class Settings:
         expand_tables: Sequence[str],
         collapse_tables: Sequence[str],
         skip_wrap_for_keys: Sequence[str],
+        key_order: Mapping[str, Sequence[str]] = ...,
+        sort_arrays: Sequence[str] = (),
+        rule_files: Sequence[str] = (),
+        pin_envs: Sequence[str] = (),
+        migrate_legacy_tox_ini: bool = False,
+        validate_tox: bool = False,
+        generate_tox_python_envs: bool = False,
+        simplify_specifiers: bool = False,
+        prune_python_markers: bool = False,
+        normalize_dependency_group_names: bool = False,
+        select: Sequence[str] = (),
+        ignore: Sequence[str] = (),
+        explain: bool = False,
     ) -> None: ...
     @property
     def column_width(self) -> int: ...
@@ -39,5 +52,83 @@ class Settings:
     def collapse_tables(self) -> Sequence[str]: ...
     @property
     def skip_wrap_for_keys(self) -> Sequence[str]: ...
+    @property
+    def key_order(self) -> Mapping[str, Sequence[str]]: ...
+    @property
+    def sort_arrays(self) -> Sequence[str]: ...
+    @property
+    def rule_files(self) -> Sequence[RuleFile]: ...
+    @property
+    def pin_envs(self) -> Sequence[str]: ...
+    @property
+    def migrate_legacy_tox_ini(self) -> bool: ...
+    @property
+    def validate_tox(self) -> bool: ...
+    @property
+    def generate_tox_python_envs(self) -> bool: ...
+    @property
+    def simplify_specifiers(self) -> bool: ...
+    @property
+    def prune_python_markers(self) -> bool: ...
+    @property
+    def normalize_dependency_group_names(self) -> bool: ...
+    @property
+    def select(self) -> Sequence[str]: ...
+    @property
+    def ignore(self) -> Sequence[str]: ...
+    @property
+    def explain(self) -> bool: ...
+
+class InlineTableRule:
+    @property
+    def discriminator(self) -> str: ...
+    @property
+    def key_order(self) -> Sequence[str]: ...
+
+class RuleFile:
+    @property
+    def table(self) -> str: ...
+    @property
+    def key_order(self) -> Sequence[str]: ...
+    @property
+    def sort_arrays(self) -> Sequence[str]: ...
+    @property
+    def dedupe_arrays(self) -> Sequence[str]: ...
+    @property
+    def case_sensitive(self) -> bool: ...
+    @property
+    def inline_tables(self) -> Sequence[InlineTableRule]: ...
+
+class Diagnostic:
+    @property
+    def offset(self) -> int: ...
+    @property
+    def line(self) -> int: ...
+    @property
+    def column(self) -> int: ...
+    @property
+    def key_path(self) -> str: ...
+    @property
+    def value(self) -> str: ...
+    @property
+    def message(self) -> str: ...
+
+class Explanation:
+    @property
+    def rule(self) -> str: ...
+    @property
+    def key_path(self) -> str: ...
+    @property
+    def before(self) -> str: ...
+    @property
+    def after(self) -> str: ...
+
+class Formatted:
+    @property
+    def content(self) -> str: ...
+    @property
+    def diagnostics(self) -> list[Diagnostic]: ...
+    @property
+    def explanations(self) -> list[Explanation]: ...
 
-def format_toml(content: str, settings: Settings) -> str: ...
+def format_toml(content: str, settings: Settings) -> Formatted: ...
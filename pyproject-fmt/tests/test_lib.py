@@ -103,7 +103,7 @@ def test_format_toml(start: str, expected: str) -> None:
         collapse_tables=[],
         skip_wrap_for_keys=[],
     )
-    res = format_toml(dedent(start), settings)
+    res = format_toml(dedent(start), settings).content
     assert res == dedent(expected)
 
 
@@ -136,5 +136,5 @@ def test_sub_table_spacing(sub_table_spacing: str, *, has_blank_line: bool) -> N
         collapse_tables=[],
         skip_wrap_for_keys=[],
     )
-    res = format_toml(start, settings)
+    res = format_toml(start, settings).content
     assert ("\n\n[tool.ruff.lint]" in res) == has_blank_line
@@ -473,3 +473,148 @@ def test_project_version_kept_verbatim(tmp_path: Path) -> None:
     assert run([str(filename), "--no-print-diff", "--no-generate-python-version-classifiers"]) == 0
 
     assert filename.read_text() == dedent(txt)
+
+
+def test_select_ignore_config(tmp_path: Path) -> None:
+    txt = """\
+    [project]
+    name = "a"
+    dependencies = ["My_Package>=1"]
+
+    [tool.pyproject-fmt]
+    ignore = ["pep508.lowercase-name", "project.classifiers.generate"]
+    """
+    filename = tmp_path / "pyproject.toml"
+    filename.write_text(dedent(txt))
+    run([str(filename), "--no-print-diff"])
+
+    assert 'dependencies = [ "My-Package>=1" ]' in filename.read_text()
+
+
+def test_select_cli(tmp_path: Path) -> None:
+    txt = """\
+    [project]
+    name = "a"
+    keywords = ["b", "a"]
+
+    [tool.ruff]
+    lint.select = ["B", "A"]
+    """
+    filename = tmp_path / "pyproject.toml"
+    filename.write_text(dedent(txt))
+    run([str(filename), "--no-print-diff", "--select", "ruff"])
+
+    got = filename.read_text()
+    assert 'keywords = [ "b", "a" ]' in got
+    assert 'lint.select = [ "A", "B" ]' in got
+
+
+def test_key_order_and_sort_arrays_config(tmp_path: Path) -> None:
+    txt = """\
+    [tool.acme]
+    targets = ["b", "a"]
+    name = "x"
+
+    [tool.pyproject-fmt]
+    sort_arrays = ["tool.acme.targets"]
+    key_order = { "tool.acme" = ["name", "targets"] }
+    """
+    filename = tmp_path / "pyproject.toml"
+    filename.write_text(dedent(txt))
+    run([str(filename), "--no-print-diff"])
+
+    assert '[tool.acme]\nname = "x"\ntargets = [ "a", "b" ]\n' in filename.read_text()
+
+
+def test_rule_files_config(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> None:
+    rules = tmp_path / "rules"
+    rules.mkdir()
+    (rules / "acme.toml").write_text('table = "tool.acme"\nkey_order = ["name", "targets"]\n')
+    txt = """\
+    [tool.acme]
+    targets = ["b", "a"]
+    name = "x"
+
+    [tool.pyproject-fmt]
+    rule_files = ["rules/acme.toml"]
+    """
+    filename = tmp_path / "pyproject.toml"
+    filename.write_text(dedent(txt))
+    elsewhere = tmp_path / "elsewhere"
+    elsewhere.mkdir()
+    monkeypatch.chdir(elsewhere)  # the path is relative to the config, not to where the formatter runs
+
+    run([str(filename), "--no-print-diff"])
+
+    got = filename.read_text()
+    assert got.index('name = "x"') < got.index("targets = ")
+
+
+def test_rule_files_cli_missing(tmp_path: Path, capsys: pytest.CaptureFixture[str]) -> None:
+    filename = tmp_path / "pyproject.toml"
+    filename.write_text("[tool.acme]\nname = 'x'\n")
+
+    assert run([str(filename), "--no-print-diff", "--rule-files", str(tmp_path / "missing.toml")]) == 1
+
+    _, err = capsys.readouterr()
+    assert "missing.toml" in err
+
+
+def test_sort_arrays_cli(tmp_path: Path) -> None:
+    filename = tmp_path / "pyproject.toml"
+    filename.write_text('[tool.acme]\ntargets = ["b", "a"]\n')
+    run([str(filename), "--no-print-diff", "--sort-arrays", "tool.acme.targets"])
+
+    assert filename.read_text() == '[tool.acme]\ntargets = [ "a", "b" ]\n'
+
+
+def test_tox_and_requirement_options_config(tmp_path: Path, capsys: pytest.CaptureFixture[str]) -> None:
+    txt = """\
+    [project]
+    name = "a"
+    dependencies = ["requests>=2.1,>=2.4"]
+
+    [dependency-groups]
+    Dev_Tools = ["b"]
+
+    [tool.pyproject-fmt]
+    generate_python_version_classifiers = false
+    pin_envs = ["type"]
+    validate_tox = true
+    simplify_specifiers = true
+    normalize_dependency_group_names = true
+
+    [tool.tox]
+    env_list = ["py312", "type"]
+
+    [tool.tox.env.type]
+    dep = ["mypy"]
+    """
+    filename = tmp_path / "pyproject.toml"
+    filename.write_text(dedent(txt))
+    assert run([str(filename), "--no-print-diff"]) == 1
+
+    got = filename.read_text()
+    assert 'dependencies = [ "requests>=2.4" ]' in got
+    assert 'dev-tools = [ "b" ]' in got
+    assert 'env_list = [ "type", "py312" ]' in got
+    assert "did you mean deps?" in capsys.readouterr().err
+
+
+def test_tox_and_requirement_options_cli(tmp_path: Path) -> None:
+    txt = """\
+    [project]
+    name = "a"
+    dependencies = ["requests>=2.1,>=2.4"]
+
+    [tool.tox]
+    env_list = ["py312", "type"]
+    """
+    filename = tmp_path / "pyproject.toml"
+    filename.write_text(dedent(txt))
+    args = ["--no-print-diff", "--no-generate-python-version-classifiers", "--pin-env", "type", "--simplify-specifiers"]
+    run([str(filename), *args])
+
+    got = filename.read_text()
+    assert 'dependencies = [ "requests>=2.4" ]' in got
+    assert 'env_list = [ "type", "py312" ]' in got
@@ -71,7 +71,7 @@ def format_example(module: str, before: str, config: str = "") -> str:
     lib = importlib.import_module(f"{module}._lib")
     defaults = dict(DEFAULTS[module])
     defaults.update(_parse_config(config, defaults))
-    return lib.format_toml(before, lib.Settings(**defaults))
+    return lib.format_toml(before, lib.Settings(**defaults)).content
 
 
 def _parse_config(config: str, defaults: Mapping[str, Any]) -> dict[str, Any]:
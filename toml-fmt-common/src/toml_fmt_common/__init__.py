@@ -19,7 +19,7 @@ from dataclasses import dataclass
 from functools import partial
 from importlib.metadata import version
 from pathlib import Path
-from typing import TYPE_CHECKING, Any, Generic, TypeVar, cast
+from typing import TYPE_CHECKING, Any, Generic, Protocol, TypeVar, cast
 
 if TYPE_CHECKING:
     from collections.abc import Callable, Iterable, Mapping, Sequence
@@ -39,6 +39,7 @@ class FmtNamespace(Namespace):
     stdout: bool
     check: bool
     no_print_diff: bool
+    explain: bool
     config: Path | None
 
     column_width: int
@@ -49,11 +50,52 @@ class FmtNamespace(Namespace):
     expand_tables: Sequence[str]
     collapse_tables: Sequence[str]
     skip_wrap_for_keys: Sequence[str]
+    select: Sequence[str]
+    ignore: Sequence[str]
 
 
 T = TypeVar("T", bound=FmtNamespace)
 
 
+class Diagnostic(Protocol):
+    """A problem the formatter found but left as written, e.g. an invalid PEP 508 requirement."""
+
+    @property
+    def line(self) -> int: ...  # 1-based
+
+    @property
+    def column(self) -> int: ...  # 1-based, in characters
+
+    @property
+    def key_path(self) -> str: ...
+
+    @property
+    def message(self) -> str: ...
+
+
+class Explanation(Protocol):
+    """A change the formatter made, named by the rule behind it, e.g. ``pep508/normalize-spacing``."""
+
+    @property
+    def rule(self) -> str: ...
+
+    @property
+    def key_path(self) -> str: ...
+
+
+class Formatted(Protocol):
+    """Formatted text together with the problems found while formatting it."""
+
+    @property
+    def content(self) -> str: ...
+
+    @property
+    def diagnostics(self) -> Sequence[Diagnostic]: ...
+
+    @property
+    def explanations(self) -> Sequence[Explanation]: ...  # filled only when run with --explain
+
+
 class TOMLFormatter(ABC, Generic[T]):
     """API for a TOML formatter."""
 
@@ -96,14 +138,23 @@ class TOMLFormatter(ABC, Generic[T]):
         """
         raise NotImplementedError
 
+    @property
+    def path_options(self) -> tuple[str, ...]:
+        """
+        Options holding lists of file paths, which a config file gives relative to its own directory.
+
+        :returns: the option names
+        """
+        return ()
+
     @abstractmethod
-    def format(self, text: str, opt: T) -> str:
+    def format(self, text: str, opt: T) -> str | Formatted:
         """
         Run the formatter.
 
         :param text: the TOML text to format
         :param opt: the flags to format with
-        :returns: the formatted TOML text
+        :returns: the formatted TOML text, or the text with the diagnostics found while formatting it
         """
         raise NotImplementedError
 
@@ -167,12 +218,14 @@ def _cli_args(info: TOMLFormatter[T], args: Sequence[str]) -> list[_Config[T]]:
                 break
             config = config[part]
         override_opt = deepcopy(info.opt)
+        apply = partial(_apply_config, override_opt, type_conversion=type_conversion, paths=info.path_options)
+        start = pyproject_toml.parent if pyproject_toml is not None else Path.cwd()
         if explicit_config is not None:
-            _apply_config(override_opt, _load_shared_config(explicit_config), type_conversion)
-        elif found := _find_config_file(info.prog, pyproject_toml.parent if pyproject_toml is not None else Path.cwd()):
-            _apply_config(override_opt, _load_shared_config(found), type_conversion)
+            apply(_load_shared_config(explicit_config), base=explicit_config.absolute().parent)
+        elif found := _find_config_file(info.prog, start):
+            apply(_load_shared_config(found), base=found.parent)
         if isinstance(config, dict):
-            _apply_config(override_opt, config, type_conversion)
+            apply(config, base=start)
 
         res.append(
             _Config(
@@ -188,14 +241,24 @@ def _cli_args(info: TOMLFormatter[T], args: Sequence[str]) -> list[_Config[T]]:
     return res
 
 
-_NON_FORMAT_KEYS = frozenset({"inputs", "stdout", "check", "no_print_diff", "config"})
+_NON_FORMAT_KEYS = frozenset({"inputs", "stdout", "check", "no_print_diff", "explain", "config"})
 
 
-def _apply_config(opt: T, config: dict[str, Any], type_conversion: Mapping[str, Callable[[Any], Any]]) -> None:
+def _apply_config(
+    opt: T,
+    config: dict[str, Any],
+    *,
+    type_conversion: Mapping[str, Callable[[Any], Any]],
+    paths: Iterable[str],
+    base: Path,
+) -> None:
     for key in set(vars(opt).keys()) - _NON_FORMAT_KEYS:
         if key in config:
             raw = config[key]
-            setattr(opt, key, type_conversion[key](raw) if key in type_conversion else raw)
+            value = type_conversion[key](raw) if key in type_conversion else raw
+            if key in paths:  # relative to the config file, not to where the formatter runs
+                value = [str(base / path) for path in value]
+            setattr(opt, key, value)
 
 
 def _find_config_file(prog: str, start: Path) -> Path | None:
@@ -238,6 +301,11 @@ def build_cli(of: TOMLFormatter[T]) -> tuple[ArgumentParser, Mapping[str, Callab
         action="store_true",
         help="Flag indicating to print diff for the check mode",
     )
+    mode_group.add_argument(
+        "--explain",
+        action="store_true",
+        help="print the rule behind each change, e.g. 'project.dependencies[3]: pep508/normalize-spacing'",
+    )
     parser.add_argument(
         "--config",
         type=Path,
@@ -302,6 +370,18 @@ def build_cli(of: TOMLFormatter[T]) -> tuple[ArgumentParser, Mapping[str, Callab
         default=[],
         help="comma-separated list of key patterns to skip string wrapping (supports wildcards like '*.parse')",
     )
+    format_group.add_argument(
+        "--select",
+        type=list_argument,
+        default=[],
+        help="comma-separated list of rules to run, e.g. 'project,uv' (default: all)",
+    )
+    format_group.add_argument(
+        "--ignore",
+        type=list_argument,
+        default=[],
+        help="comma-separated list of rules to skip, e.g. 'tox.alias-normalization,project.classifiers.generate'",
+    )
     of.add_format_flags(format_group)
     type_conversion: Mapping[str, Callable[[Any], Any]] = {
         a.dest: cast("Callable[[Any], Any]", a.type)
@@ -367,12 +447,23 @@ def _display_name(path: Path | None) -> str:
 
 def _handle_one(info: TOMLFormatter[T], config: _Config[T]) -> bool:
     try:
-        formatted = info.format(config.toml, config.opt)
+        result = info.format(config.toml, config.opt)
     except ValueError as exc:  # the formatter rejected the content, e.g. an invalid project.version
         print(f"{_display_name(config.toml_filename)}: {exc}", file=sys.stderr)  # ruff: ignore[print]
         return True
+    if isinstance(result, str):  # formatters built before diagnostics existed return the text only
+        formatted, diagnostics, explanations = result, (), ()
+    else:  # formatters built before explanations existed have no such attribute
+        formatted, diagnostics = result.content, result.diagnostics
+        explanations = getattr(result, "explanations", ())
+    for diagnostic in diagnostics:  # left as written, so the rest of the file is still formatted
+        location = f"{_display_name(config.toml_filename)}:{diagnostic.line}:{diagnostic.column}"
+        print(f"{location}: {diagnostic.key_path}: {diagnostic.message}", file=sys.stderr)  # ruff: ignore[print]
+    for explanation in explanations:
+        name = _display_name(config.toml_filename)
+        print(f"{name}: {explanation.key_path}: {explanation.rule}", file=sys.stderr)  # ruff: ignore[print]
     before = config.toml
-    changed = before != formatted
+    changed = before != formatted or bool(diagnostics)
     if config.toml_filename is None or config.stdout:  # when reading from stdin or writing to stdout, print new format
         print(formatted, end="")  # ruff: ignore[print]
         return changed
@@ -383,7 +474,7 @@ def _handle_one(info: TOMLFormatter[T], config: _Config[T]) -> bool:
         return changed
     name = _display_name(config.toml_filename)
     diff: Iterable[str] = []
-    if changed:
+    if before != formatted:
         diff = difflib.unified_diff(before.splitlines(), formatted.splitlines(), fromfile=name, tofile=name)
 
     if diff:
@@ -424,7 +515,10 @@ _build_cli = build_cli
 
 __all__ = [
     "ArgumentGroup",
+    "Diagnostic",
+    "Explanation",
     "FmtNamespace",
+    "Formatted",
     "TOMLFormatter",
     "_build_cli",
     "build_cli",
@@ -2,6 +2,7 @@ from __future__ import annotations
 
 import os
 from io import StringIO
+from types import SimpleNamespace
 from typing import TYPE_CHECKING
 
 import pytest
@@ -425,6 +426,40 @@ def test_config_shared_custom_type(tmp_path: Path) -> None:
     assert dumb.read_text() == "\nextras = 'E'\nmagic = '1,2,3'"
 
 
+class PathDumb(Dumb):
+    @property
+    def path_options(self) -> tuple[str, ...]:
+        return ("tuple_magic",)
+
+
+def test_config_path_options_relative_to_config(tmp_path: Path) -> None:
+    shared = tmp_path / "shared"
+    shared.mkdir()
+    config_file = shared / "toml-fmt-common.toml"
+    config_file.write_text("tuple_magic = 'a.b'")
+    dumb = tmp_path / "dumb.toml"
+    dumb.write_text("[start.sub]\nextra = 'B'")
+    formatter = PathDumb()
+
+    run(formatter, ["E", str(dumb), "--config", str(config_file)])
+
+    assert formatter.last_format_opt is not None
+    assert formatter.last_format_opt.tuple_magic == [str(shared / "a"), str(shared / "b")]
+
+
+def test_config_path_options_relative_to_toml_file(tmp_path: Path) -> None:
+    sub = tmp_path / "sub"
+    sub.mkdir()
+    dumb = sub / "dumb.toml"
+    dumb.write_text("[start.sub]\ntuple_magic = 'a'")
+    formatter = PathDumb()
+
+    run(formatter, ["E", str(dumb)])
+
+    assert formatter.last_format_opt is not None
+    assert formatter.last_format_opt.tuple_magic == [str(sub / "a")]
+
+
 def test_shared_args_in_help(capsys: pytest.CaptureFixture[str]) -> None:
     with pytest.raises(SystemExit):
         run(Dumb(), ["--help"])
@@ -551,3 +586,45 @@ def test_format_rejection_reported_for_stdin(
     out, err = capsys.readouterr()
     assert not out
     assert err == "<stdin>: bad version\n"
+
+
+def test_format_diagnostics_reported(
+    capsys: pytest.CaptureFixture[str],
+    tmp_path: Path,
+    monkeypatch: pytest.MonkeyPatch,
+    mocker: MockerFixture,
+) -> None:
+    monkeypatch.chdir(tmp_path)
+    dumb = tmp_path / "dumb.toml"
+    dumb.write_text('deps = ["a,,"]\n')
+    diagnostic = SimpleNamespace(line=1, column=10, key_path="deps", message="Invalid version specifier")
+    formatted = SimpleNamespace(content='deps = ["a,,"]\n', diagnostics=[diagnostic])
+    mocker.patch.object(Dumb, "format", return_value=formatted)
+
+    assert run(Dumb(), ["E", "dumb.toml"]) == 1
+
+    out, err = capsys.readouterr()
+    assert out == "no change for dumb.toml\n"
+    assert err == "dumb.toml:1:10: deps: Invalid version specifier\n"
+    assert dumb.read_text() == 'deps = ["a,,"]\n'
+
+
+def test_format_explanations_reported(
+    capsys: pytest.CaptureFixture[str],
+    tmp_path: Path,
+    monkeypatch: pytest.MonkeyPatch,
+    mocker: MockerFixture,
+) -> None:
+    monkeypatch.chdir(tmp_path)
+    dumb = tmp_path / "dumb.toml"
+    dumb.write_text('deps = ["A >= 1"]\n')
+    explanation = SimpleNamespace(key_path="deps[0]", rule="pep508/normalize-spacing")
+    formatted = SimpleNamespace(content='deps = ["A>=1"]\n', diagnostics=[], explanations=[explanation])
+    format_mock = mocker.patch.object(Dumb, "format", return_value=formatted)
+
+    assert run(Dumb(), ["E", "dumb.toml", "--check", "--no-print-diff", "--explain"]) == 1
+
+    assert format_mock.call_args.args[1].explain is True
+    out, err = capsys.readouterr()
+    assert not out
+    assert err == "dumb.toml: deps[0]: pep508/normalize-spacing\n"
@@ -0,0 +1,302 @@
+//! Quick fixes for one entry at a time, and the legacy tox names they rename.
+//!
+//! Positions come from walking the syntax tree and adding up token lengths, since the tree is lossless and its own
+//! ranges count lines and columns rather than bytes.
+
+use std::cmp::Ordering;
+use std::ops::Range;
+
+use lexical_sort::natural_lexical_cmp;
+use tombi_syntax::SyntaxKind::{
+    ARRAY, ARRAY_OF_TABLE, BASIC_STRING, INLINE_TABLE, KEY_VALUE, KEYS, LITERAL_STRING, TABLE,
+};
+use tombi_syntax::SyntaxNode;
+
+use _tox_toml_fmt::global::alias_target;
+use common::array::sort;
+use common::formatted::TextEdit;
+use common::pep508::Requirement;
+use common::string::{get_string_token, get_string_value, load_text, strip_quotes};
+
+use crate::document::{Document, Kind};
+
+const PYPROJECT_DEPENDENCIES: &[&str] = &[
+    "build-system.requires",
+    "project.dependencies",
+    "project.optional-dependencies.*",
+    "dependency-groups.*",
+    "tool.tox.requires",
+    "tool.tox.env_run_base.deps",
+    "tool.tox.env_pkg_base.deps",
+    "tool.tox.env.*.deps",
+];
+
+const TOX_DEPENDENCIES: &[&str] = &["requires", "env_run_base.deps", "env_pkg_base.deps", "env.*.deps"];
+
+pub struct Action {
+    pub title: String,
+    pub edits: Vec<TextEdit>,
+}
+
+/// A key spelled the way `tox.ini` did, with the byte range of the key itself.
+pub struct LegacyAlias {
+    pub range: Range<usize>,
+    pub alias: String,
+    pub name: &'static str,
+}
+
+/// A key-value set directly in a table (or at the root), not inside an inline table or array.
+struct Entry {
+    node: SyntaxNode,
+    start: usize,
+    /// Header keys then the entry's own keys, as written (quotes kept).
+    path: Vec<String>,
+    /// Bytes of the last key, the one a rename replaces.
+    key: Range<usize>,
+}
+
+impl Entry {
+    fn end(&self) -> usize {
+        self.start + self.node.to_string().len()
+    }
+
+    fn matches(&self, pattern: &str) -> bool {
+        let parts: Vec<&str> = pattern.split('.').collect();
+        parts.len() == self.path.len()
+            && parts
+                .iter()
+                .zip(&self.path)
+                .all(|(part, key)| *part == "*" || *part == strip_quotes(key))
+    }
+
+    fn value(&self, kind: tombi_syntax::SyntaxKind) -> Option<SyntaxNode> {
+        self.node.descendants().find(|node| {
+            node.kind() == kind
+                && node
+                    .ancestors()
+                    .skip(1)
+                    .take_while(|a| a != &self.node)
+                    .all(|a| a.kind() != ARRAY)
+        })
+    }
+}
+
+/// Calls `visit` with every node and its byte offset, in document order, and returns where `node` ends.
+fn walk(node: &SyntaxNode, offset: usize, visit: &mut impl FnMut(&SyntaxNode, usize)) -> usize {
+    visit(node, offset);
+    let mut offset = offset;
+    for child in node.children_with_tokens() {
+        if let Some(child) = child.as_node() {
+            offset = walk(child, offset, visit);
+        } else if let Some(token) = child.as_token() {
+            offset += token.text().len();
+        }
+    }
+    offset
+}
+
+/// Key parts with their byte ranges in `keys`, split at dots outside quotes.
+fn split_keys(keys: &str) -> Vec<(Range<usize>, String)> {
+    let mut parts = vec![];
+    let mut quote = None;
+    let mut start = 0;
+    for (at, c) in keys.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(open)) if c == open => quote = None,
+            ('.', None) => {
+                parts.push(start..at);
+                start = at + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(start..keys.len());
+    parts
+        .into_iter()
+        .map(|range| {
+            let text = &keys[range.clone()];
+            let trimmed = text.trim();
+            let lead = text.len() - text.trim_start().len();
+            (
+                range.start + lead..range.start + lead + trimmed.len(),
+                trimmed.to_string(),
+            )
+        })
+        .collect()
+}
+
+fn keys_of(node: &SyntaxNode) -> Option<SyntaxNode> {
+    node.children().find(|child| child.kind() == KEYS)
+}
+
+fn entries(text: &str) -> Vec<Entry> {
+    let root = tombi_parser::parse(text).syntax_node();
+    let mut entries = vec![];
+    walk(&root, 0, &mut |node, start| {
+        if node.kind() != KEY_VALUE || node.ancestors().any(|a| matches!(a.kind(), INLINE_TABLE | ARRAY)) {
+            return;
+        }
+        let Some(keys) = keys_of(node) else {
+            return;
+        };
+        let header = node
+            .ancestors()
+            .find(|a| matches!(a.kind(), TABLE | ARRAY_OF_TABLE))
+            .and_then(|table| keys_of(&table))
+            .map(|keys| split_keys(&keys.to_string()))
+            .unwrap_or_default();
+        let keys_text = keys.to_string();
+        let keys_start = start + node.to_string().find(&keys_text).unwrap_or(0);
+        let own = split_keys(&keys_text);
+        let last = own.last().map_or(0..0, |(range, _)| range.clone());
+        entries.push(Entry {
+            node: node.clone(),
+            start,
+            path: header.into_iter().chain(own).map(|(_, key)| key).collect(),
+            key: keys_start + last.start..keys_start + last.end,
+        });
+    });
+    entries
+}
+
+/// Keys set in tox's root or environment tables under their `tox.ini` names.
+#[must_use]
+pub fn legacy_aliases(document: &Document) -> Vec<LegacyAlias> {
+    let prefix = document.kind.tox_prefix();
+    entries(&document.text)
+        .into_iter()
+        .filter_map(|entry| {
+            let (key, table) = entry.path.split_last()?;
+            let name = alias_target(&table.join("."), key, prefix)?;
+            Some(LegacyAlias {
+                range: entry.key,
+                alias: key.clone(),
+                name,
+            })
+        })
+        .collect()
+}
+
+const fn overlaps(range: &Range<usize>, start: usize, end: usize) -> bool {
+    start <= range.end && range.start <= end
+}
+
+/// Fixes for the entries `selected` touches: rename a legacy tox key, normalize a requirement, sort a dependency
+/// array.
+#[must_use]
+pub fn code_actions(document: &Document, selected: &Range<usize>, keep_full_version: bool) -> Vec<Action> {
+    let aliases = legacy_aliases(document);
+    let mut actions: Vec<Action> = aliases
+        .into_iter()
+        .filter(|alias| overlaps(selected, alias.range.start, alias.range.end))
+        .map(|alias| Action {
+            title: format!("Rename `{}` to `{}`", alias.alias, alias.name),
+            edits: vec![TextEdit {
+                range: alias.range,
+                new_text: alias.name.to_string(),
+            }],
+        })
+        .collect();
+    let patterns = match document.kind {
+        Kind::Pyproject => PYPROJECT_DEPENDENCIES,
+        Kind::Tox => TOX_DEPENDENCIES,
+    };
+    for entry in entries(&document.text) {
+        if !overlaps(selected, entry.start, entry.end()) || !patterns.iter().any(|pattern| entry.matches(pattern)) {
+            continue;
+        }
+        let Some(array) = entry.value(ARRAY) else {
+            continue;
+        };
+        let array_start = entry.start + entry.node.to_string().find(&array.to_string()).unwrap_or(0);
+        actions.extend(normalize_requirements(&array, array_start, selected, keep_full_version));
+        actions.extend(sort_dependencies(&array, array_start, &entry.path.join(".")));
+    }
+    actions
+}
+
+fn normalize_requirements(
+    array: &SyntaxNode,
+    array_start: usize,
+    selected: &Range<usize>,
+    keep_full_version: bool,
+) -> Vec<Action> {
+    let mut actions = vec![];
+    walk(array, array_start, &mut |node, start| {
+        let direct = node
+            .ancestors()
+            .skip(1)
+            .find(|a| matches!(a.kind(), ARRAY | INLINE_TABLE))
+            .as_ref()
+            == Some(array);
+        if !matches!(node.kind(), BASIC_STRING | LITERAL_STRING) || !direct {
+            return;
+        }
+        let (Some(token), Some(value)) = (get_string_token(node), get_string_value(node)) else {
+            return;
+        };
+        let token_start = start + node.to_string().find(token.text()).unwrap_or(0);
+        let token_range = token_start..token_start + token.text().len();
+        if !overlaps(selected, token_range.start, token_range.end) {
+            return;
+        }
+        let Ok(requirement) = Requirement::new(&value) else {
+            return;
+        };
+        let normalized = requirement.normalize(keep_full_version).to_string();
+        if normalized != value {
+            actions.push(Action {
+                title: format!("Normalize requirement `{value}` to `{normalized}`"),
+                edits: vec![TextEdit {
+                    range: token_range,
+                    new_text: quote(&normalized, node.kind()),
+                }],
+            });
+        }
+    });
+    actions
+}
+
+/// Keeps a literal string literal when it can be, otherwise writes a basic string.
+fn quote(value: &str, kind: tombi_syntax::SyntaxKind) -> String {
+    if kind == LITERAL_STRING && !value.contains('\'') {
+        format!("'{value}'")
+    } else {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+/// Sorts by canonical package name, as the formatter does for `project.dependencies`.
+fn sort_dependencies(array: &SyntaxNode, array_start: usize, key_path: &str) -> Option<Action> {
+    let before = array.to_string();
+    let root = tombi_parser::parse(&format!("a = {before}"))
+        .syntax_node()
+        .clone_for_update();
+    let sorted = root.descendants().find(|node| node.kind() == ARRAY)?;
+    sort::<(String, String), _, _>(
+        &sorted,
+        |node| {
+            get_string_token(node).map(|token| {
+                let value = load_text(token.text(), node.kind());
+                let name = Requirement::new(&value).map_or_else(|_| value.to_lowercase(), |r| r.canonical_name());
+                (name, value)
+            })
+        },
+        &|lhs, rhs| match natural_lexical_cmp(&lhs.0, &rhs.0) {
+            Ordering::Equal => natural_lexical_cmp(&lhs.1, &rhs.1),
+            order => order,
+        },
+    );
+    let after = sorted.to_string();
+    (after != before).then(|| Action {
+        title: format!("Sort `{key_path}`"),
+        edits: TextEdit::between(&before, &after)
+            .into_iter()
+            .map(|edit| TextEdit {
+                range: array_start + edit.range.start..array_start + edit.range.end,
+                new_text: edit.new_text,
+            })
+            .collect(),
+    })
+}
@@ -0,0 +1,119 @@
+use std::path::PathBuf;
+
+use lsp_types::{Position, Uri};
+
+/// Which formatter a document belongs to, from its file name; other TOML files are left alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Pyproject,
+    Tox,
+}
+
+impl Kind {
+    #[must_use]
+    pub fn from_path(path: &str) -> Option<Self> {
+        match path.rsplit('/').next()? {
+            "pyproject.toml" => Some(Self::Pyproject),
+            "tox.toml" => Some(Self::Tox),
+            _ => None,
+        }
+    }
+
+    /// Where the tox configuration lives in the document.
+    #[must_use]
+    pub const fn tox_prefix(self) -> &'static str {
+        match self {
+            Self::Pyproject => "tool.tox",
+            Self::Tox => "",
+        }
+    }
+}
+
+pub struct Document {
+    pub kind: Kind,
+    /// On-disk location, used to find the shared config file; `None` for unsaved buffers.
+    pub path: Option<PathBuf>,
+    pub text: String,
+}
+
+impl Document {
+    #[must_use]
+    pub fn new(uri: &Uri, text: String) -> Option<Self> {
+        let path = percent_decode(uri.path().as_str());
+        Some(Self {
+            kind: Kind::from_path(&path)?,
+            path: uri
+                .scheme()
+                .is_some_and(|scheme| scheme.as_str().eq_ignore_ascii_case("file"))
+                .then(|| PathBuf::from(path)),
+            text,
+        })
+    }
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut at = 0;
+    while at < bytes.len() {
+        let hex = bytes
+            .get(at + 1..at + 3)
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (bytes[at], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                at += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                at += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Converts between byte offsets, which the formatter works in, and LSP positions, which count UTF-16 code units.
+pub struct LineIndex<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    #[must_use]
+    pub fn new(text: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(at, _)| at + 1))
+            .collect();
+        Self { text, line_starts }
+    }
+
+    #[must_use]
+    pub fn position(&self, offset: usize) -> Position {
+        let offset = offset.min(self.text.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let character = self.text[self.line_starts[line]..offset].encode_utf16().count();
+        Position::new(
+            u32::try_from(line).unwrap_or(u32::MAX),
+            u32::try_from(character).unwrap_or(u32::MAX),
+        )
+    }
+
+    /// Positions past the end of a line clamp to its end, and past the last line to the end of the text.
+    #[must_use]
+    pub fn offset(&self, position: Position) -> usize {
+        let Some(&start) = self.line_starts.get(position.line as usize) else {
+            return self.text.len();
+        };
+        let line = &self.text[start..];
+        let line = &line[..line.find('\n').unwrap_or(line.len())];
+        let mut units = 0;
+        for (at, c) in line.char_indices() {
+            if units >= position.character as usize {
+                return start + at;
+            }
+            units += c.len_utf16();
+        }
+        start + line.len()
+    }
+}
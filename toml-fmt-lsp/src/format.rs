@@ -0,0 +1,39 @@
+use _pyproject_fmt::cli::PyprojectFmt;
+use _tox_toml_fmt::cli::ToxTomlFmt;
+use common::cli::{Formatter, options_for};
+use common::formatted::{Formatted, FormattedRange};
+use common::range::Selection;
+
+use crate::document::{Document, Kind};
+
+/// Formats like the binary would for the file on disk, config file and override section included.
+pub fn format(document: &Document) -> Result<Formatted, String> {
+    match document.kind {
+        Kind::Pyproject => format_with::<PyprojectFmt>(document),
+        Kind::Tox => format_with::<ToxTomlFmt>(document),
+    }
+}
+
+pub fn format_range(document: &Document, selection: &Selection) -> Result<FormattedRange, String> {
+    match document.kind {
+        Kind::Pyproject => format_range_with::<PyprojectFmt>(document, selection),
+        Kind::Tox => format_range_with::<ToxTomlFmt>(document, selection),
+    }
+}
+
+/// Whether normalizing a requirement keeps redundant `.0` version suffixes, as configured for the formatter.
+pub fn keep_full_version(document: &Document) -> bool {
+    document.kind == Kind::Pyproject
+        && options_for::<PyprojectFmt>(document.path.as_deref(), &document.text)
+            .is_ok_and(|(_, args)| args.keep_full_version)
+}
+
+fn format_with<F: Formatter>(document: &Document) -> Result<Formatted, String> {
+    let (shared, args) = options_for::<F>(document.path.as_deref(), &document.text)?;
+    F::format(&document.text, &shared, &args)
+}
+
+fn format_range_with<F: Formatter>(document: &Document, selection: &Selection) -> Result<FormattedRange, String> {
+    let (shared, args) = options_for::<F>(document.path.as_deref(), &document.text)?;
+    F::format_range(&document.text, selection, &shared, &args)
+}
@@ -0,0 +1,16 @@
+//! A language server for `pyproject.toml` and `tox.toml` files.
+//!
+//! Editors get the same output as the `pyproject-fmt` and `tox-toml-fmt` binaries, config file and override section
+//! included, as formatting and range formatting edits. Open documents are checked as they change: values the formatter
+//! could not parse are published as errors, and tox keys still spelled the `tox.ini` way as deprecation hints. Code
+//! actions rename those keys, normalize a single requirement and sort a dependency array.
+
+mod actions;
+mod document;
+mod format;
+mod server;
+
+#[cfg(test)]
+mod tests;
+
+pub use server::run;
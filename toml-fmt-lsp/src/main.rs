@@ -0,0 +1,19 @@
+use std::process::ExitCode;
+
+use lsp_server::Connection;
+
+fn main() -> ExitCode {
+    let (connection, io_threads) = Connection::stdio();
+    let served = toml_fmt_lsp::run(&connection);
+    drop(connection);
+    match served
+        .map_err(|err| err.to_string())
+        .and_then(|()| io_threads.join().map_err(|err| err.to_string()))
+    {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("toml-fmt-lsp: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
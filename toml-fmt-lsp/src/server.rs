@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{CodeActionRequest, Formatting, RangeFormatting, Request as _};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, CodeActionProviderCapability,
+    DiagnosticSeverity, DiagnosticTag, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, DocumentFormattingParams, DocumentRangeFormattingParams, NumberOrString, OneOf,
+    PublishDiagnosticsParams, Range, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, Uri,
+    WorkspaceEdit,
+};
+use serde_json::Value;
+
+use common::formatted::TextEdit;
+use common::range::Selection;
+
+use crate::actions::{code_actions, legacy_aliases};
+use crate::document::{Document, LineIndex};
+use crate::format::{format, format_range, keep_full_version};
+
+/// Serves one client over `connection` until it asks to shut down.
+///
+/// # Errors
+///
+/// Returns an error when the handshake fails or the client goes away without shutting down.
+pub fn run(connection: &Connection) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        document_range_formatting_provider: Some(OneOf::Left(true)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        ..ServerCapabilities::default()
+    };
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+    let mut server = Server::default();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                connection
+                    .sender
+                    .send(Message::Response(server.handle_request(request)))?;
+            }
+            Message::Notification(notification) => {
+                if let Some(published) = server.handle_notification(notification) {
+                    connection.sender.send(Message::Notification(published))?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    Err("client disconnected without shutting down".into())
+}
+
+#[derive(Default)]
+struct Server {
+    documents: HashMap<Uri, Document>,
+}
+
+type Handled = Result<Value, (ErrorCode, String)>;
+
+fn params<T: serde::de::DeserializeOwned>(request: Request) -> Result<T, (ErrorCode, String)> {
+    serde_json::from_value(request.params).map_err(|err| (ErrorCode::InvalidParams, err.to_string()))
+}
+
+fn to_value(value: impl serde::Serialize) -> Handled {
+    serde_json::to_value(value).map_err(|err| (ErrorCode::InternalError, err.to_string()))
+}
+
+fn to_lsp_edits(index: &LineIndex<'_>, edits: Vec<TextEdit>) -> Vec<lsp_types::TextEdit> {
+    edits
+        .into_iter()
+        .map(|edit| lsp_types::TextEdit::new(range(index, &edit.range), edit.new_text))
+        .collect()
+}
+
+fn range(index: &LineIndex<'_>, range: &std::ops::Range<usize>) -> Range {
+    Range::new(index.position(range.start), index.position(range.end))
+}
+
+impl Server {
+    fn handle_request(&self, request: Request) -> Response {
+        let id = request.id.clone();
+        let handled = match request.method.as_str() {
+            Formatting::METHOD => params(request).and_then(|params| self.formatting(&params)),
+            RangeFormatting::METHOD => params(request).and_then(|params| self.range_formatting(&params)),
+            CodeActionRequest::METHOD => params(request).and_then(|params| self.code_action(&params)),
+            method => Err((ErrorCode::MethodNotFound, format!("unhandled method {method}"))),
+        };
+        match handled {
+            Ok(value) => Response::new_ok(id, value),
+            Err((code, message)) => Response::new_err(id, code as i32, message),
+        }
+    }
+
+    /// Returns the diagnostics to publish when a document was opened, changed or closed.
+    fn handle_notification(&mut self, notification: Notification) -> Option<Notification> {
+        let uri = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams = serde_json::from_value(notification.params).ok()?;
+                let document = Document::new(&params.text_document.uri, params.text_document.text)?;
+                self.documents.insert(params.text_document.uri.clone(), document);
+                params.text_document.uri
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams = serde_json::from_value(notification.params).ok()?;
+                let document = self.documents.get_mut(&params.text_document.uri)?;
+                document.text = params.content_changes.into_iter().last()?.text;
+                params.text_document.uri
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams = serde_json::from_value(notification.params).ok()?;
+                self.documents.remove(&params.text_document.uri)?;
+                return Some(publish(params.text_document.uri, vec![]));
+            }
+            _ => return None,
+        };
+        let diagnostics = diagnostics(self.documents.get(&uri)?);
+        Some(publish(uri, diagnostics))
+    }
+
+    fn formatting(&self, params: &DocumentFormattingParams) -> Handled {
+        let Some(document) = self.documents.get(&params.text_document.uri) else {
+            return Ok(Value::Null);
+        };
+        let formatted = format(document).map_err(|err| (ErrorCode::RequestFailed, err))?;
+        let index = LineIndex::new(&document.text);
+        to_value(to_lsp_edits(&index, formatted.edits(&document.text)))
+    }
+
+    fn range_formatting(&self, params: &DocumentRangeFormattingParams) -> Handled {
+        let Some(document) = self.documents.get(&params.text_document.uri) else {
+            return Ok(Value::Null);
+        };
+        let index = LineIndex::new(&document.text);
+        let selection = Selection::Bytes(index.offset(params.range.start)..index.offset(params.range.end));
+        let formatted = format_range(document, &selection).map_err(|err| (ErrorCode::RequestFailed, err))?;
+        to_value(to_lsp_edits(&index, formatted.edits))
+    }
+
+    // `Uri` caches its parsed parts in cells, which doesn't touch its hash or equality.
+    #[allow(clippy::mutable_key_type)]
+    fn code_action(&self, params: &CodeActionParams) -> Handled {
+        let Some(document) = self.documents.get(&params.text_document.uri) else {
+            return Ok(Value::Null);
+        };
+        let index = LineIndex::new(&document.text);
+        let selected = index.offset(params.range.start)..index.offset(params.range.end);
+        let actions: Vec<CodeActionOrCommand> = code_actions(document, &selected, keep_full_version(document))
+            .into_iter()
+            .map(|action| {
+                let changes = HashMap::from([(params.text_document.uri.clone(), to_lsp_edits(&index, action.edits))]);
+                CodeActionOrCommand::CodeAction(CodeAction {
+                    title: action.title,
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    edit: Some(WorkspaceEdit::new(changes)),
+                    ..CodeAction::default()
+                })
+            })
+            .collect();
+        to_value(actions)
+    }
+}
+
+fn publish(uri: Uri, diagnostics: Vec<lsp_types::Diagnostic>) -> Notification {
+    let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+    Notification::new(PublishDiagnostics::METHOD.to_string(), params)
+}
+
+/// What the formatter reports and leaves as written, a rejected document, and legacy tox names.
+fn diagnostics(document: &Document) -> Vec<lsp_types::Diagnostic> {
+    let index = LineIndex::new(&document.text);
+    let source = match document.kind {
+        crate::document::Kind::Pyproject => "pyproject-fmt",
+        crate::document::Kind::Tox => "tox-toml-fmt",
+    };
+    let diagnostic = |range: Range, severity, message: String| lsp_types::Diagnostic {
+        range,
+        severity: Some(severity),
+        source: Some(source.to_string()),
+        message,
+        ..lsp_types::Diagnostic::default()
+    };
+    let mut diagnostics = match format(document) {
+        Ok(formatted) => formatted
+            .diagnostics
+            .into_iter()
+            .map(|found| {
+                let span = found.offset..found.offset + found.value.len();
+                diagnostic(
+                    range(&index, &span),
+                    DiagnosticSeverity::ERROR,
+                    format!("{}: {}", found.key_path, found.message),
+                )
+            })
+            .collect(),
+        Err(err) => vec![diagnostic(Range::default(), DiagnosticSeverity::ERROR, err)],
+    };
+    diagnostics.extend(legacy_aliases(document).into_iter().map(|alias| lsp_types::Diagnostic {
+        code: Some(NumberOrString::String(String::from("legacy-alias"))),
+        tags: Some(vec![DiagnosticTag::DEPRECATED]),
+        ..diagnostic(
+            range(&index, &alias.range),
+            DiagnosticSeverity::HINT,
+            format!("`{}` is a legacy name, use `{}`", alias.alias, alias.name),
+        )
+    }));
+    diagnostics
+}
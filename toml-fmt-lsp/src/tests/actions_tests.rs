@@ -0,0 +1,143 @@
+use indoc::indoc;
+
+use common::formatted::TextEdit;
+
+use crate::actions::{code_actions, legacy_aliases};
+use crate::document::{Document, Kind};
+
+fn document(kind: Kind, text: &str) -> Document {
+    Document {
+        kind,
+        path: None,
+        text: text.to_string(),
+    }
+}
+
+fn apply(document: &Document, title: &str, selected: std::ops::Range<usize>) -> String {
+    let actions = code_actions(document, &selected, false);
+    let action = actions.iter().find(|action| action.title == title).unwrap_or_else(|| {
+        panic!(
+            "no `{title}` in {:?}",
+            actions.iter().map(|a| &a.title).collect::<Vec<_>>()
+        )
+    });
+    TextEdit::apply(&document.text, &action.edits)
+}
+
+#[test]
+fn test_legacy_aliases_in_tox_root_and_env_tables() {
+    let text = indoc! {r#"
+        envlist = ["py"]
+
+        [env.py]
+        setenv = { A = "1" }
+        envlist = ["not-an-alias-here"]
+
+        [env_run_base]
+        passenv = ["HOME"]
+    "#};
+    let found: Vec<_> = legacy_aliases(&document(Kind::Tox, text))
+        .into_iter()
+        .map(|alias| (&text[alias.range], alias.name))
+        .collect();
+    assert_eq!(
+        found,
+        [("envlist", "env_list"), ("setenv", "set_env"), ("passenv", "pass_env")]
+    );
+}
+
+#[test]
+fn test_legacy_aliases_under_tool_tox_only() {
+    let text = indoc! {r#"
+        [tool.tox]
+        envlist = ["py"]
+        env.py.basepython = "python3"
+
+        [tool.other]
+        envlist = ["py"]
+    "#};
+    let found: Vec<_> = legacy_aliases(&document(Kind::Pyproject, text))
+        .into_iter()
+        .map(|alias| (alias.range.start, alias.alias, alias.name))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (text.find("envlist").unwrap(), String::from("envlist"), "env_list"),
+            (
+                text.find("basepython").unwrap(),
+                String::from("basepython"),
+                "base_python"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_code_action_renames_selected_alias() {
+    let text = "envlist = [\"py\"]\nminversion = \"4\"\n";
+    let document = document(Kind::Tox, text);
+    assert_eq!(
+        apply(&document, "Rename `envlist` to `env_list`", 0..0),
+        "env_list = [\"py\"]\nminversion = \"4\"\n"
+    );
+    let titles: Vec<String> = code_actions(&document, &(0..0), false)
+        .into_iter()
+        .map(|action| action.title)
+        .collect();
+    assert_eq!(titles, ["Rename `envlist` to `env_list`"]);
+}
+
+#[test]
+fn test_code_action_normalizes_requirement_under_cursor() {
+    let text = indoc! {r#"
+        [project]
+        dependencies = ["requests >= 2.0", 'click >= 8.0.0']
+    "#};
+    let document = document(Kind::Pyproject, text);
+    let at = text.find("click").unwrap();
+    assert_eq!(
+        apply(
+            &document,
+            "Normalize requirement `click >= 8.0.0` to `click>=8`",
+            at..at
+        ),
+        "[project]\ndependencies = [\"requests >= 2.0\", 'click>=8']\n"
+    );
+    let titles: Vec<String> = code_actions(&document, &(at..at), false)
+        .into_iter()
+        .map(|action| action.title)
+        .collect();
+    assert!(!titles.iter().any(|title| title.contains("requests")), "{titles:?}");
+}
+
+#[test]
+fn test_code_action_sorts_dependency_array_by_name() {
+    let text = indoc! {r#"
+        [dependency-groups]
+        test = [
+          "Pytest>=8",  # runner
+          "covdefaults",
+        ]
+    "#};
+    let got = apply(
+        &document(Kind::Pyproject, text),
+        "Sort `dependency-groups.test`",
+        0..text.len(),
+    );
+    assert!(got.starts_with("[dependency-groups]\ntest = [\n"), "{got}");
+    assert!(
+        got.find("\"covdefaults\"").unwrap() < got.find("\"Pytest>=8\"").unwrap(),
+        "{got}"
+    );
+    assert!(
+        got.find("# runner").unwrap() > got.find("\"Pytest>=8\"").unwrap(),
+        "{got}"
+    );
+}
+
+#[test]
+fn test_code_action_ignores_arrays_that_are_not_dependencies() {
+    let text = "[project]\nkeywords = [\"b\", \"a\"]\n";
+    assert!(code_actions(&document(Kind::Pyproject, text), &(0..text.len()), false).is_empty());
+}
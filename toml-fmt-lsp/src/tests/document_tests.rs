@@ -0,0 +1,53 @@
+use lsp_types::{Position, Uri};
+
+use crate::document::{Document, Kind, LineIndex};
+
+#[test]
+fn test_kind_from_file_name() {
+    assert_eq!(Kind::from_path("/repo/pyproject.toml"), Some(Kind::Pyproject));
+    assert_eq!(Kind::from_path("/repo/tox.toml"), Some(Kind::Tox));
+    assert_eq!(Kind::from_path("/repo/Cargo.toml"), None);
+    assert_eq!(Kind::from_path("/repo/my-pyproject.toml"), None);
+}
+
+#[test]
+fn test_document_from_file_uri_decodes_path() {
+    let uri: Uri = "file:///my%20repo/pyproject.toml".parse().unwrap();
+    let document = Document::new(&uri, String::new()).unwrap();
+    assert_eq!(document.kind, Kind::Pyproject);
+    assert_eq!(document.path.unwrap().to_str(), Some("/my repo/pyproject.toml"));
+}
+
+#[test]
+fn test_document_unsaved_buffer_has_no_path() {
+    let uri: Uri = "untitled:/tox.toml".parse().unwrap();
+    let document = Document::new(&uri, String::new()).unwrap();
+    assert_eq!(document.kind, Kind::Tox);
+    assert!(document.path.is_none());
+}
+
+#[test]
+fn test_document_other_toml_is_ignored() {
+    let uri: Uri = "file:///repo/ruff.toml".parse().unwrap();
+    assert!(Document::new(&uri, String::new()).is_none());
+}
+
+#[test]
+fn test_line_index_counts_utf16_code_units() {
+    let text = "a = \"é😀\"\nb = 1\n";
+    let index = LineIndex::new(text);
+    let after_emoji = text.find('"').unwrap() + 1 + 'é'.len_utf8() + '😀'.len_utf8();
+    assert_eq!(index.position(after_emoji), Position::new(0, 8));
+    assert_eq!(index.offset(Position::new(0, 8)), after_emoji);
+    assert_eq!(index.position(text.find('b').unwrap()), Position::new(1, 0));
+    assert_eq!(index.offset(Position::new(1, 0)), text.find('b').unwrap());
+}
+
+#[test]
+fn test_line_index_clamps_past_the_end() {
+    let text = "a = 1\nb = 2";
+    let index = LineIndex::new(text);
+    assert_eq!(index.offset(Position::new(0, 99)), 5);
+    assert_eq!(index.offset(Position::new(9, 0)), text.len());
+    assert_eq!(index.position(99), Position::new(1, 5));
+}
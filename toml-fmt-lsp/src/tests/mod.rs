@@ -0,0 +1,3 @@
+pub mod actions_tests;
+pub mod document_tests;
+pub mod server_tests;
@@ -0,0 +1,189 @@
+use std::thread;
+
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidOpenTextDocument, Exit, Initialized, Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{CodeActionRequest, Formatting, RangeFormatting, Request as _, Shutdown};
+use lsp_types::{
+    CodeActionOrCommand, DiagnosticSeverity, DiagnosticTag, Position, PublishDiagnosticsParams, Range, TextEdit,
+};
+use serde_json::{Value, json};
+
+use crate::run;
+
+struct Client {
+    connection: Connection,
+    next_id: i32,
+}
+
+impl Client {
+    fn start() -> (Self, thread::JoinHandle<()>) {
+        let (server, connection) = Connection::memory();
+        let handle = thread::spawn(move || run(&server).unwrap());
+        let mut client = Self { connection, next_id: 0 };
+        client.request("initialize", json!({"capabilities": {}}));
+        client.notify(Initialized::METHOD, json!({}));
+        (client, handle)
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Response {
+        self.next_id += 1;
+        let id = RequestId::from(self.next_id);
+        let request = Request::new(id.clone(), method.to_string(), params);
+        self.connection.sender.send(Message::Request(request)).unwrap();
+        match self.connection.receiver.recv().unwrap() {
+            Message::Response(response) if response.id == id => response,
+            other => panic!("expected response to {method}, got {other:?}"),
+        }
+    }
+
+    fn result<T: serde::de::DeserializeOwned>(&mut self, method: &str, params: Value) -> T {
+        let response = self.request(method, params);
+        assert!(response.error.is_none(), "{:?}", response.error);
+        serde_json::from_value(response.result.unwrap()).unwrap()
+    }
+
+    fn notify(&self, method: &str, params: Value) {
+        let notification = Notification::new(method.to_string(), params);
+        self.connection
+            .sender
+            .send(Message::Notification(notification))
+            .unwrap();
+    }
+
+    fn diagnostics(&self) -> PublishDiagnosticsParams {
+        match self.connection.receiver.recv().unwrap() {
+            Message::Notification(notification) if notification.method == PublishDiagnostics::METHOD => {
+                serde_json::from_value(notification.params).unwrap()
+            }
+            other => panic!("expected diagnostics, got {other:?}"),
+        }
+    }
+
+    fn open(&self, uri: &str, text: &str) -> PublishDiagnosticsParams {
+        let document = json!({"uri": uri, "languageId": "toml", "version": 1, "text": text});
+        self.notify(DidOpenTextDocument::METHOD, json!({ "textDocument": document }));
+        self.diagnostics()
+    }
+
+    fn stop(mut self, handle: thread::JoinHandle<()>) {
+        self.request(Shutdown::METHOD, Value::Null);
+        self.notify(Exit::METHOD, Value::Null);
+        handle.join().unwrap();
+    }
+}
+
+fn file_uri(name: &str) -> String {
+    let dir = std::env::temp_dir().join(format!("toml-fmt-lsp-{}", std::process::id()));
+    format!("file://{}", dir.join(name).display())
+}
+
+#[test]
+fn test_server_formats_and_reports_pyproject() {
+    let (mut client, handle) = Client::start();
+    let uri = file_uri("pyproject.toml");
+    let text = "[project]\nname=\"demo\"\ndependencies = [\"Requests>=2.0,,\", \"Click\"]\n";
+
+    let published = client.open(&uri, text);
+    assert_eq!(published.diagnostics.len(), 1);
+    let diagnostic = &published.diagnostics[0];
+    assert_eq!(diagnostic.range, Range::new(Position::new(2, 17), Position::new(2, 32)));
+    assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::ERROR));
+    assert_eq!(diagnostic.source.as_deref(), Some("pyproject-fmt"));
+    assert!(
+        diagnostic.message.starts_with("project.dependencies: "),
+        "{}",
+        diagnostic.message
+    );
+
+    let document = json!({ "uri": uri });
+    let edits: Vec<TextEdit> = client.result(
+        Formatting::METHOD,
+        json!({"textDocument": document, "options": {"tabSize": 2, "insertSpaces": true}}),
+    );
+    assert!(edits.iter().any(|edit| edit.range.start.line == 1), "{edits:?}");
+
+    let selected = Range::new(Position::new(1, 0), Position::new(1, 4));
+    let edits: Vec<TextEdit> = client.result(
+        RangeFormatting::METHOD,
+        json!({"textDocument": document, "range": selected, "options": {"tabSize": 2, "insertSpaces": true}}),
+    );
+    assert!(!edits.is_empty());
+    assert!(edits.iter().all(|edit| edit.range.start.line >= 1), "{edits:?}");
+
+    client.notify(
+        DidChangeTextDocument::METHOD,
+        json!({"textDocument": {"uri": uri, "version": 2}, "contentChanges": [{"text": "[project]\nname = \"demo\"\n"}]}),
+    );
+    assert!(client.diagnostics().diagnostics.is_empty());
+
+    client.stop(handle);
+}
+
+#[test]
+fn test_server_offers_legacy_alias_rename_for_tox() {
+    let (mut client, handle) = Client::start();
+    let uri = file_uri("tox.toml");
+
+    let published = client.open(&uri, "envlist = [\"py\"]\n");
+    assert_eq!(published.diagnostics.len(), 1);
+    let hint = &published.diagnostics[0];
+    assert_eq!(hint.range, Range::new(Position::new(0, 0), Position::new(0, 7)));
+    assert_eq!(hint.severity, Some(DiagnosticSeverity::HINT));
+    assert_eq!(hint.tags, Some(vec![DiagnosticTag::DEPRECATED]));
+
+    let cursor = Range::new(Position::new(0, 2), Position::new(0, 2));
+    let actions: Vec<CodeActionOrCommand> = client.result(
+        CodeActionRequest::METHOD,
+        json!({"textDocument": {"uri": uri}, "range": cursor, "context": {"diagnostics": []}}),
+    );
+    let [CodeActionOrCommand::CodeAction(action)] = actions.as_slice() else {
+        panic!("{actions:?}");
+    };
+    assert_eq!(action.title, "Rename `envlist` to `env_list`");
+    let edit = action.edit.as_ref().unwrap();
+    let edits: Vec<&TextEdit> = edit
+        .changes
+        .iter()
+        .flat_map(|changes| changes.values().flatten())
+        .collect();
+    assert_eq!(
+        edits,
+        [&TextEdit::new(
+            Range::new(Position::new(0, 0), Position::new(0, 7)),
+            String::from("env_list")
+        )]
+    );
+
+    client.stop(handle);
+}
+
+#[test]
+fn test_server_rejects_unknown_requests_and_ignores_other_files() {
+    let (mut client, handle) = Client::start();
+    let response = client.request("textDocument/hover", json!({}));
+    assert_eq!(
+        response.error.unwrap().code,
+        lsp_server::ErrorCode::MethodNotFound as i32
+    );
+
+    let response = client.request(Formatting::METHOD, json!({"textDocument": 1}));
+    assert_eq!(
+        response.error.unwrap().code,
+        lsp_server::ErrorCode::InvalidParams as i32
+    );
+
+    let uri = file_uri("ruff.toml");
+    client.notify(
+        DidOpenTextDocument::METHOD,
+        json!({"textDocument": {"uri": uri, "languageId": "toml", "version": 1, "text": "a=1\n"}}),
+    );
+    let edits: Value = client.result(
+        Formatting::METHOD,
+        json!({"textDocument": {"uri": uri}, "options": {"tabSize": 2, "insertSpaces": true}}),
+    );
+    assert_eq!(edits, Value::Null);
+
+    client.stop(handle);
+}
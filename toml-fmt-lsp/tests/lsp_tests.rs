@@ -0,0 +1,69 @@
+use std::io::{BufReader, Write};
+use std::process::{ChildStdout, Command, Stdio};
+
+use lsp_server::{Message, Notification, Request, RequestId};
+use serde_json::{Value, json};
+
+fn send(stdin: &mut impl Write, message: Message) {
+    message.write(stdin).unwrap();
+    stdin.flush().unwrap();
+}
+
+fn receive(stdout: &mut BufReader<ChildStdout>) -> Message {
+    Message::read(stdout).unwrap().expect("server closed its output")
+}
+
+fn request(id: i32, method: &str, params: Value) -> Message {
+    Message::Request(Request::new(RequestId::from(id), method.to_string(), params))
+}
+
+fn notification(method: &str, params: Value) -> Message {
+    Message::Notification(Notification::new(method.to_string(), params))
+}
+
+#[test]
+fn test_binary_serves_tox_toml_over_stdio() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_toml-fmt-lsp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+
+    send(&mut stdin, request(1, "initialize", json!({"capabilities": {}})));
+    let Message::Response(initialized) = receive(&mut stdout) else {
+        panic!("expected initialize response");
+    };
+    assert_eq!(
+        initialized.result.unwrap()["capabilities"]["documentFormattingProvider"],
+        true
+    );
+    send(&mut stdin, notification("initialized", json!({})));
+
+    let uri = "untitled:/tox.toml";
+    let document = json!({"uri": uri, "languageId": "toml", "version": 1, "text": "envlist = [\"py\"]\n"});
+    send(
+        &mut stdin,
+        notification("textDocument/didOpen", json!({ "textDocument": document })),
+    );
+    let Message::Notification(published) = receive(&mut stdout) else {
+        panic!("expected diagnostics");
+    };
+    assert_eq!(published.method, "textDocument/publishDiagnostics");
+    assert_eq!(published.params["diagnostics"][0]["code"], "legacy-alias");
+
+    let params = json!({"textDocument": {"uri": uri}, "options": {"tabSize": 2, "insertSpaces": true}});
+    send(&mut stdin, request(2, "textDocument/formatting", params));
+    let Message::Response(formatted) = receive(&mut stdout) else {
+        panic!("expected formatting response");
+    };
+    assert!(formatted.error.is_none(), "{:?}", formatted.error);
+    assert!(formatted.result.unwrap().is_array());
+
+    send(&mut stdin, request(3, "shutdown", Value::Null));
+    assert!(matches!(receive(&mut stdout), Message::Response(_)));
+    send(&mut stdin, notification("exit", Value::Null));
+    drop(stdin);
+    assert!(child.wait().unwrap().success());
+}
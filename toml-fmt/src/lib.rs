@@ -0,0 +1,64 @@
+//! Formats `pyproject.toml` and `tox.toml` files from Rust.
+//!
+//! The same formatters back the `pyproject-fmt` and `tox-toml-fmt` Python packages; this crate exposes them without
+//! Python. Each [`pyproject::Settings`] and [`tox::Settings`] starts from the command line defaults:
+//!
+//! ```
+//! let settings = toml_fmt::pyproject::Settings::builder()
+//!     .generate_python_version_classifiers(false)
+//!     .build();
+//! let formatted = toml_fmt::format_pyproject("[project]\nname=\"My_Package\"\n", &settings).unwrap();
+//! assert_eq!(formatted.content, "[project]\nname = \"my-package\"\n");
+//! assert!(formatted.diagnostics.is_empty());
+//! ```
+
+pub use common::diagnostic::Diagnostic;
+pub use common::explain::Explanation;
+pub use common::formatted::{Formatted, FormattedRange, TextEdit};
+pub use common::range::Selection;
+
+/// Options for [`format_pyproject`](crate::format_pyproject).
+pub mod pyproject {
+    pub use _pyproject_fmt::{Settings, SettingsBuilder};
+}
+
+/// Options for [`format_tox`](crate::format_tox).
+pub mod tox {
+    pub use _tox_toml_fmt::{Settings, SettingsBuilder};
+}
+
+#[cfg(test)]
+mod tests;
+
+/// Formats a `pyproject.toml` document, including an embedded `[tool.tox]` table.
+///
+/// # Errors
+///
+/// Will return a message describing why the content was rejected, e.g. an invalid `project.version`.
+pub fn format_pyproject(content: &str, settings: &pyproject::Settings) -> Result<Formatted, String> {
+    _pyproject_fmt::format_toml(content, settings)
+}
+
+/// Formats only the `pyproject.toml` tables `selection` overlaps, returning the edits to apply and the diagnostics of
+/// those tables.
+///
+/// # Errors
+///
+/// Will return a message describing why the affected tables were rejected, e.g. an invalid `project.version`.
+pub fn format_pyproject_range(
+    content: &str,
+    selection: &Selection,
+    settings: &pyproject::Settings,
+) -> Result<FormattedRange, String> {
+    _pyproject_fmt::format_range(content, selection, settings)
+}
+
+/// Formats a `tox.toml` document.
+///
+/// Unlike [`format_pyproject`] this cannot fail: `tox.toml` has no value that stops the formatter the way an invalid
+/// `project.version` does. Entries it cannot parse, and whatever the checks [`tox::Settings`] enables find, are reported
+/// in [`Formatted::diagnostics`] instead.
+#[must_use]
+pub fn format_tox(content: &str, settings: &tox::Settings) -> Formatted {
+    _tox_toml_fmt::format_toml(content, settings)
+}
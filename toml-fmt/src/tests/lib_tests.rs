@@ -0,0 +1,65 @@
+use indoc::indoc;
+use insta::assert_snapshot;
+
+use crate::{Selection, TextEdit, format_pyproject, format_pyproject_range, format_tox, pyproject, tox};
+
+#[test]
+fn test_format_pyproject_with_builder() {
+    let settings = pyproject::Settings::builder()
+        .generate_python_version_classifiers(false)
+        .build();
+    let start = indoc! {r#"
+        [project]
+        name="My_Package"
+        dependencies=["Requests>=2.0,,"]
+        "#};
+    let got = format_pyproject(start, &settings).unwrap();
+    assert_snapshot!(got.content, @r#"
+    [project]
+    name = "my-package"
+    dependencies = [ "Requests>=2.0,," ]
+    "#);
+    assert_eq!(got.diagnostics.len(), 1);
+    assert_eq!(got.diagnostics[0].key_path, "project.dependencies");
+}
+
+#[test]
+fn test_format_pyproject_rejects_invalid_version() {
+    let got = format_pyproject("[project]\nversion=\"1.9.xyz\"\n", &pyproject::Settings::default());
+    assert_eq!(
+        got.unwrap_err(),
+        "project.version `1.9.xyz` is not a valid PEP 440 version"
+    );
+}
+
+#[test]
+fn test_format_pyproject_range_with_builder() {
+    let settings = pyproject::Settings::builder()
+        .generate_python_version_classifiers(false)
+        .build();
+    let start = indoc! {r#"
+        [project]
+        name="My_Package"
+
+        [tool.black]
+        line-length=120
+        "#};
+    let edits = format_pyproject_range(start, &Selection::Lines(1..=2), &settings)
+        .unwrap()
+        .edits;
+    assert_snapshot!(TextEdit::apply(start, &edits), @r#"
+    [project]
+    name = "my-package"
+
+    [tool.black]
+    line-length=120
+    "#);
+}
+
+#[test]
+fn test_format_tox_with_builder() {
+    let settings = tox::Settings::builder().pin_envs(["lint"]).build();
+    let got = format_tox("env_list = [\"test\", \"lint\"]\n", &settings);
+    assert_snapshot!(got.content, @r#"env_list = [ "lint", "test" ]"#);
+    assert!(got.diagnostics.is_empty());
+}
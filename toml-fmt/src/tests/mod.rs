@@ -0,0 +1 @@
+pub mod lib_tests;
@@ -0,0 +1,5 @@
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    ExitCode::from(_tox_toml_fmt::cli::run(std::env::args_os()))
+}
@@ -0,0 +1,118 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::error::ErrorKind;
+use common::cli::{config_bool, config_list, list_argument, CommaList, ConfigValue, FormatArgs, Formatter};
+use common::formatted::Formatted;
+
+use crate::{convert_ini, format_toml, Settings};
+
+/// The `tox-toml-fmt` binary, driven by [`common::cli::run`].
+pub struct ToxTomlFmt;
+
+/// Runs the binary. With `--from-ini` the `tox.ini` is converted into a `tox.toml` next to it, formatted with the
+/// options any input there would get; `--check` and `--stdout` show the result without creating the file.
+#[must_use]
+pub fn run(args: impl IntoIterator<Item = impl Into<OsString>>) -> u8 {
+    let mut args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+    let Some(ini) = from_ini(&args) else {
+        return common::cli::run::<ToxTomlFmt>(args);
+    };
+    // the tox.ini stands in for the tox.toml that does not exist yet, so usage errors leave nothing behind
+    let matches = common::cli::try_parse::<ToxTomlFmt>(args.iter().cloned().chain([ini.clone().into_os_string()]))
+        .unwrap_or_else(|err| err.exit());
+    if matches.get_many::<PathBuf>("inputs").map_or(0, Iterator::count) > 1 {
+        let message = "argument --from-ini: converts one tox.ini, pass no other inputs";
+        clap::Error::raw(ErrorKind::ArgumentConflict, format!("{message}\n")).exit();
+    }
+    let target = ini.with_file_name("tox.toml");
+    if let Err(err) = fs::File::create_new(&target) {
+        if target.exists() {
+            eprintln!("{}: already exists, not overwriting it", target.display());
+        } else {
+            eprintln!("{}: {err}", target.display());
+        }
+        return 1;
+    }
+    args.push(target.clone().into_os_string());
+    let code = common::cli::run::<ToxTomlFmt>(args);
+    if fs::metadata(&target).is_ok_and(|meta| meta.len() == 0) {
+        let _ = fs::remove_file(&target); // --check and --stdout leave it unwritten
+    }
+    code
+}
+
+fn from_ini(args: &[OsString]) -> Option<PathBuf> {
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        let arg = arg.to_string_lossy();
+        if arg == "--from-ini" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(value) = arg.strip_prefix("--from-ini=") {
+            return Some(PathBuf::from(value));
+        }
+    }
+    None
+}
+
+#[derive(Debug, Clone, clap::Args)]
+#[command(next_help_heading = "formatting behavior")]
+pub struct ToxArgs {
+    /// environments pinned to the start of env_list (comma separated)
+    #[arg(long = "pin-env", default_value = "", value_parser = list_argument)]
+    pin_envs: CommaList,
+    /// move settings every environment repeats into env_run_base
+    #[arg(long)]
+    hoist_shared: bool,
+    /// report undefined environments, mismatched base_python, unknown keys and values of the wrong type
+    #[arg(long)]
+    validate: bool,
+    /// convert this tox.ini into a tox.toml next to it, then format that
+    #[arg(long, value_name = "path")]
+    from_ini: Option<PathBuf>,
+}
+
+impl Formatter for ToxTomlFmt {
+    type Args = ToxArgs;
+    const PROG: &'static str = "tox-toml-fmt";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+    const FILENAME: &'static str = "tox.toml";
+    const OVERRIDE_SECTION: &'static [&'static str] = &["tox-toml-fmt"];
+
+    fn apply_config(args: &mut ToxArgs, key: &str, value: &ConfigValue, _config_dir: &Path) -> Result<bool, String> {
+        match key {
+            "pin_envs" => args.pin_envs = config_list(key, value)?,
+            "hoist_shared" => args.hoist_shared = config_bool(key, value)?,
+            "validate" => args.validate = config_bool(key, value)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn format(text: &str, shared: &FormatArgs, args: &ToxArgs) -> Result<Formatted, String> {
+        let settings = Settings::builder()
+            .column_width(shared.column_width)
+            .indent(shared.indent)
+            .table_format(shared.table_format.as_str())
+            .sub_table_spacing(shared.sub_table_spacing.as_str())
+            .separate_root_table(shared.separate_root_table.as_str())
+            .expand_tables(&shared.expand_tables)
+            .collapse_tables(&shared.collapse_tables)
+            .skip_wrap_for_keys(&shared.skip_wrap_for_keys)
+            .pin_envs(&args.pin_envs)
+            .hoist_shared(args.hoist_shared)
+            .validate(args.validate)
+            .select(&shared.select)
+            .ignore(&shared.ignore)
+            .explain(shared.explain)
+            .build();
+        if let Some(ini) = &args.from_ini {
+            // `text` is the empty tox.toml `run` created for the conversion
+            let ini_text = fs::read_to_string(ini).map_err(|err| format!("{}: {err}", ini.display()))?;
+            return Ok(convert_ini(&ini_text, &settings));
+        }
+        Ok(format_toml(text, &settings))
+    }
+}
@@ -0,0 +1,139 @@
+//! Splits tox command lines into argument lists, for `tox.ini` conversion and for commands written as one string.
+
+use std::fmt::Write;
+
+/// Characters tox passes to the command verbatim, but a reader expects a shell to interpret.
+const SHELL_CHARS: &[char] = &['|', '&', ';', '<', '>', '`'];
+
+struct Word {
+    text: String,
+    /// Whether the word holds a shell character outside quotes and substitutions.
+    shell: bool,
+    /// Whether the word holds a backslash outside single quotes, an escape to a POSIX shell but a path separator on
+    /// Windows.
+    backslash: bool,
+}
+
+fn words(line: &str) -> Option<Vec<Word>> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut started = false;
+    let mut shell = false;
+    let mut backslash = false;
+    let mut quote_char: Option<char> = None;
+    let mut depth = 0_usize;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quote_char, c) {
+            (Some(open), c) if c == open => quote_char = None,
+            (Some(open), c) => {
+                backslash |= open == '"' && c == '\\';
+                current.push(c);
+            }
+            (None, '\'' | '"') if depth == 0 => {
+                quote_char = Some(c);
+                started = true;
+            }
+            (None, c) if c.is_whitespace() && depth == 0 => {
+                if started {
+                    words.push(Word {
+                        text: std::mem::take(&mut current),
+                        shell,
+                        backslash,
+                    });
+                    started = false;
+                    shell = false;
+                    backslash = false;
+                }
+            }
+            (None, c) => {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth = depth.saturating_sub(1),
+                    '$' if depth == 0 && chars.peek() == Some(&'(') => shell = true,
+                    c if depth == 0 && SHELL_CHARS.contains(&c) => shell = true,
+                    '\\' => backslash = true,
+                    _ => {}
+                }
+                current.push(c);
+                started = true;
+            }
+        }
+    }
+    if quote_char.is_some() {
+        return None;
+    }
+    if started {
+        words.push(Word {
+            text: current,
+            shell,
+            backslash,
+        });
+    }
+    Some(words)
+}
+
+/// Splits `line` into words the way tox splits `tox.ini` commands: quotes as in a POSIX shell, but backslashes are kept
+/// literally, so `C:\dir\python.exe` and `tests\unit` survive, and `{...}` substitutions stay whole. `None` when a
+/// quote is left open.
+#[must_use]
+pub fn split(line: &str) -> Option<Vec<String>> {
+    Some(words(line)?.into_iter().map(|word| word.text).collect())
+}
+
+/// The arguments of a command written as one string, as TOML values. `None` when splitting could change what runs:
+/// an open quote, a line break, shell syntax such as pipes, which tox passes on literally, or a backslash outside
+/// single quotes, which could be an escape or a Windows path separator.
+#[must_use]
+pub fn argv(line: &str) -> Option<Vec<String>> {
+    if line.contains('\n') {
+        return None;
+    }
+    let words = words(line)?;
+    if words.is_empty() || words.iter().any(|word| word.shell || word.backslash) {
+        return None;
+    }
+    Some(words.iter().map(|word| argument(&word.text)).collect())
+}
+
+/// A single argument as a TOML value.
+#[must_use]
+pub fn argument(word: &str) -> String {
+    posargs(word).unwrap_or_else(|| quote(word))
+}
+
+/// `{posargs}` or `{posargs:default words}` as the `{ replace = "posargs" }` table extending the command.
+#[must_use]
+pub fn posargs(word: &str) -> Option<String> {
+    let inner = word.strip_prefix("{posargs")?.strip_suffix('}')?;
+    if inner.is_empty() {
+        return Some(String::from("{ replace = \"posargs\", extend = true }"));
+    }
+    let default: Vec<String> = split(inner.strip_prefix(':')?)?
+        .iter()
+        .map(|word| quote(word))
+        .collect();
+    Some(format!(
+        "{{ replace = \"posargs\", default = [{}], extend = true }}",
+        default.join(", ")
+    ))
+}
+
+/// `value` as a TOML basic string.
+#[must_use]
+pub fn quote(value: &str) -> String {
+    let mut out = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04X}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -1,20 +1,28 @@
-use std::cell::RefCell;
-use std::collections::HashSet;
+use std::cell::{RefCell, RefMut};
+use std::collections::{BTreeMap, HashSet};
 
 use lexical_sort::natural_lexical_cmp;
 use regex::Regex;
-use tombi_syntax::SyntaxKind::{ARRAY, BASIC_STRING, INLINE_TABLE, VALUE_WITH_COMMA_GROUP};
+use tombi_syntax::SyntaxKind::{
+    ARRAY, BASIC_STRING, INLINE_TABLE, KEY_VALUE, KEY_VALUE_WITH_COMMA_GROUP, LITERAL_STRING, VALUE_WITH_COMMA_GROUP,
+};
 use tombi_syntax::{SyntaxElement, SyntaxNode};
 
-use common::array::{sort, sort_strings, transform};
-use common::create::make_entry_of_string;
-use common::pep508::Requirement;
-use common::string::load_text;
+use common::array::{dedupe_strings, sort, sort_strings, transform_with_node};
+use common::create::{
+    make_comma, make_entry_of_string, make_newline, make_string_node, make_table_entry, make_whitespace_n,
+};
+use common::diagnostic::Diagnostics;
+use common::explain::{is_recording, record_path};
+use common::pep508::{normalize_requirement, Requirement};
+use common::string::{load_text, strip_quotes};
 use common::table::{
-    count_unquoted_dots, for_entries, rename_keys, reorder_inline_table_keys, reorder_table_keys, InlineTableSchema,
-    Tables,
+    count_unquoted_dots, for_entries, rename_keys, reorder_inline_table_keys, reorder_table_keys,
+    sort_inline_table_keys, InlineTableSchema, Tables,
 };
 
+use crate::command;
+
 fn strip_prefix<'a>(key: &'a str, prefix: &str) -> Option<&'a str> {
     if prefix.is_empty() {
         Some(key)
@@ -46,7 +54,16 @@ fn env_tables<'a>(tables: &'a Tables, prefix: &str) -> Vec<(&'a String, Vec<&'a
         .collect()
 }
 
-const ROOT_ALIASES: &[(&str, &str)] = &[
+fn key_path(table: &str, key: &str) -> String {
+    if table.is_empty() {
+        key.to_string()
+    } else {
+        format!("{table}.{key}")
+    }
+}
+
+/// Legacy `tox.ini` names of root settings and the names tox 4 uses for them.
+pub const ROOT_ALIASES: &[(&str, &str)] = &[
     ("envlist", "env_list"),
     ("toxinidir", "tox_root"),
     ("toxworkdir", "work_dir"),
@@ -57,7 +74,8 @@ const ROOT_ALIASES: &[(&str, &str)] = &[
     ("ignore_basepython_conflict", "ignore_base_python_conflict"),
 ];
 
-const ENV_ALIASES: &[(&str, &str)] = &[
+/// Legacy `tox.ini` names of environment settings and the names tox 4 uses for them.
+pub const ENV_ALIASES: &[(&str, &str)] = &[
     ("setenv", "set_env"),
     ("passenv", "pass_env"),
     ("envdir", "env_dir"),
@@ -148,6 +166,20 @@ const ENV_KEY_ORDER: &[&str] = &[
     "env_log_dir",
 ];
 
+/// The current name for `key` set directly in `table`, when `key` is a legacy alias there: `envlist` at the root,
+/// `setenv` in an environment table. `prefix` is where the tox configuration lives, `tool.tox` in `pyproject.toml`.
+#[must_use]
+pub fn alias_target(table: &str, key: &str, prefix: &str) -> Option<&'static str> {
+    let aliases = if table == prefix {
+        ROOT_ALIASES
+    } else if is_env_table(table, prefix) {
+        ENV_ALIASES
+    } else {
+        return None;
+    };
+    aliases.iter().find(|(alias, _)| *alias == key).map(|(_, name)| *name)
+}
+
 pub fn normalize_aliases(tables: &Tables) {
     normalize_aliases_with_prefix(tables, "");
 }
@@ -165,11 +197,12 @@ pub fn normalize_aliases_with_prefix(tables: &Tables, prefix: &str) {
     }
 }
 
-pub fn fix_root(tables: &Tables) {
-    fix_root_with_prefix(tables, "");
+/// `lowercase_name` lowercases the names of the `requires` entries, as the `pep508.lowercase-name` rule.
+pub fn fix_root(tables: &Tables, lowercase_name: bool, diagnostics: &Diagnostics) {
+    fix_root_with_prefix(tables, "", lowercase_name, diagnostics);
 }
 
-pub fn fix_root_with_prefix(tables: &Tables, prefix: &str) {
+pub fn fix_root_with_prefix(tables: &Tables, prefix: &str, lowercase_name: bool, diagnostics: &Diagnostics) {
     let Some(root_tables) = tables.get(prefix) else {
         return;
     };
@@ -177,35 +210,99 @@ pub fn fix_root_with_prefix(tables: &Tables, prefix: &str) {
         let table = &mut table_ref.borrow_mut();
         for_entries(table, &mut |key, entry| {
             if key == "requires" {
-                transform(entry, &|s| Requirement::new(s).unwrap().normalize(false).to_string());
+                let key_path = key_path(prefix, &key);
+                transform_with_node(
+                    entry,
+                    &|node, s| match normalize_requirement(s, false, lowercase_name) {
+                        Ok(normalized) => normalized,
+                        Err(err) => {
+                            diagnostics.report_node(node, &key_path, s, &err);
+                            s.to_string()
+                        }
+                    },
+                );
                 sort_strings::<String, _, _>(
                     entry,
-                    |s| Requirement::new(s.as_str()).unwrap().canonical_name(),
+                    |s| Requirement::new(s.as_str()).map_or_else(|_| s.to_lowercase(), |r| r.canonical_name()),
                     &|lhs, rhs| natural_lexical_cmp(lhs, rhs),
                 );
+            } else if key == "labels" {
+                label_members(entry).iter().for_each(dedupe_and_sort);
+            } else if key.starts_with("labels.") {
+                dedupe_and_sort(entry);
             }
         });
         reorder_table_keys(table, ROOT_KEY_ORDER);
     }
+    for table_ref in tables.get(&key_path(prefix, "labels")).into_iter().flatten() {
+        for_entries(&table_ref.borrow(), &mut |_, entry| dedupe_and_sort(entry));
+    }
 }
 
-pub fn fix_envs(tables: &Tables) {
-    fix_envs_with_prefix(tables, "");
+/// The member arrays of a `labels = { name = [...] }` inline table.
+fn label_members(labels: &SyntaxNode) -> Vec<SyntaxNode> {
+    labels
+        .children()
+        .filter(|group| group.kind() == KEY_VALUE_WITH_COMMA_GROUP)
+        .flat_map(|group| group.children().filter(|entry| entry.kind() == KEY_VALUE))
+        .flat_map(|entry| entry.children().filter(|value| value.kind() == ARRAY))
+        .collect()
 }
 
-pub fn fix_envs_with_prefix(tables: &Tables, prefix: &str) {
-    for (_key, table_refs) in env_tables(tables, prefix) {
+/// `lowercase_name` lowercases the names of the `deps` and `constraints` entries, as the `pep508.lowercase-name` rule.
+pub fn fix_envs(tables: &Tables, lowercase_name: bool, diagnostics: &Diagnostics) {
+    fix_envs_with_prefix(tables, "", lowercase_name, diagnostics);
+}
+
+pub fn fix_envs_with_prefix(tables: &Tables, prefix: &str, lowercase_name: bool, diagnostics: &Diagnostics) {
+    for (env_key, table_refs) in env_tables(tables, prefix) {
         for table_ref in table_refs {
             let table = &mut table_ref.borrow_mut();
             upgrade_use_develop(table);
             for_entries(table, &mut |key, entry| {
-                fix_env_entry(&key, entry);
+                fix_env_entry(&key, entry, &key_path(env_key, &key), lowercase_name, diagnostics);
             });
-            reorder_table_keys(table, ENV_KEY_ORDER);
+            reorder_env_keys(table);
+        }
+        for table_ref in tables.get(&format!("{env_key}.set_env")).into_iter().flatten() {
+            let table = &mut table_ref.borrow_mut();
+            let order = set_env_keys(table, "");
+            reorder_table_keys(table, &order.iter().map(String::as_str).collect::<Vec<_>>());
         }
     }
 }
 
+/// Orders an env table by `ENV_KEY_ORDER`, with dotted `set_env.X` keys ordered like an inline `set_env` table.
+fn reorder_env_keys(table: &mut RefMut<Vec<SyntaxElement>>) {
+    let set_env = set_env_keys(table, "set_env.");
+    let mut order: Vec<&str> = Vec::with_capacity(ENV_KEY_ORDER.len() + set_env.len());
+    for &key in ENV_KEY_ORDER {
+        if key == "set_env" {
+            order.extend(set_env.iter().map(String::as_str));
+        }
+        order.push(key);
+    }
+    reorder_table_keys(table, &order);
+}
+
+/// The keys of `table` starting with `prefix` in `set_env` order: `set_env.` picks the dotted keys of an env table,
+/// an empty prefix every key of an expanded `[env.x.set_env]` table.
+fn set_env_keys(table: &[SyntaxElement], prefix: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    for_entries(table, &mut |key, _| {
+        if let Some(name) = key.strip_prefix(prefix) {
+            keys.push((set_env_order(&strip_quotes(name)), key.clone()));
+        }
+    });
+    keys.sort();
+    keys.into_iter().map(|(_, key)| key).collect()
+}
+
+/// `set_env` keys are environment variable names, so case matters; the `file` entry loading a dotenv file goes first.
+fn set_env_order(key: &str) -> (bool, String) {
+    (key != "file", key.to_string())
+}
+
 fn get_key_name(entry: &SyntaxElement) -> Option<String> {
     use tombi_syntax::SyntaxKind::KEYS;
     let node = entry.as_node()?;
@@ -266,12 +363,23 @@ fn should_skip_normalization(s: &str) -> bool {
         || s.contains('{')
 }
 
-fn normalize_and_sort_requirements(entry: &SyntaxNode) {
-    transform(entry, &|s| {
+fn normalize_and_sort_requirements(
+    entry: &SyntaxNode,
+    key_path: &str,
+    lowercase_name: bool,
+    diagnostics: &Diagnostics,
+) {
+    transform_with_node(entry, &|node, s| {
         if should_skip_normalization(s) {
             return s.to_string();
         }
-        Requirement::new(s).unwrap().normalize(false).to_string()
+        match normalize_requirement(s, false, lowercase_name) {
+            Ok(normalized) => normalized,
+            Err(err) => {
+                diagnostics.report_node(node, key_path, s, &err);
+                s.to_string()
+            }
+        }
     });
     sort_strings::<String, _, _>(
         entry,
@@ -279,23 +387,131 @@ fn normalize_and_sort_requirements(entry: &SyntaxNode) {
             if should_skip_normalization(&s) {
                 return s.to_lowercase();
             }
-            Requirement::new(s.as_str()).unwrap().canonical_name()
+            Requirement::new(s.as_str()).map_or_else(|_| s.to_lowercase(), |r| r.canonical_name())
         },
         &|lhs, rhs| natural_lexical_cmp(lhs, rhs),
     );
 }
 
-fn fix_env_entry(key: &str, entry: &SyntaxNode) {
+fn fix_env_entry(key: &str, entry: &SyntaxNode, key_path: &str, lowercase_name: bool, diagnostics: &Diagnostics) {
     match key {
-        "deps" | "constraints" => normalize_and_sort_requirements(entry),
-        "dependency_groups" | "allowlist_externals" | "extras" | "labels" | "depends" => {
-            sort_strings::<String, _, _>(entry, |s| s.to_lowercase(), &|lhs, rhs| natural_lexical_cmp(lhs, rhs));
+        "deps" | "constraints" => normalize_and_sort_requirements(entry, key_path, lowercase_name, diagnostics),
+        "dependency_groups" | "allowlist_externals" | "extras" | "labels" | "depends" => dedupe_and_sort(entry),
+        "pass_env" => {
+            dedupe_strings(entry, str::to_string);
+            sort_pass_env(entry);
         }
-        "pass_env" => sort_pass_env(entry),
+        "set_env" => sort_inline_table_keys(entry, set_env_order),
+        "commands" | "commands_pre" | "commands_post" | "extra_setup_commands" => split_commands(entry),
         _ => {}
     }
 }
 
+fn dedupe_and_sort(entry: &SyntaxNode) {
+    dedupe_strings(entry, str::to_string);
+    sort_strings::<String, _, _>(entry, |s| s.to_lowercase(), &|lhs, rhs| natural_lexical_cmp(lhs, rhs));
+}
+
+/// Rewrites commands written as one shell string into argument lists, and `{posargs}` arguments into the `replace`
+/// table. Commands that need a shell to mean what they say (pipes, redirects, open quotes) are left alone.
+fn split_commands(entry: &SyntaxNode) {
+    if entry.kind() != ARRAY {
+        return;
+    }
+    for command in array_values(entry) {
+        let argv = match command.kind() {
+            ARRAY => split_command_array(&command),
+            BASIC_STRING | LITERAL_STRING => {
+                command::argv(&load_text(&command.text().to_string(), command.kind())).map(|args| args.join(", "))
+            }
+            _ => None,
+        };
+        if let Some(argv) = argv {
+            replace_with_array(&command, &argv);
+        }
+    }
+}
+
+/// The arguments of a command array needing a rewrite: a lone element holding a whole command line, or a `{posargs}`
+/// string. A first element with spaces followed by arguments is more likely a path such as `C:\Program Files\...`,
+/// so it is kept.
+fn split_command_array(array: &SyntaxNode) -> Option<String> {
+    let mut changed = false;
+    let mut args = Vec::new();
+    let values = array_values(array);
+    for (idx, value) in values.iter().enumerate() {
+        let text = value.text().to_string();
+        if !matches!(value.kind(), BASIC_STRING | LITERAL_STRING) {
+            args.push(text);
+            continue;
+        }
+        let word = load_text(&text, value.kind());
+        if idx == 0 && values.len() == 1 && word.contains(char::is_whitespace) {
+            args.extend(command::argv(&word)?);
+            changed = true;
+        } else if let Some(posargs) = command::posargs(&word) {
+            args.push(posargs);
+            changed = true;
+        } else {
+            args.push(text);
+        }
+    }
+    changed.then(|| args.join(", "))
+}
+
+/// The values of `array`, looking through the comma groups the parser wraps them in.
+fn array_values(array: &SyntaxNode) -> Vec<SyntaxNode> {
+    array
+        .children()
+        .flat_map(|child| {
+            if child.kind() == VALUE_WITH_COMMA_GROUP {
+                array_values(&child)
+            } else {
+                vec![child]
+            }
+        })
+        .collect()
+}
+
+/// Adds `values` as strings after the last of `items`, the values of one array, leaving its comments in place.
+fn append_strings(items: &[SyntaxNode], values: &[String]) {
+    let Some(last) = items.last() else {
+        return;
+    };
+    let Some(parent) = last.parent() else {
+        return;
+    };
+    let Some(idx) = parent
+        .children_with_tokens()
+        .position(|child| child.as_node() == Some(last))
+    else {
+        return;
+    };
+    let added: Vec<SyntaxElement> = values
+        .iter()
+        .flat_map(|value| [make_comma(), make_whitespace_n(1), make_string_node(value)])
+        .collect();
+    parent.splice_children(idx + 1..idx + 1, added);
+}
+
+fn replace_with_array(value: &SyntaxNode, items: &str) {
+    let Some(parent) = value.parent() else {
+        return;
+    };
+    let Some(array) = crate::parse(&format!("a = [{items}]"))
+        .descendants()
+        .find(|node| node.kind() == ARRAY)
+    else {
+        return;
+    };
+    if let Some(idx) = parent
+        .children_with_tokens()
+        .position(|child| child.as_node() == Some(value))
+    {
+        parent.splice_children(idx..idx + 1, vec![array.into()]);
+    }
+}
+
 fn sort_pass_env(entry: &SyntaxNode) {
     sort::<(u8, String), _, _>(
         entry,
@@ -329,6 +545,99 @@ fn classify_env_part(part: &str) -> Option<(i32, i32, i32)> {
     None
 }
 
+/// A Python interpreter named by an environment factor (`py312`, `pypy3.10`, `3.13`) or a `base_python` value
+/// (`python3.12`): whether it is PyPy, the major version, and the minor version when given.
+fn python_spec(text: &str) -> Option<(bool, u32, Option<u32>)> {
+    let re = Regex::new(r"^(?:(pypy|cpython|python|py)(\d)\.?(\d+)?|(\d)\.(\d+))$").unwrap();
+    let caps = re.captures(text)?;
+    let number = |at: usize| caps.get(at).and_then(|m| m.as_str().parse::<u32>().ok());
+    match caps.get(1) {
+        Some(implementation) => Some((implementation.as_str() == "pypy", number(2)?, number(3))),
+        None => Some((false, number(4)?, number(5))),
+    }
+}
+
+pub fn check_base_python(tables: &Tables, diagnostics: &Diagnostics) {
+    check_base_python_with_prefix(tables, "", diagnostics);
+}
+
+/// Reports `[env.*]` tables whose `base_python` names another interpreter than the Python factor of the environment,
+/// `base_python = "python3.11"` in `[env.py312-django]`. With `ignore_base_python_conflict = true` tox runs the
+/// factor's interpreter on purpose, so nothing is reported.
+pub fn check_base_python_with_prefix(tables: &Tables, prefix: &str, diagnostics: &Diagnostics) {
+    let config = tox_config(tables, prefix);
+    if config.get("ignore_base_python_conflict").and_then(toml::Value::as_bool) == Some(true) {
+        return;
+    }
+    for (env_name, section) in config.get("env").and_then(toml::Value::as_table).into_iter().flatten() {
+        let Some(base_python) = section.get("base_python").and_then(|value| match value {
+            toml::Value::Array(items) => items.first().and_then(toml::Value::as_str),
+            value => value.as_str(),
+        }) else {
+            continue;
+        };
+        let Some((factor, wanted)) = env_name
+            .split('-')
+            .find_map(|factor| python_spec(factor).map(|spec| (factor, spec)))
+        else {
+            continue;
+        };
+        let Some(got) = python_spec(base_python) else {
+            continue;
+        };
+        let minor_differs = matches!((wanted.2, got.2), (Some(lhs), Some(rhs)) if lhs != rhs);
+        if wanted.0 != got.0 || wanted.1 != got.1 || minor_differs {
+            diagnostics.report(
+                &key_path(prefix, &format!("env.{env_name}.base_python")),
+                base_python,
+                &format!("base_python {base_python} does not match the {factor} factor of {env_name}"),
+            );
+        }
+    }
+}
+
+/// Adds a `py3X` entry to `env_list` for each `3.X` in `versions` it lacks, when `env_list` already lists environments
+/// by Python version; entries for versions outside the range are left alone. New entries follow the spelling of the
+/// first existing one, `py312`, `py3.12` or `3.12`.
+pub fn add_python_envs_with_prefix(tables: &Tables, prefix: &str, versions: &[(u8, u8)]) {
+    let Some(root_tables) = tables.get(prefix) else {
+        return;
+    };
+    for table_ref in root_tables {
+        let table = table_ref.borrow();
+        for_entries(&table, &mut |key, entry| {
+            if key != "env_list" || entry.kind() != ARRAY {
+                return;
+            }
+            let items = array_values(entry);
+            let names: Vec<String> = items
+                .iter()
+                .filter(|item| matches!(item.kind(), BASIC_STRING | LITERAL_STRING))
+                .map(|item| load_text(&item.text().to_string(), item.kind()))
+                .collect();
+            let pythons: Vec<(bool, u32, Option<u32>)> = names.iter().filter_map(|name| python_spec(name)).collect();
+            let Some(template) = names.iter().find(|name| python_spec(name).is_some()) else {
+                return;
+            };
+            let spell = |(major, minor): (u8, u8)| {
+                if !template.starts_with("py") {
+                    format!("{major}.{minor}")
+                } else if template.contains('.') {
+                    format!("py{major}.{minor}")
+                } else {
+                    format!("py{major}{minor}")
+                }
+            };
+            let missing: Vec<String> = versions
+                .iter()
+                .filter(|(major, minor)| !pythons.contains(&(false, u32::from(*major), Some(u32::from(*minor)))))
+                .map(|version| spell(*version))
+                .collect();
+            append_strings(&items, &missing);
+        });
+    }
+}
+
 pub fn sort_env_list(tables: &Tables, pin_envs: &[String]) {
     sort_env_list_with_prefix(tables, pin_envs, "");
 }
@@ -402,62 +711,742 @@ pub fn normalize_strings(tables: &Tables) {
     }
 }
 
-fn get_env_list_order(tables: &Tables) -> Vec<String> {
-    get_env_list_order_with_prefix(tables, "")
+/// The tables below the tox root, relative to it and in order: the base tables, `env_list`'s environments, the other
+/// environments alphabetically, then `env` itself.
+pub fn table_order_with_prefix(tables: &Tables, prefix: &str) -> Vec<String> {
+    let mut order: Vec<String> = ["env_run_base", "env_pkg_base", "env_base"].map(String::from).into();
+    let listed: Vec<String> = env_list_envs(tables, prefix)
+        .0
+        .into_iter()
+        .map(|env_name| format!("env.{env_name}"))
+        .collect();
+    let mut remaining: Vec<String> = tables
+        .header_to_pos
+        .keys()
+        .filter_map(|key| strip_prefix(key, prefix))
+        .filter(|key| key.starts_with("env.") && count_unquoted_dots(key) == 1 && !listed.iter().any(|env| env == key))
+        .map(String::from)
+        .collect();
+    remaining.sort_by_key(|key| key.to_lowercase());
+    order.extend(listed);
+    order.extend(remaining);
+    order.push(String::from("env"));
+    order
 }
 
-fn get_env_list_order_with_prefix(tables: &Tables, prefix: &str) -> Vec<String> {
-    let mut env_order = Vec::new();
-    let env_prefix = if prefix.is_empty() {
-        String::from("env.")
-    } else {
-        format!("{prefix}.env.")
+/// The environments `env_list` names, in order, with every `product` entry expanded in place, and the factors those
+/// products combine.
+fn env_list_envs(tables: &Tables, prefix: &str) -> (Vec<String>, HashSet<String>) {
+    let mut envs = Vec::new();
+    let mut factors = HashSet::new();
+    let Some(root_tables) = tables.get(prefix) else {
+        return (envs, factors);
     };
+    for table_ref in root_tables {
+        let table = table_ref.borrow();
+        for_entries(&table, &mut |key, entry| {
+            if key != "env_list" || entry.kind() != ARRAY {
+                return;
+            }
+            for array_child in entry.children_with_tokens() {
+                let values: Vec<SyntaxElement> = if array_child.kind() == VALUE_WITH_COMMA_GROUP {
+                    array_child.as_node().unwrap().children_with_tokens().collect()
+                } else {
+                    vec![array_child]
+                };
+                for value in values {
+                    match value.kind() {
+                        BASIC_STRING => envs.push(load_text(&value.to_string(), BASIC_STRING)),
+                        INLINE_TABLE => {
+                            if let Some(product) = Product::parse(&value.to_string()) {
+                                factors.extend(product.factors.iter().flatten().cloned());
+                                envs.extend(product.envs());
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        });
+    }
+    (envs, factors)
+}
 
-    if let Some(root_tables) = tables.get(prefix) {
-        for table_ref in root_tables {
-            let table = table_ref.borrow();
-            for_entries(&table, &mut |key, entry| {
-                if key == "env_list" && entry.kind() == ARRAY {
-                    for array_child in entry.children_with_tokens() {
-                        if array_child.kind() == BASIC_STRING {
-                            let env_name = load_text(&array_child.to_string(), BASIC_STRING);
-                            env_order.push(format!("{env_prefix}{env_name}"));
-                        } else if array_child.kind() == VALUE_WITH_COMMA_GROUP {
-                            for inner in array_child.as_node().unwrap().children_with_tokens() {
-                                if inner.kind() == BASIC_STRING {
-                                    let env_name = load_text(&inner.to_string(), BASIC_STRING);
-                                    env_order.push(format!("{env_prefix}{env_name}"));
-                                }
+/// A `{ product = [...], exclude = [...] }` entry of `env_list`: every combination of one factor per group, joined
+/// with `-`, except the excluded ones.
+struct Product {
+    factors: Vec<Vec<String>>,
+    exclude: HashSet<String>,
+}
+
+impl Product {
+    fn parse(text: &str) -> Option<Self> {
+        let root = format!("entry = {text}").parse::<toml::Table>().ok()?;
+        let entry = root.get("entry")?.as_table()?;
+        let factors = entry
+            .get("product")?
+            .as_array()?
+            .iter()
+            .map(factor_group)
+            .collect::<Option<Vec<_>>>()?;
+        let exclude = match entry.get("exclude") {
+            Some(exclude) => exclude
+                .as_array()?
+                .iter()
+                .map(|env| env.as_str().map(String::from))
+                .collect::<Option<_>>()?,
+            None => HashSet::new(),
+        };
+        Some(Self { factors, exclude })
+    }
+
+    fn envs(&self) -> Vec<String> {
+        self.factors
+            .iter()
+            .fold(vec![String::new()], |envs, group| {
+                envs.iter()
+                    .flat_map(|env| {
+                        group.iter().map(move |factor| {
+                            if env.is_empty() {
+                                factor.clone()
+                            } else {
+                                format!("{env}-{factor}")
                             }
+                        })
+                    })
+                    .collect()
+            })
+            .into_iter()
+            .filter(|env| !env.is_empty() && !self.exclude.contains(env))
+            .collect()
+    }
+}
+
+/// A factor group of a product: a list of factors, or a `{ prefix = "py3", start = 12, stop = 14 }` range whose `stop`
+/// is included. Open ranges depend on the interpreters tox knows about, so they cannot be expanded here.
+fn factor_group(value: &toml::Value) -> Option<Vec<String>> {
+    if let Some(range) = value.as_table() {
+        let prefix = range.get("prefix").map_or(Some(""), toml::Value::as_str)?;
+        let start = range.get("start")?.as_integer()?;
+        let stop = range.get("stop")?.as_integer()?;
+        return Some((start..=stop).map(|n| format!("{prefix}{n}")).collect());
+    }
+    value
+        .as_array()?
+        .iter()
+        .map(|factor| factor.as_str().map(String::from))
+        .collect()
+}
+
+/// Reports env tables made of the factors of an `env_list` product that no entry produces, such as an excluded
+/// combination or one left behind when a factor was dropped. Tables sharing no factor with a product, like
+/// `[env.dev]`, are run by name and not reported.
+fn report_unproduced_envs(tables: &Tables, prefix: &str, diagnostics: &Diagnostics) {
+    let (envs, factors) = env_list_envs(tables, prefix);
+    if factors.is_empty() {
+        return;
+    }
+    let envs: HashSet<String> = envs.into_iter().collect();
+    let mut unproduced: Vec<(&String, String)> = env_tables(tables, prefix)
+        .into_iter()
+        .filter_map(|(table, _)| {
+            let env_name = strip_quotes(strip_prefix(table, prefix)?.strip_prefix("env.")?);
+            let generated = env_name.split('-').any(|factor| factors.contains(factor));
+            (generated && !envs.contains(&env_name)).then_some((table, env_name))
+        })
+        .collect();
+    unproduced.sort();
+    for (table, env_name) in unproduced {
+        diagnostics.report(table, &env_name, "no env_list entry produces this environment");
+    }
+}
+
+pub fn check_references(tables: &Tables, diagnostics: &Diagnostics) {
+    check_references_with_prefix(tables, "", diagnostics);
+}
+
+/// Reports references to environments that are neither an `[env.*]` table nor produced by `env_list`: label members,
+/// `depends`, `package_env`, `wheel_build_env` and `{ replace = "ref", env = ... }` substitutions. Names tox creates on
+/// demand, such as `.pkg` or Python factors like `py313`, always resolve. `base` entries must name a table, e.g.
+/// `env.lint`. Env tables `env_list` should produce but does not are reported too, see [`report_unproduced_envs`].
+pub fn check_references_with_prefix(tables: &Tables, prefix: &str, diagnostics: &Diagnostics) {
+    let config = tox_config(tables, prefix);
+    let mut envs: HashSet<String> = env_list_envs(tables, prefix).0.into_iter().collect();
+    envs.extend(
+        config
+            .get("env")
+            .and_then(toml::Value::as_table)
+            .into_iter()
+            .flat_map(|section| section.keys().cloned()),
+    );
+    let check_env = |path: &str, env_name: &str| {
+        let on_demand = env_name.starts_with('.')
+            || env_name.contains(['{', '*'])
+            || env_name.split('-').all(|factor| classify_env_part(factor).is_some());
+        if !on_demand && !envs.contains(env_name) {
+            diagnostics.report(
+                &key_path(prefix, path),
+                env_name,
+                &format!("undefined environment {env_name}"),
+            );
+        }
+    };
+
+    if let Some(labels) = config.get("labels").and_then(toml::Value::as_table) {
+        for (label, members) in labels {
+            for member in string_items(members) {
+                check_env(&format!("labels.{label}"), member);
+            }
+        }
+    }
+    let mut sections: Vec<(String, &toml::Table)> = vec![(String::new(), &config)];
+    for name in ["env_run_base", "env_pkg_base"] {
+        if let Some(section) = config.get(name).and_then(toml::Value::as_table) {
+            sections.push((String::from(name), section));
+        }
+    }
+    for group in ["env", "env_base"] {
+        for (name, section) in config.get(group).and_then(toml::Value::as_table).into_iter().flatten() {
+            if let Some(section) = section.as_table() {
+                sections.push((format!("{group}.{name}"), section));
+            }
+        }
+    }
+    for (section_path, section) in sections {
+        for (key, value) in section {
+            if section_path.is_empty()
+                && ["env", "env_base", "env_run_base", "env_pkg_base", "labels"].contains(&key.as_str())
+            {
+                continue;
+            }
+            let path = key_path(&section_path, key);
+            match key.as_str() {
+                "depends" => string_items(value).for_each(|env_name| check_env(&path, env_name)),
+                "package_env" | "wheel_build_env" => value
+                    .as_str()
+                    .into_iter()
+                    .for_each(|env_name| check_env(&path, env_name)),
+                "base" if !section_path.is_empty() => {
+                    for base in string_items(value) {
+                        let known = ["env_run_base", "env_pkg_base"].contains(&base)
+                            || get_at(&config, &key_segments(base)).is_some_and(toml::Value::is_table);
+                        if !known {
+                            diagnostics.report(&key_path(prefix, &path), base, &format!("undefined base {base}"));
                         }
                     }
                 }
+                _ => {}
+            }
+            let mut referenced = Vec::new();
+            referenced_envs(value, &mut referenced);
+            for env_name in referenced {
+                check_env(&path, env_name);
+            }
+        }
+    }
+    report_unproduced_envs(tables, prefix, diagnostics);
+}
+
+/// Tables at the root of the configuration that hold environments rather than settings.
+const ROOT_TABLES: &[&str] = &["env", "env_base", "env_run_base", "env_pkg_base", "legacy_tox_ini"];
+
+#[derive(Clone, Copy)]
+enum ValueKind {
+    Bool,
+    String,
+    Strings,
+    Table,
+    Commands,
+}
+
+impl ValueKind {
+    const fn describe(self) -> &'static str {
+        match self {
+            Self::Bool => "a boolean",
+            Self::String => "a string",
+            Self::Strings => "an array of strings",
+            Self::Table => "a table",
+            Self::Commands => "an array of commands, each an array of strings",
+        }
+    }
+
+    fn matches(self, value: &toml::Value) -> bool {
+        if is_directive(value) {
+            return true;
+        }
+        match self {
+            Self::Bool => value.is_bool(),
+            Self::String => value.is_str(),
+            Self::Strings => value
+                .as_array()
+                .is_some_and(|items| items.iter().all(|item| item.is_str() || is_directive(item))),
+            Self::Table => value.is_table(),
+            Self::Commands => value
+                .as_array()
+                .is_some_and(|items| items.iter().all(|item| Self::Strings.matches(item))),
+        }
+    }
+}
+
+const ROOT_VALUE_KINDS: &[(&str, ValueKind)] = &[
+    ("min_version", ValueKind::String),
+    ("requires", ValueKind::Strings),
+    ("env_list", ValueKind::Strings),
+    ("labels", ValueKind::Table),
+    ("no_package", ValueKind::Bool),
+    ("ignore_base_python_conflict", ValueKind::Bool),
+];
+
+const ENV_VALUE_KINDS: &[(&str, ValueKind)] = &[
+    ("runner", ValueKind::String),
+    ("description", ValueKind::String),
+    ("system_site_packages", ValueKind::Bool),
+    ("always_copy", ValueKind::Bool),
+    ("download", ValueKind::Bool),
+    ("package", ValueKind::String),
+    ("package_env", ValueKind::String),
+    ("wheel_build_env", ValueKind::String),
+    ("skip_install", ValueKind::Bool),
+    ("use_develop", ValueKind::Bool),
+    ("pip_pre", ValueKind::Bool),
+    ("deps", ValueKind::Strings),
+    ("dependency_groups", ValueKind::Strings),
+    ("constraints", ValueKind::Strings),
+    ("constrain_package_deps", ValueKind::Bool),
+    ("use_frozen_constraints", ValueKind::Bool),
+    ("extras", ValueKind::Strings),
+    ("recreate", ValueKind::Bool),
+    ("parallel_show_output", ValueKind::Bool),
+    ("fail_fast", ValueKind::Bool),
+    ("pass_env", ValueKind::Strings),
+    ("disallow_pass_env", ValueKind::Strings),
+    ("set_env", ValueKind::Table),
+    ("args_are_paths", ValueKind::Bool),
+    ("ignore_errors", ValueKind::Bool),
+    ("ignore_outcome", ValueKind::Bool),
+    ("extra_setup_commands", ValueKind::Commands),
+    ("commands_pre", ValueKind::Commands),
+    ("commands", ValueKind::Commands),
+    ("commands_post", ValueKind::Commands),
+    ("allowlist_externals", ValueKind::Strings),
+    ("labels", ValueKind::Strings),
+    ("depends", ValueKind::Strings),
+];
+
+/// A `{ replace = ... }` substitution or a `{ product = ... }` generator, which tox expands into a value of any type.
+fn is_directive(value: &toml::Value) -> bool {
+    value
+        .as_table()
+        .is_some_and(|table| table.contains_key("replace") || table.contains_key("product"))
+}
+
+pub fn check_schema(tables: &Tables, diagnostics: &Diagnostics) {
+    check_schema_with_prefix(tables, "", diagnostics);
+}
+
+/// Reports keys tox does not know, suggesting the closest known key, and values of the wrong type for settings with a
+/// fixed one: `skip_install` must be a boolean, `deps` an array of strings, `set_env` a table. The known keys are
+/// the ones the formatter orders.
+pub fn check_schema_with_prefix(tables: &Tables, prefix: &str, diagnostics: &Diagnostics) {
+    let config = tox_config(tables, prefix);
+    let root_keys: Vec<&str> = ROOT_KEY_ORDER.iter().chain(ROOT_TABLES).copied().collect();
+    let mut sections: Vec<(String, &toml::Table)> = Vec::new();
+    for (key, value) in &config {
+        match key.as_str() {
+            "env_run_base" | "env_pkg_base" => {
+                if let Some(section) = value.as_table() {
+                    sections.push((key.clone(), section));
+                }
+            }
+            "env" | "env_base" => {
+                for (name, section) in value.as_table().into_iter().flatten() {
+                    if let Some(section) = section.as_table() {
+                        sections.push((format!("{key}.{name}"), section));
+                    }
+                }
+            }
+            _ => check_setting(prefix, "", key, value, &root_keys, ROOT_VALUE_KINDS, diagnostics),
+        }
+    }
+    for (section_path, section) in sections {
+        for (key, value) in section {
+            check_setting(
+                prefix,
+                &section_path,
+                key,
+                value,
+                ENV_KEY_ORDER,
+                ENV_VALUE_KINDS,
+                diagnostics,
+            );
+        }
+    }
+}
+
+fn check_setting(
+    prefix: &str,
+    section: &str,
+    key: &str,
+    value: &toml::Value,
+    known: &[&str],
+    kinds: &[(&str, ValueKind)],
+    diagnostics: &Diagnostics,
+) {
+    let path = key_path(prefix, &key_path(section, key));
+    if !known.contains(&key) {
+        let message = match closest_key(key, known) {
+            Some(suggestion) => format!("unknown key {key}, did you mean {suggestion}?"),
+            None => format!("unknown key {key}"),
+        };
+        diagnostics.report(&path, key, &message);
+    } else if let Some((_, kind)) = kinds.iter().find(|(name, _)| *name == key) {
+        if !kind.matches(value) {
+            diagnostics.report(&path, key, &format!("{key} must be {}", kind.describe()));
+        }
+    }
+}
+
+/// The known key within two edits of `key`, the nearest first.
+fn closest_key<'a>(key: &str, known: &[&'a str]) -> Option<&'a str> {
+    known
+        .iter()
+        .filter(|name| !name.is_empty())
+        .map(|name| (edit_distance(key, name), *name))
+        .filter(|(distance, _)| *distance <= 2 && *distance < key.len())
+        .min()
+        .map(|(_, name)| name)
+}
+
+/// The Levenshtein distance between `lhs` and `rhs`.
+fn edit_distance(lhs: &str, rhs: &str) -> usize {
+    let rhs: Vec<char> = rhs.chars().collect();
+    let mut previous: Vec<usize> = (0..=rhs.len()).collect();
+    for (i, left) in lhs.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, right) in rhs.iter().enumerate() {
+            let substitute = previous[j] + usize::from(left != *right);
+            current.push(substitute.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[rhs.len()]
+}
+
+fn string_items(value: &toml::Value) -> impl Iterator<Item = &str> {
+    value.as_array().into_iter().flatten().filter_map(toml::Value::as_str)
+}
+
+/// Environments named by the `{ replace = "ref", ... }` substitutions in `value`, through `env` or an
+/// `of = ["env", name, ...]` path.
+fn referenced_envs<'a>(value: &'a toml::Value, found: &mut Vec<&'a str>) {
+    match value {
+        toml::Value::Table(table) => {
+            if table.get("replace").and_then(toml::Value::as_str) == Some("ref") {
+                found.extend(table.get("env").and_then(toml::Value::as_str));
+                let of: Vec<&str> = table.get("of").map(|of| string_items(of).collect()).unwrap_or_default();
+                if let ["env", env_name, _, ..] = of.as_slice() {
+                    found.push(*env_name);
+                }
+            }
+            table.values().for_each(|value| referenced_envs(value, found));
+        }
+        toml::Value::Array(items) => items.iter().for_each(|item| referenced_envs(item, found)),
+        _ => {}
+    }
+}
+
+/// The tox configuration under `prefix` as plain values, however it is split over tables, dotted keys and inline
+/// tables.
+fn tox_config(tables: &Tables, prefix: &str) -> toml::Table {
+    let mut config = toml::Table::new();
+    for (header, positions) in &tables.header_to_pos {
+        let Some(relative) = strip_prefix(header, prefix) else {
+            continue;
+        };
+        for pos in positions {
+            let table = tables.table_set[*pos].borrow();
+            for_entries(&table, &mut |key, entry| {
+                let Ok(mut parsed) = format!("value = {}", entry.text()).parse::<toml::Table>() else {
+                    return;
+                };
+                let mut path = key_segments(relative);
+                path.extend(key_segments(&key));
+                if let Some(value) = parsed.remove("value") {
+                    insert_at(&mut config, &path, value);
+                }
             });
         }
     }
+    config
+}
 
-    env_order
+/// The unquoted parts of a dotted key, `env."3.13".deps` giving `env`, `3.13` and `deps`.
+fn key_segments(key: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    for (at, c) in key.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '.' if !in_quotes => {
+                segments.push(&key[start..at]);
+                start = at + 1;
+            }
+            _ => {}
+        }
+    }
+    segments.push(&key[start..]);
+    segments
+        .into_iter()
+        .map(|segment| strip_quotes(segment.trim()))
+        .filter(|segment| !segment.is_empty())
+        .collect()
 }
 
-pub fn reorder_tables(root_ast: &SyntaxNode, tables: &Tables, root_table_spacing: &str, sub_table_spacing: &str) {
-    let env_list_order = get_env_list_order(tables);
-    let mut order: Vec<&str> = vec!["", "env_run_base", "env_pkg_base", "env_base"];
+fn get_at<'a>(config: &'a toml::Table, path: &[String]) -> Option<&'a toml::Value> {
+    let (first, rest) = path.split_first()?;
+    let mut value = config.get(first)?;
+    for segment in rest {
+        value = value.as_table()?.get(segment)?;
+    }
+    Some(value)
+}
 
-    let env_refs: Vec<&str> = env_list_order.iter().map(|s| s.as_str()).collect();
-    let env_list_set: HashSet<&str> = env_refs.iter().copied().collect();
-    order.extend(env_refs);
+fn insert_at(config: &mut toml::Table, path: &[String], value: toml::Value) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut table = config;
+    for segment in parents {
+        let entry = table
+            .entry(segment.clone())
+            .or_insert(toml::Value::Table(toml::Table::new()));
+        let toml::Value::Table(next) = entry else {
+            return;
+        };
+        table = next;
+    }
+    table.insert(last.clone(), value);
+}
 
-    let mut remaining_envs: Vec<&String> = tables
-        .header_to_pos
-        .keys()
-        .filter(|k| k.starts_with("env.") && count_unquoted_dots(k) == 1 && !env_list_set.contains(k.as_str()))
+/// Moves the settings every run environment repeats into `[env_run_base]`, which they all inherit from, and explains a
+/// `base` table for settings only some of them share (see [`common::explain`]). Run environments are the `[env.*]`
+/// tables inheriting `env_run_base`, by having no `base` of their own or by listing it there; package environments such
+/// as `.pkg` are excluded. Nothing is moved while `env_list`, a label, `depends` or a `ref` substitution names an
+/// environment without a table, as that environment would inherit the moved settings too.
+pub fn hoist_shared(tables: &mut Tables) {
+    let mut declared = HashSet::new();
+    let mut run_envs: Vec<(String, String, toml::Table)> = Vec::new();
+    for (table, cells) in env_tables(tables, "") {
+        let Some(env_name) = table.strip_prefix("env.").map(strip_quotes) else {
+            continue;
+        };
+        declared.insert(env_name.clone());
+        let settings = tox_config_of(&cells);
+        let run_base = settings
+            .get("base")
+            .is_none_or(|base| string_items(base).any(|base| base == "env_run_base"));
+        if !env_name.starts_with('.') && run_base {
+            run_envs.push((table.clone(), env_name, settings));
+        }
+    }
+    run_envs.sort_by(|lhs, rhs| natural_lexical_cmp(&lhs.1, &rhs.1));
+    let Some(((_, _, first), rest)) = run_envs.split_first() else {
+        return;
+    };
+    if rest.is_empty() {
+        return;
+    }
+    let config = tox_config(tables, "");
+    let untabled = env_list_envs(tables, "")
+        .0
+        .iter()
+        .map(String::as_str)
+        .chain(named_envs(&config))
+        .any(|env_name| !env_name.starts_with('.') && !declared.contains(env_name));
+    let base = tables
+        .get("env_run_base")
+        .map(|cells| tox_config_of(&cells))
+        .unwrap_or_default();
+    let hoisted: HashSet<&str> = first
+        .iter()
+        .filter(|(key, value)| {
+            !untabled
+                && *key != "base"
+                && rest.iter().all(|(_, _, settings)| settings.get(*key) == Some(*value))
+                && base.get(*key).is_none_or(|inherited| inherited == *value)
+        })
+        .map(|(key, _)| key.as_str())
         .collect();
-    remaining_envs.sort_by_key(|a| a.to_lowercase());
-    let remaining_env_refs: Vec<&str> = remaining_envs.iter().map(|s| s.as_str()).collect();
-    order.extend(remaining_env_refs);
 
-    order.push("env");
+    suggest_shared_base(&run_envs, &hoisted);
+    if hoisted.is_empty() {
+        return;
+    }
+    let mut moved = Vec::new();
+    for (table, _, _) in &run_envs {
+        for cell in tables.get(table).into_iter().flatten() {
+            let removed = remove_entries(&mut cell.borrow_mut(), &hoisted);
+            if moved.is_empty() {
+                moved = removed;
+            }
+        }
+    }
+    moved.retain(|(key, _)| !base.contains_key(key));
+    append_entries(tables, "env_run_base", moved.iter().map(|(_, text)| text.as_str()));
+    if let Some(cells) = tables.get("env_run_base") {
+        for cell in cells {
+            reorder_env_keys(&mut cell.borrow_mut());
+        }
+    }
+}
+
+/// Environments a label, `depends` or a `{ replace = "ref", ... }` substitution names.
+fn named_envs(config: &toml::Table) -> Vec<&str> {
+    let mut found = Vec::new();
+    config.values().for_each(|value| referenced_envs(value, &mut found));
+    let labels = config
+        .get("labels")
+        .and_then(toml::Value::as_table)
+        .into_iter()
+        .flat_map(toml::Table::values);
+    let envs = config
+        .get("env")
+        .and_then(toml::Value::as_table)
+        .into_iter()
+        .flat_map(toml::Table::values);
+    let depends = config
+        .get("env_run_base")
+        .into_iter()
+        .chain(envs)
+        .filter_map(|section| section.get("depends"));
+    found.extend(labels.chain(depends).flat_map(string_items));
+    found
+}
+
+/// Suggests a `base` table for each group of settings that the same two or more run environments, but not all of them,
+/// share. Only a suggestion, so it is recorded as an explanation rather than reported as a problem.
+fn suggest_shared_base(run_envs: &[(String, String, toml::Table)], hoisted: &HashSet<&str>) {
+    if !is_recording() {
+        return;
+    }
+    let mut groups: BTreeMap<Vec<&str>, Vec<&str>> = BTreeMap::new();
+    for (_, env_name, settings) in run_envs {
+        for (key, value) in settings {
+            if hoisted.contains(key.as_str()) {
+                continue;
+            }
+            let sharing: Vec<&str> = run_envs
+                .iter()
+                .filter(|(_, _, other)| other.get(key) == Some(value))
+                .map(|(_, other_name, _)| other_name.as_str())
+                .collect();
+            if sharing.len() > 1 && sharing.first() == Some(&env_name.as_str()) {
+                groups.entry(sharing).or_default().push(key.as_str());
+            }
+        }
+    }
+    for (envs, keys) in groups {
+        let common: Vec<&str> = envs[0]
+            .split('-')
+            .filter(|factor| {
+                envs.iter()
+                    .all(|env_name| env_name.split('-').any(|other| other == *factor))
+            })
+            .collect();
+        let name = if common.is_empty() {
+            String::from("shared")
+        } else {
+            common.join("-")
+        };
+        record_path(
+            &format!("env.{}", envs[0]),
+            "tox/suggest-shared-base",
+            &format!("{} share {}", envs.join(", "), keys.join(", ")),
+            &format!("[{name}] with base = [\"{name}\", \"env_run_base\"] in each"),
+        );
+    }
+}
+
+/// The settings of one table, keyed by their top-level key.
+fn tox_config_of(cells: &[&RefCell<Vec<SyntaxElement>>]) -> toml::Table {
+    let mut config = toml::Table::new();
+    for cell in cells {
+        for_entries(&cell.borrow(), &mut |key, entry| {
+            if let Ok(mut parsed) = format!("value = {}", entry.text()).parse::<toml::Table>() {
+                if let Some(value) = parsed.remove("value") {
+                    insert_at(&mut config, &key_segments(&key), value);
+                }
+            }
+        });
+    }
+    config
+}
+
+/// Removes the entries whose top-level key is in `keys`, returning that key and the text of each, including the comment
+/// lines directly above it.
+fn remove_entries(table: &mut Vec<SyntaxElement>, keys: &HashSet<&str>) -> Vec<(String, String)> {
+    use tombi_syntax::SyntaxKind::{COMMENT, KEY_VALUE, LINE_BREAK, WHITESPACE};
+    let mut removed = Vec::new();
+    let mut idx = 0;
+    while idx < table.len() {
+        let top_key = (table[idx].kind() == KEY_VALUE)
+            .then(|| get_key_name(&table[idx]))
+            .flatten()
+            .and_then(|key| key_segments(&key).into_iter().next());
+        match top_key {
+            Some(key) if keys.contains(key.as_str()) => {
+                let mut start = idx;
+                while start >= 2 && table[start - 1].kind() == LINE_BREAK && table[start - 2].kind() == COMMENT {
+                    start -= 2;
+                }
+                let text: String = table.drain(start..=idx).map(|element| element.to_string()).collect();
+                removed.push((key, text));
+                idx = start;
+                while idx < table.len() && matches!(table[idx].kind(), WHITESPACE | LINE_BREAK) {
+                    table.remove(idx);
+                }
+            }
+            _ => idx += 1,
+        }
+    }
+    removed
+}
+
+/// Appends `key = value` entries to the table `name`, creating the table when missing.
+fn append_entries<'a>(tables: &mut Tables, name: &str, entries: impl Iterator<Item = &'a str>) {
+    use tombi_syntax::SyntaxKind::{COMMENT, DANGLING_COMMENT_GROUP, KEY_VALUE, KEY_VALUE_GROUP, LINE_BREAK};
+    let text: String = entries.map(|entry| format!("{entry}\n")).collect();
+    if text.is_empty() {
+        return;
+    }
+    let mut new_entries = Vec::new();
+    for child in crate::parse(&text).children_with_tokens() {
+        if [KEY_VALUE_GROUP, DANGLING_COMMENT_GROUP].contains(&child.kind()) {
+            new_entries.extend(child.as_node().unwrap().children_with_tokens());
+        } else if [KEY_VALUE, LINE_BREAK, COMMENT].contains(&child.kind()) {
+            new_entries.push(child);
+        }
+    }
+    if let Some(cells) = tables.get(name) {
+        let mut table = cells[0].borrow_mut();
+        if table.last().is_some_and(|last| last.kind() != LINE_BREAK) {
+            table.push(make_newline());
+        }
+        table.extend(new_entries);
+        return;
+    }
+    let mut table = make_table_entry(name);
+    table.extend(new_entries);
+    let pos = tables.table_set.len();
+    tables.table_set.push(RefCell::new(table));
+    tables.header_to_pos.entry(String::from(name)).or_default().push(pos);
+}
+
+pub fn reorder_tables(root_ast: &SyntaxNode, tables: &Tables, root_table_spacing: &str, sub_table_spacing: &str) {
+    let table_order = table_order_with_prefix(tables, "");
+    let mut order: Vec<&str> = vec![""];
+    order.extend(table_order.iter().map(String::as_str));
 
     tables.reorder_with_key_order(
         root_ast,
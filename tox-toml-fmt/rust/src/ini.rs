@@ -0,0 +1,529 @@
+//! Converts a legacy `tox.ini` into `tox.toml` text, ready for [`crate::format_toml`].
+//!
+//! Sections map to tables (`[tox]` to the root, `[testenv]` to `env_run_base`, `[testenv:name]` to `env.name`,
+//! `[pkgenv]` to `env_pkg_base`), legacy keys take their tox 4 names and values get the type tox expects: multi-line
+//! `deps` become arrays, each `commands` line an argument list. Substitutions standing for a whole value or argument
+//! (`{posargs}`, `{env:X}`, `{[section]key}`) become `{ replace = ... }` inline tables. What has no TOML form, such as
+//! generative environment lists or factor-conditional lines, is kept as a `# TODO` comment.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::command::{self, posargs, quote};
+use crate::global::{ENV_ALIASES, ROOT_ALIASES};
+
+struct Section {
+    name: String,
+    items: Vec<Item>,
+}
+
+enum Item {
+    Comment(String),
+    Setting { key: String, lines: Vec<String> },
+}
+
+/// Where a section ends up in `tox.toml`.
+enum Target {
+    Root,
+    Table(String),
+    Todo(String),
+}
+
+/// A converted value and the notes on what could not be carried over.
+#[derive(Default)]
+struct Converted {
+    value: Option<String>,
+    todos: Vec<String>,
+}
+
+const BOOL_KEYS: &[&str] = &[
+    "no_package",
+    "skip_missing_interpreters",
+    "ignore_base_python_conflict",
+    "skip_install",
+    "use_develop",
+    "system_site_packages",
+    "always_copy",
+    "download",
+    "recreate",
+    "ignore_errors",
+    "ignore_outcome",
+    "parallel_show_output",
+    "pip_pre",
+    "args_are_paths",
+    "fail_fast",
+    "constrain_package_deps",
+    "use_frozen_constraints",
+];
+const NUMBER_KEYS: &[&str] = &[
+    "suicide_timeout",
+    "interrupt_timeout",
+    "terminate_timeout",
+    "commands_retry",
+];
+/// Lists written one entry per line; requirement specifiers contain commas, so only newlines separate entries.
+const LINE_LIST_KEYS: &[&str] = &["deps", "constraints", "requires"];
+/// Lists whose entries may also share a line, separated by commas.
+const COMMA_LIST_KEYS: &[&str] = &[
+    "env_list",
+    "extras",
+    "depends",
+    "labels",
+    "dependency_groups",
+    "allowlist_externals",
+    "pass_env",
+    "base_python",
+];
+const COMMAND_LIST_KEYS: &[&str] = &["commands", "commands_pre", "commands_post", "extra_setup_commands"];
+const COMMAND_KEYS: &[&str] = &["install_command", "list_dependencies_command"];
+/// Substitution names of `tox.ini` and their `tox.toml` spelling.
+const LEGACY_SUBSTITUTIONS: &[(&str, &str)] = &[
+    ("toxinidir", "tox_root"),
+    ("toxworkdir", "work_dir"),
+    ("envname", "env_name"),
+    ("envdir", "env_dir"),
+    ("envtmpdir", "env_tmp_dir"),
+    ("envlogdir", "env_log_dir"),
+    ("envsitepackagesdir", "env_site_packages_dir"),
+    ("envbindir", "env_bin_dir"),
+    ("envpython", "env_python"),
+    ("distdir", "dist_dir"),
+];
+
+/// The `tox.toml` equivalent of `ini`, unformatted.
+#[must_use]
+pub fn ini_to_toml(ini: &str) -> String {
+    ini_to_toml_with_todos(ini).0
+}
+
+/// The `tox.toml` equivalent of `ini`, unformatted, and what it could not carry over; each of those is also left as a
+/// `# TODO` comment in the text.
+#[must_use]
+pub fn ini_to_toml_with_todos(ini: &str) -> (String, Vec<String>) {
+    let (preamble, sections) = parse(ini);
+    let mut todos = Vec::new();
+    let referenced = referenced_sections(&sections);
+    let mut header = preamble;
+    let mut root = String::new();
+    let mut tables = String::new();
+    for section in &sections {
+        match target(&section.name, &referenced) {
+            Target::Root => write_items(&mut root, &section.items, true, &mut todos),
+            Target::Table(name) => {
+                let _ = writeln!(tables, "\n[{name}]");
+                write_items(&mut tables, &section.items, false, &mut todos);
+            }
+            Target::Todo(reason) => {
+                header.push(format!("TODO: {reason}, not converted:"));
+                todos.push(reason);
+                header.push(format!("[{}]", section.name));
+                for item in &section.items {
+                    match item {
+                        Item::Comment(text) => header.push(format!("# {text}")),
+                        Item::Setting { key, lines } => {
+                            header.push(format!("{key} = {}", lines.first().map_or("", String::as_str)));
+                            header.extend(lines.iter().skip(1).map(|line| format!("    {line}")));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    let mut out = String::new();
+    for line in header {
+        let _ = writeln!(out, "# {line}");
+    }
+    out.push_str(&root);
+    out.push_str(&tables);
+    (out.trim_start_matches('\n').to_string(), todos)
+}
+
+/// Splits `ini` into sections the way `configparser` reads `tox.ini`. Lines before the first section, and lines that
+/// are neither a setting nor a continuation, come back as notes for the top of the file.
+fn parse(ini: &str) -> (Vec<String>, Vec<Section>) {
+    let mut preamble = Vec::new();
+    let mut sections: Vec<Section> = Vec::new();
+    for raw in ini.lines() {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Some(text) = trimmed.strip_prefix('#').or_else(|| trimmed.strip_prefix(';')) {
+            let indented = raw.starts_with([' ', '\t']);
+            match sections.last_mut() {
+                // A commented out line of a multi-line value, dropped like `configparser` does.
+                Some(section) if indented && matches!(section.items.last(), Some(Item::Setting { .. })) => {}
+                Some(section) => section.items.push(Item::Comment(text.trim().to_string())),
+                None => preamble.push(text.trim().to_string()),
+            }
+            continue;
+        }
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            sections.push(Section {
+                name: trimmed[1..trimmed.len() - 1].trim().to_string(),
+                items: vec![],
+            });
+            continue;
+        }
+        let Some(section) = sections.last_mut() else {
+            preamble.push(format!("TODO: outside of any section, not converted: {trimmed}"));
+            continue;
+        };
+        let value = strip_inline_comment(trimmed);
+        if raw.starts_with([' ', '\t']) {
+            if let Some(Item::Setting { lines, .. }) = section.items.last_mut() {
+                lines.push(value);
+                continue;
+            }
+        }
+        match trimmed.find(['=', ':']) {
+            Some(at) => {
+                let first = strip_inline_comment(trimmed[at + 1..].trim());
+                section.items.push(Item::Setting {
+                    key: trimmed[..at].trim().to_string(),
+                    lines: if first.is_empty() { vec![] } else { vec![first] },
+                });
+            }
+            None => section
+                .items
+                .push(Item::Comment(format!("TODO: not a setting, not converted: {trimmed}"))),
+        }
+    }
+    (preamble, sections)
+}
+
+/// Drops a ` # comment` at the end of a value; `\#` stands for a literal `#`.
+fn strip_inline_comment(value: &str) -> String {
+    static COMMENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s+#.*$").unwrap());
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(at) = rest.find("\\#") {
+        let (head, tail) = rest.split_at(at);
+        if COMMENT.is_match(head) {
+            break;
+        }
+        out.push_str(head);
+        out.push('#');
+        rest = &tail[2..];
+    }
+    out.push_str(&COMMENT.replace(rest, ""));
+    out.trim().to_string()
+}
+
+/// Sections named by a `{[section]key}` substitution, which must survive as tables for the reference to resolve.
+fn referenced_sections(sections: &[Section]) -> HashSet<String> {
+    static REFERENCE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{\[([^\]]+)\][^}]+\}").unwrap());
+    sections
+        .iter()
+        .flat_map(|section| &section.items)
+        .filter_map(|item| match item {
+            Item::Setting { lines, .. } => Some(lines),
+            Item::Comment(_) => None,
+        })
+        .flatten()
+        .flat_map(|line| REFERENCE.captures_iter(line).map(|caps| caps[1].trim().to_string()))
+        .collect()
+}
+
+fn target(section: &str, referenced: &HashSet<String>) -> Target {
+    match section {
+        "tox" => Target::Root,
+        "testenv" => Target::Table(String::from("env_run_base")),
+        "pkgenv" => Target::Table(String::from("env_pkg_base")),
+        _ => match section.strip_prefix("testenv:") {
+            Some(env) if env.contains('{') => Target::Todo(format!(
+                "generative environment section [{section}], write one [env.<name>] table per environment"
+            )),
+            Some(env) => Target::Table(format!("env.{}", toml_key(env.trim()))),
+            None if referenced.contains(section) => Target::Table(toml_key(section)),
+            None => Target::Todo(format!("section [{section}] is not tox configuration")),
+        },
+    }
+}
+
+fn write_items(out: &mut String, items: &[Item], root: bool, todos: &mut Vec<String>) {
+    let aliases = if root { ROOT_ALIASES } else { ENV_ALIASES };
+    for item in items {
+        match item {
+            Item::Comment(text) => {
+                let _ = writeln!(out, "# {text}");
+            }
+            Item::Setting { key, lines } => {
+                let key = aliases
+                    .iter()
+                    .find(|(alias, _)| *alias == key.as_str())
+                    .map_or(key.as_str(), |(_, name)| *name);
+                let converted = convert(key, lines, root);
+                for todo in converted.todos {
+                    let _ = writeln!(out, "# TODO: {todo}");
+                    todos.push(todo);
+                }
+                if let Some(value) = converted.value {
+                    let _ = writeln!(out, "{} = {value}", toml_key(key));
+                }
+            }
+        }
+    }
+}
+
+fn convert(key: &str, lines: &[String], root: bool) -> Converted {
+    let mut converted = Converted::default();
+    if root && key == "isolated_build" {
+        converted.todos.push(String::from(
+            "isolated_build has no tox 4 setting, packages are always built in isolation",
+        ));
+        return converted;
+    }
+    let lines: Vec<&str> = if root {
+        lines.iter().map(String::as_str).collect()
+    } else {
+        lines
+            .iter()
+            .filter(|line| {
+                let conditional = is_factor_conditional(line);
+                if conditional {
+                    converted
+                        .todos
+                        .push(format!("factor-conditional {key} entry, not converted: {line}"));
+                }
+                !conditional
+            })
+            .map(String::as_str)
+            .collect()
+    };
+    converted.value = match key {
+        "env_list" if root => Some(env_list(&lines, &mut converted.todos)),
+        "labels" if root => Some(labels(&lines)),
+        "set_env" => Some(set_env(&lines, &mut converted.todos)),
+        "description" => Some(string_value(&lines.join(" "))),
+        _ if COMMAND_LIST_KEYS.contains(&key) => Some(commands(&lines, &mut converted.todos)),
+        _ if COMMAND_KEYS.contains(&key) => Some(array(&argv(&lines.join(" ")))),
+        _ if LINE_LIST_KEYS.contains(&key) => {
+            Some(array(&lines.iter().map(|line| list_item(line)).collect::<Vec<_>>()))
+        }
+        _ if COMMA_LIST_KEYS.contains(&key) => Some(array(
+            &comma_split(&lines)
+                .iter()
+                .map(|entry| list_item(entry))
+                .collect::<Vec<_>>(),
+        )),
+        _ => scalar(key, &lines),
+    };
+    converted
+}
+
+fn scalar(key: &str, lines: &[&str]) -> Option<String> {
+    match lines {
+        [] => None,
+        [value] if BOOL_KEYS.contains(&key) && ["true", "false"].contains(&value.to_lowercase().as_str()) => {
+            Some(value.to_lowercase())
+        }
+        [value] if NUMBER_KEYS.contains(&key) && value.parse::<f64>().is_ok() => Some((*value).to_string()),
+        [value] => Some(string_value(value)),
+        _ => Some(array(&lines.iter().map(|line| list_item(line)).collect::<Vec<_>>())),
+    }
+}
+
+/// A line guarded by factors, e.g. `py312: pytest-xdist` or `!windows,py3: coverage`.
+fn is_factor_conditional(line: &str) -> bool {
+    static FACTORS: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^!?[\w.{}-]+(,\s*!?[\w.{}-]+)*\s*:(\s|$)").unwrap());
+    FACTORS.is_match(line)
+}
+
+/// Splits on commas outside of braces, so `py3{11,12}` stays one entry.
+fn comma_split<'a>(lines: &[&'a str]) -> Vec<&'a str> {
+    let mut entries = Vec::new();
+    for line in lines {
+        let mut depth = 0_usize;
+        let mut start = 0;
+        for (at, c) in line.char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    entries.push(line[start..at].trim());
+                    start = at + 1;
+                }
+                _ => {}
+            }
+        }
+        entries.push(line[start..].trim());
+    }
+    entries.retain(|entry| !entry.is_empty());
+    entries
+}
+
+fn env_list(lines: &[&str], todos: &mut Vec<String>) -> String {
+    let mut envs = Vec::new();
+    for env in comma_split(lines) {
+        if env.contains('{') {
+            todos.push(format!(
+                "generative environment list entry, list the environments explicitly: {env}"
+            ));
+        } else {
+            envs.push(quote(env));
+        }
+    }
+    array(&envs)
+}
+
+fn labels(lines: &[&str]) -> String {
+    let entries: Vec<String> = lines
+        .iter()
+        .filter_map(|line| line.split_once('='))
+        .map(|(label, envs)| {
+            let envs: Vec<String> = comma_split(&[envs]).iter().map(|env| quote(env)).collect();
+            format!("{} = {}", toml_key(label.trim()), array(&envs))
+        })
+        .collect();
+    format!("{{ {} }}", entries.join(", "))
+}
+
+fn set_env(lines: &[&str], todos: &mut Vec<String>) -> String {
+    let mut entries = Vec::new();
+    for line in lines {
+        match line.split_once('=') {
+            Some((name, value)) => entries.push(format!("{} = {}", toml_key(name.trim()), string_value(value.trim()))),
+            None => todos.push(format!("set_env entry without a value, not converted: {line}")),
+        }
+    }
+    format!("{{ {} }}", entries.join(", "))
+}
+
+fn commands(lines: &[&str], todos: &mut Vec<String>) -> String {
+    let mut joined: Vec<String> = Vec::new();
+    let mut continued = false;
+    for line in lines {
+        match joined.last_mut() {
+            Some(last) if continued => {
+                last.push(' ');
+                last.push_str(line.trim_end_matches('\\').trim());
+            }
+            _ => joined.push(line.trim_end_matches('\\').trim().to_string()),
+        }
+        continued = line.ends_with('\\');
+    }
+    let mut commands = Vec::new();
+    for line in &joined {
+        if let Some(reference) = reference(line) {
+            commands.push(reference);
+            continue;
+        }
+        let line = match line.strip_prefix('-') {
+            Some(rest) => {
+                todos.push(format!(
+                    "tox.ini ignored the exit code of `{}`, set ignore_errors or handle it in the command",
+                    rest.trim()
+                ));
+                rest.trim()
+            }
+            None => line,
+        };
+        commands.push(array(&argv(line)));
+    }
+    array(&commands)
+}
+
+/// Splits a command line into arguments, keeping the whole line as one argument when a quote is left open.
+fn argv(line: &str) -> Vec<String> {
+    command::split(line)
+        .unwrap_or_else(|| vec![line.to_string()])
+        .iter()
+        .map(|word| argument(word))
+        .collect()
+}
+
+fn argument(arg: &str) -> String {
+    posargs(&modern_substitutions(arg))
+        .or_else(|| environment(arg))
+        .unwrap_or_else(|| quote(&modern_substitutions(arg)))
+}
+
+/// An entry of a list value: substitutions expanding to lists extend it.
+fn list_item(value: &str) -> String {
+    reference(value)
+        .or_else(|| posargs(&modern_substitutions(value)))
+        .unwrap_or_else(|| string_value(value))
+}
+
+fn string_value(value: &str) -> String {
+    environment(value)
+        .or_else(|| reference(value).map(|reference| reference.replace(", extend = true }", " }")))
+        .unwrap_or_else(|| quote(&modern_substitutions(value)))
+}
+
+fn environment(value: &str) -> Option<String> {
+    let inner = value.strip_prefix("{env:")?.strip_suffix('}')?;
+    if inner.contains(['{', '}']) {
+        return None;
+    }
+    Some(match inner.split_once(':') {
+        Some((name, default)) => format!(
+            "{{ replace = \"env\", name = {}, default = {} }}",
+            quote(name),
+            quote(&modern_substitutions(default))
+        ),
+        None => format!("{{ replace = \"env\", name = {} }}", quote(inner)),
+    })
+}
+
+/// `{[section]key}` as a reference; environments are referenced by name, other tables by their path.
+fn reference(value: &str) -> Option<String> {
+    let inner = value.strip_prefix("{[")?.strip_suffix('}')?;
+    let (section, key) = inner.split_once(']')?;
+    if key.is_empty() || key.contains(['{', '}']) {
+        return None;
+    }
+    let key = ENV_ALIASES
+        .iter()
+        .chain(ROOT_ALIASES)
+        .find(|(alias, _)| *alias == key)
+        .map_or(key, |(_, name)| *name);
+    Some(match section.trim() {
+        "tox" => format!("{{ replace = \"ref\", of = [{}], extend = true }}", quote(key)),
+        "testenv" => format!(
+            "{{ replace = \"ref\", of = [\"env_run_base\", {}], extend = true }}",
+            quote(key)
+        ),
+        "pkgenv" => format!(
+            "{{ replace = \"ref\", of = [\"env_pkg_base\", {}], extend = true }}",
+            quote(key)
+        ),
+        section => match section.strip_prefix("testenv:") {
+            Some(env) => format!(
+                "{{ replace = \"ref\", env = {}, key = {}, extend = true }}",
+                quote(env.trim()),
+                quote(key)
+            ),
+            None => format!(
+                "{{ replace = \"ref\", of = [{}, {}], extend = true }}",
+                quote(section),
+                quote(key)
+            ),
+        },
+    })
+}
+
+fn modern_substitutions(value: &str) -> String {
+    LEGACY_SUBSTITUTIONS
+        .iter()
+        .fold(value.to_string(), |value, (legacy, modern)| {
+            value.replace(&format!("{{{legacy}}}"), &format!("{{{modern}}}"))
+        })
+}
+
+fn array(items: &[String]) -> String {
+    format!("[{}]", items.join(", "))
+}
+
+fn toml_key(key: &str) -> String {
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        key.to_string()
+    } else {
+        quote(key)
+    }
+}
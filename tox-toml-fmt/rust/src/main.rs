@@ -3,6 +3,7 @@ use std::string::String;
 
 #[cfg(feature = "extension-module")]
 use pyo3::prelude::{PyModule, PyModuleMethods};
+#[cfg(feature = "python")]
 use pyo3::{pyclass, pymethods};
 #[cfg(feature = "extension-module")]
 use pyo3::{pyfunction, pymodule, wrap_pyfunction, Bound, PyResult, Python};
@@ -11,16 +12,31 @@ use tombi_config::TomlVersion;
 use tombi_syntax::SyntaxKind::KEY_VALUE;
 
 use crate::global::{
-    fix_envs, fix_root, normalize_aliases, normalize_strings, reorder_inline_tables, reorder_tables, sort_env_list,
+    check_base_python, check_references, check_schema, fix_envs, fix_root, hoist_shared, normalize_aliases,
+    normalize_strings, reorder_inline_tables, reorder_tables, sort_env_list,
 };
 use common::array::ensure_all_arrays_multiline;
+#[cfg(feature = "extension-module")]
+use common::diagnostic::Diagnostic;
+use common::diagnostic::Diagnostics;
+use common::explain::record_edits;
+#[cfg(feature = "extension-module")]
+use common::explain::Explanation;
+use common::formatted::Formatted;
+use common::rules::Rules;
 use common::table::{apply_table_formatting, count_unquoted_dots, first_unquoted_dot, split_table_name, Tables};
 
+#[cfg(any(test, feature = "cli"))]
+pub mod cli;
+pub mod command;
 pub mod global;
+pub mod ini;
 #[cfg(test)]
 mod tests;
 
-#[pyclass(frozen, get_all)]
+/// Options for [`format_toml`]. Build one with [`Settings::builder`]; [`Settings::default`] matches the
+/// `tox-toml-fmt` command line defaults.
+#[cfg_attr(feature = "python", pyclass(frozen, get_all))]
 pub struct Settings {
     column_width: usize,
     indent: usize,
@@ -31,13 +47,19 @@ pub struct Settings {
     collapse_tables: Vec<String>,
     skip_wrap_for_keys: Vec<String>,
     pin_envs: Vec<String>,
+    select: Vec<String>,
+    ignore: Vec<String>,
+    explain: bool,
+    hoist_shared: bool,
+    validate: bool,
 }
 
+#[cfg(feature = "python")]
 #[pymethods]
 impl Settings {
     #[new]
     #[allow(clippy::too_many_arguments)]
-    #[pyo3(signature = (*, column_width, indent, table_format, sub_table_spacing, separate_root_table, expand_tables, collapse_tables, skip_wrap_for_keys, pin_envs))]
+    #[pyo3(signature = (*, column_width, indent, table_format, sub_table_spacing, separate_root_table, expand_tables, collapse_tables, skip_wrap_for_keys, pin_envs, select = vec![], ignore = vec![], explain = false, hoist_shared = false, validate = false))]
     fn new(
         column_width: usize,
         indent: usize,
@@ -48,6 +70,11 @@ impl Settings {
         collapse_tables: Vec<String>,
         skip_wrap_for_keys: Vec<String>,
         pin_envs: Vec<String>,
+        select: Vec<String>,
+        ignore: Vec<String>,
+        explain: bool,
+        hoist_shared: bool,
+        validate: bool,
     ) -> Self {
         Self {
             column_width,
@@ -59,10 +86,142 @@ impl Settings {
             collapse_tables,
             skip_wrap_for_keys,
             pin_envs,
+            select,
+            ignore,
+            explain,
+            hoist_shared,
+            validate,
         }
     }
 }
 
+impl Settings {
+    /// Starts from the command line defaults, see [`SettingsBuilder`].
+    pub fn builder() -> SettingsBuilder {
+        SettingsBuilder::default()
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            column_width: 120,
+            indent: 2,
+            table_format: String::from("short"),
+            sub_table_spacing: String::new(),
+            separate_root_table: String::from("\n"),
+            expand_tables: vec![],
+            collapse_tables: vec![],
+            skip_wrap_for_keys: vec![],
+            pin_envs: vec![],
+            select: vec![],
+            ignore: vec![],
+            explain: false,
+            hoist_shared: false,
+            validate: false,
+        }
+    }
+}
+
+/// Builds [`Settings`], starting from the `tox-toml-fmt` command line defaults.
+#[derive(Default)]
+#[must_use]
+pub struct SettingsBuilder {
+    settings: Settings,
+}
+
+impl SettingsBuilder {
+    /// Column after which arrays and inline tables are split over lines and long strings are wrapped.
+    pub const fn column_width(mut self, column_width: usize) -> Self {
+        self.settings.column_width = column_width;
+        self
+    }
+
+    /// Spaces per indentation level.
+    pub const fn indent(mut self, indent: usize) -> Self {
+        self.settings.indent = indent;
+        self
+    }
+
+    /// `"short"` collapses sub-tables into dotted keys, `"long"` expands them to `[table.subtable]` headers.
+    pub fn table_format(mut self, table_format: impl Into<String>) -> Self {
+        self.settings.table_format = table_format.into();
+        self
+    }
+
+    /// Extra newlines between sub-tables in the same group.
+    pub fn sub_table_spacing(mut self, spacing: impl Into<String>) -> Self {
+        self.settings.sub_table_spacing = spacing.into();
+        self
+    }
+
+    /// Extra newlines between root table groups.
+    pub fn separate_root_table(mut self, spacing: impl Into<String>) -> Self {
+        self.settings.separate_root_table = spacing.into();
+        self
+    }
+
+    /// Tables always expanded, whatever the table format.
+    pub fn expand_tables(mut self, tables: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.settings.expand_tables = tables.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Tables always collapsed, whatever the table format or expanded tables.
+    pub fn collapse_tables(mut self, tables: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.settings.collapse_tables = tables.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Key patterns whose strings are never wrapped, with wildcards like `*.parse`.
+    pub fn skip_wrap_for_keys(mut self, keys: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.settings.skip_wrap_for_keys = keys.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Environments pinned to the start of `env_list`, in the given order.
+    pub fn pin_envs(mut self, envs: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.settings.pin_envs = envs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Rules to run, Ruff-style: `["project", "uv"]` runs only those passes. Empty runs every rule.
+    pub fn select(mut self, rules: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.settings.select = rules.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Rules to skip, e.g. `tox.alias-normalization`; the most specific of `select` and `ignore` wins.
+    pub fn ignore(mut self, rules: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.settings.ignore = rules.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Record the rule behind each change in [`Formatted::explanations`].
+    pub const fn explain(mut self, explain: bool) -> Self {
+        self.settings.explain = explain;
+        self
+    }
+
+    /// Move settings every environment repeats into `env_run_base`, see [`global::hoist_shared`].
+    pub const fn hoist_shared(mut self, hoist_shared: bool) -> Self {
+        self.settings.hoist_shared = hoist_shared;
+        self
+    }
+
+    /// Report references to undefined environments, `base_python` values contradicting the environment name, unknown
+    /// keys and values of the wrong type, see [`global::check_references`], [`global::check_base_python`] and
+    /// [`global::check_schema`].
+    pub const fn validate(mut self, validate: bool) -> Self {
+        self.settings.validate = validate;
+        self
+    }
+
+    pub fn build(self) -> Settings {
+        self.settings
+    }
+}
+
 pub struct TableFormatConfig {
     pub default_collapse: bool,
     pub expand_tables: HashSet<String>,
@@ -111,20 +270,70 @@ async fn format_with_tombi(content: &str, column_width: usize, indent: usize) ->
 #[cfg(feature = "extension-module")]
 #[pyfunction]
 #[pyo3(name = "format_toml")]
-fn format_toml_py(py: Python<'_>, content: &str, opt: &Settings) -> String {
+fn format_toml_py(py: Python<'_>, content: &str, opt: &Settings) -> Formatted {
     py.detach(|| format_toml(content, opt))
 }
 
+#[cfg(feature = "extension-module")]
+#[pyfunction]
+#[pyo3(name = "convert_ini")]
+fn convert_ini_py(py: Python<'_>, content: &str, opt: &Settings) -> Formatted {
+    py.detach(|| convert_ini(content, opt))
+}
+
+/// Converts a legacy `tox.ini` to `tox.toml` and formats it; what cannot be converted is kept as a `# TODO` comment.
+#[must_use]
+pub fn convert_ini(content: &str, opt: &Settings) -> Formatted {
+    format_toml(&ini::ini_to_toml(content), opt)
+}
+
+/// Entries the formatter cannot parse, such as an invalid requirement in `deps`, are left as written and reported in
+/// [`Formatted::diagnostics`] while the rest of the file is still formatted.
 #[must_use]
-pub fn format_toml(content: &str, opt: &Settings) -> String {
-    common::disabled::with_disabled_keys(content, |content| format_core(content, opt))
+pub fn format_toml(content: &str, opt: &Settings) -> Formatted {
+    let diagnostics = Diagnostics::default();
+    let (formatted, explanations) = common::explain::collect(opt.explain, || {
+        common::disabled::with_disabled_keys(content, |content| format_core(content, opt, &diagnostics))
+    });
+    Formatted {
+        content: formatted,
+        diagnostics: diagnostics.locate(content),
+        explanations,
+    }
 }
 
-fn format_core(content: &str, opt: &Settings) -> String {
+/// Every rule `select` and `ignore` can name; an entry covering none of them is reported.
+const RULES: &[&str] = &[
+    "pep508.lowercase-name",
+    "strings.wrap",
+    "tables.layout",
+    "tables.reorder",
+    "tox.alias-normalization",
+    "tox.base-python",
+    "tox.env-list-sort",
+    "tox.envs",
+    "tox.hoist-shared",
+    "tox.inline-tables",
+    "tox.references",
+    "tox.root",
+    "tox.schema",
+    "tox.strings",
+];
+
+fn format_core(content: &str, opt: &Settings, diagnostics: &Diagnostics) -> String {
     let root_ast = parse(content);
     common::string::normalize_key_quotes(&root_ast);
     let mut tables = Tables::from_ast(&root_ast);
     let table_config = TableFormatConfig::from_settings(opt);
+    let rules = Rules::new(&opt.select, &opt.ignore);
+    for (list, name) in rules.unknown(RULES) {
+        diagnostics.report(list, name, &format!("unknown rule {name}"));
+    }
+    let run = |rule: &str, tables: &mut Tables, fix: &dyn Fn(&mut Tables)| {
+        if rules.enabled(rule) {
+            fix(tables);
+        }
+    };
 
     let mut prefixes: Vec<String> = vec![
         String::from("env"),
@@ -144,19 +353,21 @@ fn format_core(content: &str, opt: &Settings) -> String {
         }
     }
     let prefix_refs: Vec<&str> = prefixes.iter().map(|s| s.as_str()).collect();
-    apply_table_formatting(
-        &mut tables,
-        |name| {
-            if let Some(rest) = name.strip_prefix("env.") {
-                if count_unquoted_dots(rest) == 0 {
-                    return false;
+    run("tables.layout", &mut tables, &|tables| {
+        apply_table_formatting(
+            tables,
+            |name| {
+                if let Some(rest) = name.strip_prefix("env.") {
+                    if count_unquoted_dots(rest) == 0 {
+                        return false;
+                    }
                 }
-            }
-            table_config.should_collapse(name)
-        },
-        &prefix_refs,
-        opt.column_width,
-    );
+                table_config.should_collapse(name)
+            },
+            &prefix_refs,
+            opt.column_width,
+        );
+    });
 
     tables.header_to_pos.retain(|name, positions| {
         if !prefixes.contains(name) {
@@ -167,17 +378,44 @@ fn format_core(content: &str, opt: &Settings) -> String {
             .all(|&pos| tables.table_set[pos].borrow().iter().any(|e| e.kind() == KEY_VALUE))
     });
 
-    normalize_aliases(&tables);
-    fix_root(&tables);
-    fix_envs(&tables);
-    sort_env_list(&tables, &opt.pin_envs);
-    normalize_strings(&tables);
-    reorder_inline_tables(&root_ast);
-    reorder_tables(&root_ast, &tables, &opt.separate_root_table, &opt.sub_table_spacing);
+    run("tox.alias-normalization", &mut tables, &|tables| {
+        normalize_aliases(tables)
+    });
+    let lowercase_name = rules.enabled("pep508.lowercase-name");
+    run("tox.root", &mut tables, &|tables| {
+        fix_root(tables, lowercase_name, diagnostics)
+    });
+    run("tox.envs", &mut tables, &|tables| {
+        fix_envs(tables, lowercase_name, diagnostics)
+    });
+    if opt.hoist_shared {
+        run("tox.hoist-shared", &mut tables, &|tables| hoist_shared(tables));
+    }
+    if opt.validate {
+        run("tox.references", &mut tables, &|tables| {
+            check_references(tables, diagnostics)
+        });
+        run("tox.base-python", &mut tables, &|tables| {
+            check_base_python(tables, diagnostics)
+        });
+        run("tox.schema", &mut tables, &|tables| check_schema(tables, diagnostics));
+    }
+    run("tox.env-list-sort", &mut tables, &|tables| {
+        sort_env_list(tables, &opt.pin_envs)
+    });
+    run("tox.strings", &mut tables, &|tables| normalize_strings(tables));
+    if rules.enabled("tox.inline-tables") {
+        reorder_inline_tables(&root_ast);
+    }
+    if rules.enabled("tables.reorder") {
+        reorder_tables(&root_ast, &tables, &opt.separate_root_table, &opt.sub_table_spacing);
+    }
     ensure_all_arrays_multiline(&root_ast, opt.column_width);
 
     let indent_string = " ".repeat(opt.indent);
-    common::string::wrap_all_long_strings(&root_ast, opt.column_width, &indent_string, &opt.skip_wrap_for_keys);
+    run("strings.wrap", &mut tables, &|_| {
+        common::string::wrap_all_long_strings(&root_ast, opt.column_width, &indent_string, &opt.skip_wrap_for_keys);
+    });
 
     let modified_content = root_ast.to_string();
 
@@ -186,15 +424,19 @@ fn format_core(content: &str, opt: &Settings) -> String {
         .build()
         .unwrap();
     let formatted = rt.block_on(format_with_tombi(&modified_content, opt.column_width, opt.indent));
+    record_edits("tombi/reformat", &modified_content, &formatted);
 
     let formatted_ast = parse(&formatted);
     common::array::align_array_comments(&formatted_ast);
     let aligned = formatted_ast.to_string();
+    record_edits("layout/align-comments", &formatted, &aligned);
 
     let sub_spacing = (opt.table_format == "long").then_some(opt.sub_table_spacing.as_str());
     let result =
         common::table::normalize_table_spacing(&aligned, &["env_base", "env"], &opt.separate_root_table, sub_spacing);
-    common::util::limit_blank_lines(&result, 2)
+    let result = common::util::limit_blank_lines(&result, 2);
+    record_edits("layout/table-spacing", &aligned, &result);
+    result
 }
 
 /// # Errors
@@ -207,6 +449,10 @@ fn format_core(content: &str, opt: &Settings) -> String {
 #[pyo3(name = "_lib")]
 pub fn _lib(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(format_toml_py, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_ini_py, m)?)?;
     m.add_class::<Settings>()?;
+    m.add_class::<Formatted>()?;
+    m.add_class::<Diagnostic>()?;
+    m.add_class::<Explanation>()?;
     Ok(())
 }
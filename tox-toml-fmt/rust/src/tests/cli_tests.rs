@@ -0,0 +1,55 @@
+use std::fs;
+
+use common::cli::run;
+
+use crate::cli::ToxTomlFmt;
+
+#[test]
+fn test_cli_pins_envs_from_flag() {
+    let dir = std::env::temp_dir().join(format!("tox-toml-fmt-cli-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("tox.toml");
+    fs::write(&file, "env_list = [\"3.13\", \"lint\"]\n").unwrap();
+    assert_eq!(
+        run::<ToxTomlFmt>(["tox-toml-fmt", "--check", "--pin-env", "lint", dir.to_str().unwrap()]),
+        1
+    );
+    assert_eq!(fs::read_to_string(&file).unwrap(), "env_list = [\"3.13\", \"lint\"]\n");
+    assert_eq!(
+        run::<ToxTomlFmt>(["tox-toml-fmt", "--pin-env", "lint", dir.to_str().unwrap()]),
+        1
+    );
+    assert_eq!(
+        fs::read_to_string(&file).unwrap(),
+        "env_list = [ \"lint\", \"3.13\" ]\n"
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cli_from_ini() {
+    let dir = std::env::temp_dir().join(format!("tox-toml-fmt-cli-ini-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let ini = dir.join("tox.ini");
+    let target = dir.join("tox.toml");
+    fs::write(&ini, "[tox]\nenvlist = py312, lint\n\n[testenv]\ndeps = pytest\n").unwrap();
+    fs::write(dir.join("tox-toml-fmt.toml"), "column_width = 20\n").unwrap();
+    assert_eq!(
+        crate::cli::run(["tox-toml-fmt", "--check", "--from-ini", ini.to_str().unwrap()]),
+        1
+    );
+    assert!(!target.exists());
+    assert_eq!(
+        crate::cli::run(["tox-toml-fmt", "-n", "--from-ini", ini.to_str().unwrap()]),
+        1
+    );
+    let got = fs::read_to_string(&target).unwrap();
+    assert!(got.starts_with("env_list = [\n"), "{got}"); // wrapped at the column width of the shared config
+    assert!(got.contains("deps = [ \"pytest\" ]\n"), "{got}");
+    assert_eq!(
+        crate::cli::run(["tox-toml-fmt", "--from-ini", ini.to_str().unwrap()]),
+        1
+    );
+    assert!(fs::read_to_string(&target).unwrap().starts_with("env_list = [\n"));
+    fs::remove_dir_all(&dir).unwrap();
+}
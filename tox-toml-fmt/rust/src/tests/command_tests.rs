@@ -0,0 +1,97 @@
+use crate::command::{argv, posargs, quote, split};
+
+fn words(items: &[&str]) -> Option<Vec<String>> {
+    Some(items.iter().map(ToString::to_string).collect())
+}
+
+#[test]
+fn test_split_follows_shell_quoting() {
+    assert_eq!(
+        split(r#"python -c 'print("hi")' "a b""#),
+        words(&["python", "-c", "print(\"hi\")", "a b"])
+    );
+    assert_eq!(split(r#"echo "say 'hi'" ''"#), words(&["echo", "say 'hi'", ""]));
+    assert_eq!(split("  "), words(&[]));
+}
+
+#[test]
+fn test_split_keeps_backslashes() {
+    assert_eq!(
+        split(r#""C:\Program Files\python.exe" tests\unit c\ d"#),
+        words(&[r"C:\Program Files\python.exe", r"tests\unit", r"c\", "d"])
+    );
+}
+
+#[test]
+fn test_split_keeps_substitutions_whole() {
+    assert_eq!(
+        split("pytest {posargs:tests -v} {env:HOME:/tmp}"),
+        words(&["pytest", "{posargs:tests -v}", "{env:HOME:/tmp}"])
+    );
+}
+
+#[test]
+fn test_split_rejects_open_quote() {
+    assert_eq!(split("python -c 'print(1)"), None);
+}
+
+#[test]
+fn test_argv_quotes_arguments() {
+    assert_eq!(
+        argv(r#"pytest --junitxml "{work_dir}/junit.xml" {posargs}"#),
+        words(&[
+            "\"pytest\"",
+            "\"--junitxml\"",
+            "\"{work_dir}/junit.xml\"",
+            "{ replace = \"posargs\", extend = true }",
+        ])
+    );
+}
+
+#[test]
+fn test_argv_rejects_shell_syntax() {
+    for line in [
+        "git diff | cat",
+        "make && make install",
+        "a; b",
+        "python -m build > build.log",
+        "pytest 2>&1",
+        "echo `pwd`",
+        "echo $(pwd)",
+        "echo a\nb",
+        "",
+    ] {
+        assert_eq!(argv(line), None, "{line}");
+    }
+    assert_eq!(argv("echo '|'"), words(&["\"echo\"", "\"|\""]));
+}
+
+#[test]
+fn test_argv_rejects_backslash_outside_single_quotes() {
+    for line in [r"pytest tests\unit", r#"python "C:\dir\x.py""#, r"echo a\ b"] {
+        assert_eq!(argv(line), None, "{line}");
+    }
+    assert_eq!(
+        argv(r"python 'C:\dir\x.py'"),
+        words(&["\"python\"", r#""C:\\dir\\x.py""#])
+    );
+}
+
+#[test]
+fn test_posargs_forms() {
+    assert_eq!(
+        posargs("{posargs}").as_deref(),
+        Some("{ replace = \"posargs\", extend = true }")
+    );
+    assert_eq!(
+        posargs("{posargs:tests 'a b'}").as_deref(),
+        Some("{ replace = \"posargs\", default = [\"tests\", \"a b\"], extend = true }")
+    );
+    assert_eq!(posargs("{posargsx}"), None);
+    assert_eq!(posargs("posargs"), None);
+}
+
+#[test]
+fn test_quote_escapes() {
+    assert_eq!(quote("a\"b\\c\td\u{1}"), r#""a\"b\\c\td\u0001""#);
+}
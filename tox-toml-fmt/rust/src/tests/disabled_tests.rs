@@ -5,21 +5,11 @@ use super::assert_valid_toml;
 use crate::{format_toml, Settings};
 
 fn settings() -> Settings {
-    Settings {
-        column_width: 80,
-        indent: 2,
-        table_format: String::from("short"),
-        sub_table_spacing: String::new(),
-        separate_root_table: String::from("\n"),
-        expand_tables: vec![],
-        collapse_tables: vec![],
-        skip_wrap_for_keys: vec![],
-        pin_envs: vec![],
-    }
+    Settings::builder().column_width(80).build()
 }
 
 fn evaluate(start: &str) -> String {
-    let result = format_toml(start, &settings());
+    let result = format_toml(start, &settings()).content;
     assert_valid_toml(&result);
     assert!(
         !result.contains(MARKER),
@@ -5,20 +5,10 @@ use super::assert_valid_toml;
 use crate::{format_toml, Settings};
 
 fn format_toml_helper(start: &str, indent: usize) -> String {
-    let settings = Settings {
-        column_width: 120,
-        indent,
-        table_format: String::from("short"),
-        sub_table_spacing: String::new(),
-        separate_root_table: String::from("\n"),
-        expand_tables: vec![],
-        collapse_tables: vec![],
-        skip_wrap_for_keys: vec![],
-        pin_envs: vec![],
-    };
-    let got = format_toml(start, &settings);
+    let settings = Settings::builder().indent(indent).build();
+    let got = format_toml(start, &settings).content;
     assert_valid_toml(&got);
-    let second = format_toml(got.as_str(), &settings);
+    let second = format_toml(got.as_str(), &settings).content;
     assert_eq!(second, got, "formatting should be idempotent");
     got
 }
@@ -186,7 +176,7 @@ fn test_doc_labels() {
         labels = { test = ["3.14t", "3.14", "3.13", "3.12"], static = ["ruff", "mypy"] }
         "#};
     let got = format_toml_helper(start, 2);
-    assert_snapshot!(got, @r#"labels = { test = [ "3.14t", "3.14", "3.13", "3.12" ], static = [ "ruff", "mypy" ] }"#);
+    assert_snapshot!(got, @r#"labels = { test = [ "3.12", "3.13", "3.14", "3.14t" ], static = [ "mypy", "ruff" ] }"#);
 }
 
 #[test]
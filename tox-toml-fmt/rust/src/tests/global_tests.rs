@@ -2,7 +2,7 @@ use indoc::indoc;
 use insta::assert_snapshot;
 
 use super::{assert_valid_toml, format_syntax, parse};
-use crate::global::reorder_tables;
+use crate::global::{alias_target, reorder_tables};
 use common::table::Tables;
 
 fn reorder_table_helper(start: &str) -> String {
@@ -82,6 +82,35 @@ fn test_reorder_table_reorder_with_env_list() {
     "#);
 }
 
+#[test]
+fn test_reorder_table_reorder_with_env_list_product() {
+    let start = indoc! {r#"
+        env_list = [{ product = [["py313", "py312"], ["django50"]] }, "lint"]
+
+        [env.lint]
+        description = "lint"
+
+        [env.py312-django50]
+        description = "old"
+
+        [env.py313-django50]
+        description = "new"
+    "#};
+    let got = reorder_table_helper(start);
+    assert_snapshot!(got, @r#"
+    env_list = [ { product = [ [ "py313", "py312" ], [ "django50" ] ] }, "lint" ]
+
+    [env.py313-django50]
+    description = "new"
+
+    [env.py312-django50]
+    description = "old"
+
+    [env.lint]
+    description = "lint"
+    "#);
+}
+
 #[test]
 fn test_reorder_table_reorder_env_list_partial() {
     let start = indoc! {r#"
@@ -225,3 +254,18 @@ fn test_reorder_env_list_with_env_run_base() {
     description = "test"
     "#);
 }
+
+#[test]
+fn test_alias_target_by_table() {
+    assert_eq!(alias_target("", "envlist", ""), Some("env_list"));
+    assert_eq!(alias_target("env.test", "setenv", ""), Some("set_env"));
+    assert_eq!(alias_target("env_run_base", "basepython", ""), Some("base_python"));
+    assert_eq!(alias_target("tool.tox", "envlist", "tool.tox"), Some("env_list"));
+    assert_eq!(
+        alias_target("tool.tox.env.test", "passenv", "tool.tox"),
+        Some("pass_env")
+    );
+    assert_eq!(alias_target("env.test", "envlist", ""), None);
+    assert_eq!(alias_target("", "setenv", ""), None);
+    assert_eq!(alias_target("env.test.sub", "setenv", ""), None);
+}
@@ -0,0 +1,143 @@
+use indoc::indoc;
+use insta::assert_snapshot;
+
+use super::assert_valid_toml;
+use crate::ini::ini_to_toml;
+use crate::{convert_ini, Settings};
+
+fn convert(ini: &str) -> String {
+    let got = ini_to_toml(ini);
+    assert_valid_toml(&got);
+    got
+}
+
+#[test]
+fn test_ini_to_toml_maps_sections_and_keys() {
+    let ini = indoc! {r"
+        [tox]
+        envlist = py312, lint
+        isolated_build = true
+
+        [testenv]
+        deps =
+            pytest>=8
+            -r requirements.txt
+        commands = pytest {posargs:tests}
+        setenv =
+            COVERAGE_FILE = {toxworkdir}/.coverage
+        passenv = HOME, CI
+
+        [testenv:lint]
+        skip_install = true
+        commands =
+            ruff check .
+            ruff format --check
+    "};
+    assert_snapshot!(convert(ini), @r#"
+    env_list = ["py312", "lint"]
+    # TODO: isolated_build has no tox 4 setting, packages are always built in isolation
+
+    [env_run_base]
+    deps = ["pytest>=8", "-r requirements.txt"]
+    commands = [["pytest", { replace = "posargs", default = ["tests"], extend = true }]]
+    set_env = { COVERAGE_FILE = "{work_dir}/.coverage" }
+    pass_env = ["HOME", "CI"]
+
+    [env.lint]
+    skip_install = true
+    commands = [["ruff", "check", "."], ["ruff", "format", "--check"]]
+    "#);
+}
+
+#[test]
+fn test_ini_to_toml_keeps_unconvertible_as_todo() {
+    let ini = indoc! {r"
+        [tox]
+        envlist = py3{11,12}, lint
+
+        [testenv]
+        deps =
+            pytest
+            py312: pytest-xdist
+
+        [testenv:py{311,312}-cov]
+        commands = coverage run
+
+        [flake8]
+        max-line-length = 120
+    "};
+    assert_snapshot!(convert(ini), @r#"
+    # TODO: generative environment section [testenv:py{311,312}-cov], write one [env.<name>] table per environment, not converted:
+    # [testenv:py{311,312}-cov]
+    # commands = coverage run
+    # TODO: section [flake8] is not tox configuration, not converted:
+    # [flake8]
+    # max-line-length = 120
+    # TODO: generative environment list entry, list the environments explicitly: py3{11,12}
+    env_list = ["lint"]
+
+    [env_run_base]
+    # TODO: factor-conditional deps entry, not converted: py312: pytest-xdist
+    deps = ["pytest"]
+    "#);
+}
+
+#[test]
+fn test_ini_to_toml_translates_substitutions() {
+    let ini = indoc! {r"
+        [base]
+        deps = pytest
+
+        [testenv]
+        deps = {[base]deps}
+        commands =
+            - python -m pip list
+            pytest {posargs} --junitxml {toxworkdir}/junit.xml
+            {[testenv:lint]commands}
+        set_env = HOME = {env:HOME:/tmp}
+    "};
+    assert_snapshot!(convert(ini), @r#"
+    [base]
+    deps = ["pytest"]
+
+    [env_run_base]
+    deps = [{ replace = "ref", of = ["base", "deps"], extend = true }]
+    # TODO: tox.ini ignored the exit code of `python -m pip list`, set ignore_errors or handle it in the command
+    commands = [["python", "-m", "pip", "list"], ["pytest", { replace = "posargs", extend = true }, "--junitxml", "{work_dir}/junit.xml"], { replace = "ref", env = "lint", key = "commands", extend = true }]
+    set_env = { HOME = { replace = "env", name = "HOME", default = "/tmp" } }
+    "#);
+}
+
+#[test]
+fn test_ini_to_toml_splits_quoted_arguments() {
+    let ini = indoc! {r#"
+        [testenv]
+        commands =
+            python -c 'print("hi")' \
+                --flag
+    "#};
+    assert_snapshot!(convert(ini), @r#"
+    [env_run_base]
+    commands = [["python", "-c", "print(\"hi\")", "--flag"]]
+    "#);
+}
+
+#[test]
+fn test_convert_ini_formats_result() {
+    let ini = indoc! {r"
+        # project checks
+        [tox]
+        envlist = py312
+        minversion = 4.22
+
+        [testenv]
+        usedevelop = true
+        commands = pytest
+    "};
+    let got = convert_ini(ini, &Settings::default()).content;
+    assert_valid_toml(&got);
+    assert!(got.starts_with("# project checks\n"), "{got}");
+    assert!(got.contains("min_version = \"4.22\""), "{got}");
+    assert!(got.contains("package = \"editable\""), "{got}");
+    assert!(!got.contains("use_develop"), "{got}");
+}
@@ -5,17 +5,7 @@ use super::assert_valid_toml;
 use crate::{format_toml, Settings};
 
 fn default_settings() -> Settings {
-    Settings {
-        column_width: 80,
-        indent: 2,
-        table_format: String::from("short"),
-        sub_table_spacing: String::new(),
-        separate_root_table: String::from("\n"),
-        expand_tables: vec![],
-        collapse_tables: vec![],
-        skip_wrap_for_keys: vec![],
-        pin_envs: vec![],
-    }
+    Settings::builder().column_width(80).build()
 }
 
 fn format_toml_helper(start: &str, indent: usize) -> String {
@@ -23,9 +13,9 @@ fn format_toml_helper(start: &str, indent: usize) -> String {
         indent,
         ..default_settings()
     };
-    let got = format_toml(start, &settings);
+    let got = format_toml(start, &settings).content;
     assert_valid_toml(&got);
-    let second = format_toml(got.as_str(), &settings);
+    let second = format_toml(got.as_str(), &settings).content;
     assert_eq!(second, got, "formatting should be idempotent");
     got
 }
@@ -80,7 +70,7 @@ fn test_column_width() {
         indent: 4,
         ..default_settings()
     };
-    let got = format_toml(start, &settings);
+    let got = format_toml(start, &settings).content;
     assert_snapshot!(got, @r#"
     # comment
     requires = [ "tox>=4.22" ]
@@ -112,7 +102,7 @@ fn test_string_quote_normalization() {
         indent: 2,
         ..default_settings()
     };
-    let got = format_toml(start, &settings);
+    let got = format_toml(start, &settings).content;
     assert_snapshot!(got, @r#"
     requires = [ "tox>=4.22" ]
     env_list = [ "test" ]
@@ -133,7 +123,7 @@ fn test_string_with_double_quote_preserved() {
         indent: 2,
         ..default_settings()
     };
-    let got = format_toml(start, &settings);
+    let got = format_toml(start, &settings).content;
     assert_snapshot!(got, @r#"
     [env_run_base]
     description = 'run "tests"'
@@ -215,7 +205,7 @@ fn test_format_with_multiline_arrays() {
         indent: 2,
         ..default_settings()
     };
-    let got = format_toml(start, &settings);
+    let got = format_toml(start, &settings).content;
     assert_snapshot!(got, @r#"
     env_list = [
       "py311",
@@ -302,9 +292,9 @@ fn test_idempotent_formatting() {
         indent: 2,
         ..default_settings()
     };
-    let first = format_toml(start, &settings);
-    let second = format_toml(&first, &settings);
-    let third = format_toml(&second, &settings);
+    let first = format_toml(start, &settings).content;
+    let second = format_toml(&first, &settings).content;
+    let third = format_toml(&second, &settings).content;
     assert_eq!(second, first, "Second pass should match first");
     assert_eq!(third, second, "Third pass should match second");
 }
@@ -319,7 +309,7 @@ fn test_format_with_large_indent() {
         indent: 4,
         ..default_settings()
     };
-    let got = format_toml(start, &settings);
+    let got = format_toml(start, &settings).content;
     assert_snapshot!(got, @r#"env_list = [ "test" ]"#);
 }
 
@@ -333,7 +323,7 @@ fn test_format_with_narrow_column_width() {
         indent: 2,
         ..default_settings()
     };
-    let got = format_toml(start, &settings);
+    let got = format_toml(start, &settings).content;
     assert_snapshot!(got, @r#"
     description = """\
       A very long description \
@@ -343,6 +333,7 @@ fn test_format_with_narrow_column_width() {
     "#);
 }
 
+#[cfg(feature = "python")]
 #[test]
 fn test_settings_new() {
     let settings = Settings::new(
@@ -360,6 +351,7 @@ fn test_settings_new() {
     assert_eq!(settings.indent, 4);
 }
 
+#[cfg(feature = "python")]
 #[test]
 fn test_settings_default_values() {
     let settings = Settings::new(
@@ -379,17 +371,17 @@ fn test_settings_default_values() {
 
 #[test]
 fn test_settings_field_access() {
-    let settings = Settings {
-        column_width: 100,
-        indent: 3,
-        table_format: String::from("long"),
-        sub_table_spacing: String::from("\n"),
-        separate_root_table: String::from("\n\n"),
-        expand_tables: vec![String::from("env.test")],
-        collapse_tables: vec![String::from("env.lint")],
-        skip_wrap_for_keys: vec![String::from("*.commands")],
-        pin_envs: vec![String::from("fix")],
-    };
+    let settings = Settings::builder()
+        .column_width(100)
+        .indent(3)
+        .table_format("long")
+        .sub_table_spacing("\n")
+        .separate_root_table("\n\n")
+        .expand_tables(["env.test"])
+        .collapse_tables(["env.lint"])
+        .skip_wrap_for_keys(["*.commands"])
+        .pin_envs(["fix"])
+        .build();
     assert_eq!(settings.column_width, 100);
     assert_eq!(settings.indent, 3);
     assert_eq!(settings.table_format, "long");
@@ -401,6 +393,7 @@ fn test_settings_field_access() {
     assert_eq!(settings.pin_envs, vec!["fix"]);
 }
 
+#[cfg(feature = "python")]
 #[test]
 fn test_format_toml_with_direct_settings() {
     let content = "env_list = ['a', 'b']";
@@ -415,12 +408,449 @@ fn test_format_toml_with_direct_settings() {
         vec![],
         vec![],
     );
-    let result = format_toml(content, &settings);
+    let result = format_toml(content, &settings).content;
     assert!(result.contains("env_list"));
     assert!(result.contains("\"a\""));
     assert!(result.contains("\"b\""));
 }
 
+#[test]
+fn test_format_toml_reports_invalid_deps() {
+    let start = indoc! {r#"
+        [env.test]
+        deps = ["pytest>=8,,", "Coverage"]
+        "#};
+    let got = format_toml(start, &default_settings());
+    assert_snapshot!(got.content, @r#"
+    [env.test]
+    deps = [ "coverage", "pytest>=8,," ]
+    "#);
+    assert_eq!(got.diagnostics.len(), 1);
+    let diagnostic = &got.diagnostics[0];
+    assert_eq!(diagnostic.key_path, "env.test.deps");
+    assert_eq!((diagnostic.line, diagnostic.column), (2, 10));
+}
+
+#[test]
+fn test_format_toml_reports_env_not_produced_by_env_list() {
+    let start = indoc! {r#"
+        env_list = [{ product = [{ prefix = "py3", start = 12, stop = 13 }, ["django50", "django51"]], exclude = ["py312-django51"] }]
+
+        [env.dev]
+        description = "run by name"
+
+        [env.py312-django51]
+        description = "excluded"
+
+        [env.py313-django50]
+        description = "produced"
+        "#};
+    assert!(format_toml(start, &default_settings()).diagnostics.is_empty());
+    let settings = Settings {
+        validate: true,
+        ..default_settings()
+    };
+    let got = format_toml(start, &settings);
+    assert_eq!(got.diagnostics.len(), 1);
+    let diagnostic = &got.diagnostics[0];
+    assert_eq!(diagnostic.key_path, "env.py312-django51");
+    assert_eq!(diagnostic.message, "no env_list entry produces this environment");
+    assert!(
+        got.content.find("[env.py313-django50]") < got.content.find("[env.py312-django51]"),
+        "{}",
+        got.content
+    );
+}
+
+#[test]
+fn test_format_toml_reports_undefined_references() {
+    let start = indoc! {r#"
+        env_list = ["py312", "lint"]
+        labels = { test = ["py312", "lnt"] }
+
+        [env_run_base]
+        depends = ["lint"]
+
+        [env.lint]
+        deps = [{ replace = "ref", env = "tpye", key = "deps" }]
+        base = ["env_run_base", "shared"]
+
+        [env.type]
+        package_env = ".pkg"
+        depends = ["py313", "docs"]
+        "#};
+    let settings = Settings {
+        validate: true,
+        ..default_settings()
+    };
+    let got = format_toml(start, &settings);
+    let reported: Vec<(&str, &str, &str)> = got
+        .diagnostics
+        .iter()
+        .map(|diagnostic| {
+            (
+                diagnostic.key_path.as_str(),
+                diagnostic.value.as_str(),
+                diagnostic.message.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        reported,
+        [
+            ("labels.test", "lnt", "undefined environment lnt"),
+            ("env.lint.base", "shared", "undefined base shared"),
+            ("env.lint.deps", "tpye", "undefined environment tpye"),
+            ("env.type.depends", "docs", "undefined environment docs"),
+        ]
+    );
+}
+
+#[test]
+fn test_format_toml_checks_references_only_when_validating() {
+    let start = indoc! {r#"
+        env_list = ["lint"]
+        labels = { test = ["lnt"] }
+        "#};
+    assert!(format_toml(start, &default_settings()).diagnostics.is_empty());
+}
+
+#[test]
+fn test_format_toml_hoists_shared_settings() {
+    let start = indoc! {r#"
+        env_list = ["py312", "py313"]
+
+        [env.py312]
+        deps = ["pytest"]
+        pass_env = ["CI"]
+        commands = [["pytest"]]
+
+        [env.py313]
+        pass_env = ["CI"]
+        deps = ["pytest"]
+        commands = [["pytest", "-x"]]
+        "#};
+    let settings = Settings::builder().hoist_shared(true).build();
+    let got = format_toml(start, &settings);
+    assert_valid_toml(&got.content);
+    assert!(got.diagnostics.is_empty(), "{:?}", got.diagnostics);
+    assert!(
+        got.content
+            .contains("[env_run_base]\ndeps = [ \"pytest\" ]\npass_env = [ \"CI\" ]\n"),
+        "{}",
+        got.content
+    );
+    assert_eq!(got.content.matches("deps").count(), 1, "{}", got.content);
+    assert_eq!(got.content.matches("commands").count(), 2, "{}", got.content);
+    assert_eq!(format_toml(&got.content, &settings).content, got.content);
+}
+
+#[test]
+fn test_format_toml_hoist_shared_suggests_base_for_partly_shared_settings() {
+    let start = indoc! {r#"
+        env_list = ["py312-django", "py313-django", "lint"]
+
+        [env.py312-django]
+        deps = ["django"]
+
+        [env.py313-django]
+        deps = ["django"]
+
+        [env.lint]
+        deps = ["ruff"]
+        "#};
+    let settings = Settings::builder().hoist_shared(true).explain(true).build();
+    let got = format_toml(start, &settings);
+    assert!(!got.content.contains("[env_run_base]"), "{}", got.content);
+    assert!(got.diagnostics.is_empty(), "{:?}", got.diagnostics);
+    let suggested: Vec<(String, &str, &str)> = got
+        .explanations
+        .iter()
+        .filter(|explanation| explanation.rule == "tox/suggest-shared-base")
+        .map(|explanation| {
+            (
+                explanation.to_string(),
+                explanation.before.as_str(),
+                explanation.after.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        suggested,
+        [(
+            String::from("env.py312-django: tox/suggest-shared-base"),
+            "py312-django, py313-django share deps",
+            "[django] with base = [\"django\", \"env_run_base\"] in each"
+        )]
+    );
+}
+
+#[test]
+fn test_format_toml_hoist_shared_moves_comments_with_settings() {
+    let start = indoc! {r#"
+        env_list = ["py312", "py313"]
+
+        [env.py312]
+        # keep CI variables
+        pass_env = ["CI"]
+        commands = [["pytest"]]
+
+        [env.py313]
+        # keep CI variables
+        pass_env = ["CI"]
+        commands = [["pytest", "-x"]]
+        "#};
+    let settings = Settings::builder().hoist_shared(true).build();
+    let got = format_toml(start, &settings);
+    assert_valid_toml(&got.content);
+    assert!(
+        got.content
+            .contains("[env_run_base]\n# keep CI variables\npass_env = [ \"CI\" ]\n"),
+        "{}",
+        got.content
+    );
+    assert_eq!(got.content.matches("# keep CI variables").count(), 1, "{}", got.content);
+}
+
+#[test]
+fn test_format_toml_hoist_shared_keeps_settings_for_envs_without_table() {
+    let start = indoc! {r#"
+        env_list = ["py312", "py313", "lint"]
+
+        [env.py312]
+        deps = ["pytest"]
+
+        [env.py313]
+        deps = ["pytest"]
+        "#};
+    let settings = Settings::builder().hoist_shared(true).build();
+    let got = format_toml(start, &settings);
+    assert!(!got.content.contains("[env_run_base]"), "{}", got.content);
+    assert_eq!(got.content.matches("deps").count(), 2, "{}", got.content);
+}
+
+#[test]
+fn test_format_toml_hoist_shared_counts_envs_listing_env_run_base() {
+    let start = indoc! {r#"
+        env_list = ["py312", "py313", "cov"]
+
+        [env.py312]
+        deps = ["pytest"]
+
+        [env.py313]
+        deps = ["pytest"]
+
+        [env.cov]
+        base = ["env_run_base"]
+        deps = ["pytest"]
+        commands = [["coverage", "report"]]
+        "#};
+    let settings = Settings::builder().hoist_shared(true).build();
+    let got = format_toml(start, &settings);
+    assert_valid_toml(&got.content);
+    assert!(
+        got.content.contains("[env_run_base]\ndeps = [ \"pytest\" ]\n"),
+        "{}",
+        got.content
+    );
+    // cov inherits deps through its base once, so its own copy goes like the others
+    assert_eq!(got.content.matches("deps").count(), 1, "{}", got.content);
+    assert!(got.content.contains("base = [ \"env_run_base\" ]"), "{}", got.content);
+
+    let differs = start.replace("deps = [\"pytest\"]\ncommands", "deps = [\"coverage\"]\ncommands");
+    let got = format_toml(&differs, &settings);
+    assert!(!got.content.contains("[env_run_base]"), "{}", got.content);
+}
+
+#[test]
+fn test_format_toml_hoist_shared_keeps_settings_for_named_envs_without_table() {
+    let start = indoc! {r#"
+        env_list = ["py312", "py313"]
+        labels = { ci = ["py312", "py313", "fix"] }
+
+        [env.py312]
+        deps = ["pytest"]
+
+        [env.py313]
+        deps = ["pytest"]
+        "#};
+    let settings = Settings::builder().hoist_shared(true).build();
+    let got = format_toml(start, &settings);
+    assert!(!got.content.contains("[env_run_base]"), "{}", got.content);
+    assert_eq!(got.content.matches("deps").count(), 2, "{}", got.content);
+}
+
+#[test]
+fn test_format_toml_validate_reports_unknown_keys_and_types() {
+    let start = indoc! {r#"
+        min_version = "4.22"
+        env_lst = ["lint"]
+
+        [env_run_base]
+        dep = ["pytest"]
+        skip_install = "yes"
+        set_env = ["A=1"]
+        commands = [["pytest"], { replace = "ref", of = ["env", "lint", "commands"], extend = true }]
+
+        [env.lint]
+        description = "lint"
+        deps = "ruff"
+        frobnicate = true
+        "#};
+    let settings = Settings::builder().validate(true).build();
+    let got = format_toml(start, &settings);
+    let mut reported: Vec<(&str, &str)> = got
+        .diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.key_path.as_str(), diagnostic.message.as_str()))
+        .collect();
+    reported.sort_unstable();
+    assert_eq!(
+        reported,
+        [
+            ("env.lint.deps", "deps must be an array of strings"),
+            ("env.lint.frobnicate", "unknown key frobnicate"),
+            ("env_lst", "unknown key env_lst, did you mean env_list?"),
+            ("env_run_base.dep", "unknown key dep, did you mean deps?"),
+            ("env_run_base.set_env", "set_env must be a table"),
+            ("env_run_base.skip_install", "skip_install must be a boolean"),
+        ]
+    );
+    assert!(format_toml(start, &default_settings()).diagnostics.is_empty());
+}
+
+#[test]
+fn test_format_toml_reports_base_python_not_matching_factor() {
+    let start = indoc! {r#"
+        [env.py312-django]
+        base_python = "python3.11"
+
+        [env.py313]
+        base_python = ["3.13"]
+
+        [env."pypy3.10"]
+        base_python = "python3.10"
+
+        [env.lint]
+        base_python = "python3.12"
+        "#};
+    let settings = Settings {
+        validate: true,
+        ..default_settings()
+    };
+    let got = format_toml(start, &settings);
+    let mut reported: Vec<(&str, &str)> = got
+        .diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.key_path.as_str(), diagnostic.message.as_str()))
+        .collect();
+    reported.sort_unstable();
+    assert_eq!(
+        reported,
+        [
+            (
+                "env.py312-django.base_python",
+                "base_python python3.11 does not match the py312 factor of py312-django"
+            ),
+            (
+                "env.pypy3.10.base_python",
+                "base_python python3.10 does not match the pypy3.10 factor of pypy3.10"
+            ),
+        ]
+    );
+    let ignored = format!("ignore_base_python_conflict = true\n{start}");
+    assert!(format_toml(&ignored, &settings).diagnostics.is_empty());
+    assert!(format_toml(start, &default_settings()).diagnostics.is_empty());
+}
+
+fn in_order(content: &str, needles: &[&str]) -> bool {
+    let positions: Vec<Option<usize>> = needles.iter().map(|needle| content.find(needle)).collect();
+    positions.iter().all(Option::is_some) && positions.windows(2).all(|pair| pair[0] < pair[1])
+}
+
+#[test]
+fn test_format_toml_sorts_set_env_and_dedupes_lists() {
+    let start = indoc! {r#"
+        [env.test]
+        set_env = { b = "1", PATH = "x", file = ".env", A = { default = "d", name = "A", replace = "env" } }
+        allowlist_externals = ["make", "bash", "make"]
+        depends = ["lint", "type", "lint"]
+        labels = ["b", "a", "b"]
+        pass_env = ["HOME", "CI", "HOME"]
+
+        [env.lint.set_env]
+        z = "1"
+        Z = "2"
+        file = "lint.env"
+
+        [env.type]
+        set_env.b = "1"
+        set_env.A = "2"
+        "#};
+    let got = format_toml_helper(start, 2);
+    let set_env = [
+        "file = \".env\"",
+        "A = { replace = \"env\", name = \"A\", default = \"d\" }",
+        "PATH = \"x\"",
+        "b = \"1\"",
+    ];
+    assert!(in_order(&got, &set_env), "{got}");
+    assert!(got.contains(r#"allowlist_externals = [ "bash", "make" ]"#), "{got}");
+    assert!(got.contains(r#"depends = [ "lint", "type" ]"#), "{got}");
+    assert!(got.contains(r#"labels = [ "a", "b" ]"#), "{got}");
+    assert!(got.contains(r#"pass_env = [ "CI", "HOME" ]"#), "{got}");
+    assert!(
+        in_order(&got, &["file = \"lint.env\"", "Z = \"2\"", "z = \"1\""]),
+        "{got}"
+    );
+    assert!(in_order(&got, &["set_env.A", "set_env.b"]), "{got}");
+}
+
+fn commands_of(content: &str, env: &str, key: &str) -> toml::Value {
+    let config: toml::Table = content.parse().unwrap();
+    config["env"][env][key].clone()
+}
+
+#[test]
+fn test_format_toml_splits_string_commands() {
+    let start = indoc! {r#"
+        [env.test]
+        commands_pre = ["python -m pip list"]
+        commands = ["pytest -x {posargs:tests -v}", ["coverage report --fail-under 90"], ["echo", "{posargs}"]]
+        commands_post = [["python", "-c", "print('done')"]]
+        "#};
+    let got = format_toml_helper(start, 2);
+    let expected: toml::Table = indoc! {r#"
+        commands_pre = [["python", "-m", "pip", "list"]]
+        commands = [
+            ["pytest", "-x", { replace = "posargs", default = ["tests", "-v"], extend = true }],
+            ["coverage", "report", "--fail-under", "90"],
+            ["echo", { replace = "posargs", extend = true }],
+        ]
+        commands_post = [["python", "-c", "print('done')"]]
+        "#}
+    .parse()
+    .unwrap();
+    for key in ["commands_pre", "commands", "commands_post"] {
+        assert_eq!(commands_of(&got, "test", key), expected[key], "{got}");
+    }
+}
+
+#[test]
+fn test_format_toml_keeps_shell_commands() {
+    let start = indoc! {r#"
+        [env.test]
+        commands = ["git diff | cat", "python -c 'unclosed", "make && make install", ["echo $(pwd)"], 'pytest tests\unit']
+        commands_pre = [['C:\Program Files\python.exe'], ["/opt/my app/bin/python", "-V"]]
+        extra_setup_commands = ["python -m build > build.log", { replace = "ref", env = "lint", key = "commands" }]
+        "#};
+    let got = format_toml_helper(start, 2);
+    let expected: toml::Table = toml::from_str(start).unwrap();
+    for key in ["commands", "commands_pre", "extra_setup_commands"] {
+        assert_eq!(commands_of(&got, "test", key), expected["env"]["test"][key], "{got}");
+    }
+}
+
 #[cfg(feature = "extension-module")]
 #[test]
 fn test_lib_module_registration() {
@@ -433,6 +863,8 @@ fn test_lib_module_registration() {
 
         assert!(module.hasattr("format_toml").unwrap());
         assert!(module.hasattr("Settings").unwrap());
+        assert!(module.hasattr("Formatted").unwrap());
+        assert!(module.hasattr("Diagnostic").unwrap());
     });
 }
 
@@ -473,9 +905,9 @@ fn test_array_multiline_expansion() {
         indent: 2,
         ..default_settings()
     };
-    let got = format_toml(start, &settings);
+    let got = format_toml(start, &settings).content;
     assert_valid_toml(&got);
-    let second = format_toml(got.as_str(), &settings);
+    let second = format_toml(got.as_str(), &settings).content;
     assert_eq!(second, got, "formatting should be idempotent");
     assert_snapshot!(got, @r#"
     [env_run_base]
@@ -500,9 +932,9 @@ fn test_long_string_wrapping() {
         indent: 2,
         ..default_settings()
     };
-    let got = format_toml(start, &settings);
+    let got = format_toml(start, &settings).content;
     assert_valid_toml(&got);
-    let second = format_toml(got.as_str(), &settings);
+    let second = format_toml(got.as_str(), &settings).content;
     assert_eq!(second, got, "formatting should be idempotent");
     assert_snapshot!(got, @r#"
     [env_run_base]
@@ -528,9 +960,9 @@ fn test_table_collapse_short_format() {
         indent: 2,
         ..default_settings()
     };
-    let got = format_toml(start, &settings);
+    let got = format_toml(start, &settings).content;
     assert_valid_toml(&got);
-    let second = format_toml(got.as_str(), &settings);
+    let second = format_toml(got.as_str(), &settings).content;
     assert_eq!(second, got, "formatting should be idempotent");
     assert_snapshot!(got, @r#"
     [env.test]
@@ -552,9 +984,9 @@ fn test_table_expand_long_format() {
         table_format: String::from("long"),
         ..default_settings()
     };
-    let got = format_toml(start, &settings);
+    let got = format_toml(start, &settings).content;
     assert_valid_toml(&got);
-    let second = format_toml(got.as_str(), &settings);
+    let second = format_toml(got.as_str(), &settings).content;
     assert_eq!(second, got, "formatting should be idempotent");
     assert_snapshot!(got, @r#"
     [env.test]
@@ -578,9 +1010,9 @@ fn test_expanded_sub_tables_follow_env_key_order() {
         table_format: String::from("long"),
         ..default_settings()
     };
-    let got = format_toml(start, &settings);
+    let got = format_toml(start, &settings).content;
     assert_valid_toml(&got);
-    let second = format_toml(got.as_str(), &settings);
+    let second = format_toml(got.as_str(), &settings).content;
     assert_eq!(second, got, "formatting should be idempotent");
     assert_snapshot!(got, @r#"
     [env.py313]
@@ -607,9 +1039,9 @@ fn test_skip_wrap_for_keys() {
         skip_wrap_for_keys: vec![String::from("*.skip_me")],
         ..default_settings()
     };
-    let got = format_toml(start, &settings);
+    let got = format_toml(start, &settings).content;
     assert_valid_toml(&got);
-    let second = format_toml(got.as_str(), &settings);
+    let second = format_toml(got.as_str(), &settings).content;
     assert_eq!(second, got, "formatting should be idempotent");
     assert_snapshot!(got, @r#"
     [env_run_base]
@@ -829,9 +1261,9 @@ fn test_sort_env_list_with_pin() {
         pin_envs: vec![String::from("fix"), String::from("type")],
         ..default_settings()
     };
-    let got = format_toml(start, &settings);
+    let got = format_toml(start, &settings).content;
     assert_valid_toml(&got);
-    let second = format_toml(got.as_str(), &settings);
+    let second = format_toml(got.as_str(), &settings).content;
     assert_eq!(second, got, "formatting should be idempotent");
     assert_snapshot!(got, @r#"env_list = [ "fix", "type", "3.13", "3.12", "lint" ]"#);
 }
@@ -945,9 +1377,9 @@ fn test_sort_env_list_compound_pin() {
         pin_envs: vec![String::from("fix")],
         ..default_settings()
     };
-    let got = format_toml(start, &settings);
+    let got = format_toml(start, &settings).content;
     assert_valid_toml(&got);
-    let second = format_toml(got.as_str(), &settings);
+    let second = format_toml(got.as_str(), &settings).content;
     assert_eq!(second, got, "formatting should be idempotent");
     assert_snapshot!(got, @r#"env_list = [ "fix", "py312-django", "py311-django", "lint" ]"#);
 }
@@ -1062,6 +1494,25 @@ fn test_sort_labels() {
     "#);
 }
 
+#[test]
+fn test_sort_root_labels() {
+    let start = indoc! {r#"
+        labels = { test = ["py313", "py312", "py313"], check = ["type", "lint"] }
+        "#};
+    let got = format_toml_helper(start, 2);
+    assert_snapshot!(got, @r#"labels = { test = [ "py312", "py313" ], check = [ "lint", "type" ] }"#);
+
+    let start = indoc! {r#"
+        [labels]
+        test = ["py313", "py312", "py313"]
+        "#};
+    let got = format_toml_helper(start, 2);
+    assert_snapshot!(got, @r#"
+    [labels]
+    test = [ "py312", "py313" ]
+    "#);
+}
+
 #[test]
 fn test_env_dotted_keys_expand_to_tables() {
     let start = indoc! {r#"
@@ -1617,7 +2068,7 @@ fn test_sub_table_spacing_blank_line() {
         sub_table_spacing: String::from("\n"),
         ..default_settings()
     };
-    let got = format_toml(start, &settings);
+    let got = format_toml(start, &settings).content;
     assert_valid_toml(&got);
     assert_snapshot!(got, @r#"
     env_list = [ "lint", "test" ]
@@ -1632,3 +2083,75 @@ fn test_sub_table_spacing_blank_line() {
     description = "test"
     "#);
 }
+
+#[test]
+fn test_settings_default_matches_cli() {
+    let settings = Settings::default();
+    assert_eq!((settings.column_width, settings.indent), (120, 2));
+    assert_eq!(settings.table_format, "short");
+    assert_eq!(settings.separate_root_table, "\n");
+    assert!(settings.pin_envs.is_empty());
+}
+
+#[test]
+fn test_settings_builder_overrides_defaults() {
+    let settings = Settings::builder().indent(4).pin_envs(["lint"]).build();
+    let got = format_toml("env_list = [\"test\", \"lint\"]\n", &settings);
+    assert_snapshot!(got.content, @r#"env_list = [ "lint", "test" ]"#);
+    assert_eq!(settings.column_width, 120);
+}
+
+#[test]
+fn test_format_toml_explains_legacy_rename() {
+    let settings = Settings::builder().explain(true).build();
+    let got = format_toml("envlist = [\"py\"]\n", &settings);
+    let rules: Vec<String> = got.explanations.iter().map(ToString::to_string).collect();
+    assert!(rules.contains(&String::from("env_list: table/rename-key")), "{rules:?}");
+}
+
+#[test]
+fn test_format_toml_ignore_lowercase_name_keeps_case() {
+    let settings = Settings::builder().ignore(["pep508.lowercase-name"]).build();
+    let got = format_toml("[env.a]\ndeps = [\"Django_Stubs>=1\"]\n", &settings);
+    assert_snapshot!(got.content, @r#"
+    [env.a]
+    deps = [ "Django-Stubs>=1" ]
+    "#);
+}
+
+#[test]
+fn test_format_toml_ignore_inline_tables_keeps_key_order() {
+    let start = "[env.a]\ncommands = [[\"a\", { default = [\"x\"], replace = \"posargs\" }]]\n";
+    let reordered = format_toml(start, &Settings::default());
+    assert!(
+        reordered
+            .content
+            .contains(r#"{ replace = "posargs", default = [ "x" ] }"#),
+        "{}",
+        reordered.content
+    );
+
+    let settings = Settings::builder().ignore(["tox.inline-tables"]).build();
+    let got = format_toml(start, &settings);
+    assert!(
+        got.content.contains(r#"{ default = [ "x" ], replace = "posargs" }"#),
+        "{}",
+        got.content
+    );
+}
+
+#[test]
+fn test_format_toml_reports_unknown_rules() {
+    let settings = Settings::builder().ignore(["tox.env-list", "tox.envs"]).build();
+    let got = format_toml("env_list = [\"py\"]\n", &settings);
+    assert_eq!(got.diagnostics.len(), 1);
+    assert_eq!(got.diagnostics[0].key_path, "ignore");
+    assert_eq!(got.diagnostics[0].message, "unknown rule tox.env-list");
+}
+
+#[test]
+fn test_format_toml_ignore_keeps_legacy_names() {
+    let settings = Settings::builder().ignore(["tox.alias-normalization"]).build();
+    let got = format_toml("envlist = [\"py\"]\n", &settings);
+    assert_snapshot!(got.content, @r#"envlist = [ "py" ]"#);
+}
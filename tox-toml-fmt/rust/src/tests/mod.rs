@@ -1,6 +1,9 @@
 pub use common::test_util::{assert_valid_toml, format_syntax, parse};
 
+mod cli_tests;
+mod command_tests;
 mod disabled_tests;
 mod doc_examples_tests;
 mod global_tests;
+mod ini_tests;
 mod main_tests;
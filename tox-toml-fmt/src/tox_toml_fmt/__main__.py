@@ -2,29 +2,41 @@
 
 from __future__ import annotations
 
+import sys
+from argparse import ArgumentParser
+from pathlib import Path
 from typing import TYPE_CHECKING
 
 from toml_fmt_common import ArgumentGroup, FmtNamespace, TOMLFormatter, build_cli, list_argument, run
 
-from ._lib import Settings, format_toml
+from ._lib import Settings, convert_ini, format_toml
 
 if TYPE_CHECKING:
-    from argparse import ArgumentParser
     from collections.abc import Sequence
 
+    from ._lib import Formatted
+
 
 class PyProjectFmtNamespace(FmtNamespace):
     """Formatting arguments."""
 
     pin_envs: list[str]
+    hoist_shared: bool
+    validate: bool
+    from_ini: Path | None
 
 
 class ToxTOMLFormatter(TOMLFormatter[PyProjectFmtNamespace]):
     """Format pyproject.toml."""
 
-    def __init__(self) -> None:
-        """Create a formatter."""
+    def __init__(self, ini: str | None = None) -> None:
+        """
+        Create a formatter.
+
+        :param ini: a ``tox.ini`` to convert, which then replaces what the formatted ``tox.toml`` holds
+        """
         super().__init__(PyProjectFmtNamespace())
+        self._ini = ini
 
     @property
     def prog(self) -> str:
@@ -49,19 +61,36 @@ class ToxTOMLFormatter(TOMLFormatter[PyProjectFmtNamespace]):
             dest="pin_envs",
             help="environments pinned to the start of env_list (comma separated)",
         )
+        parser.add_argument(
+            "--hoist-shared",
+            action="store_true",
+            help="move settings every environment repeats into env_run_base",
+        )
+        parser.add_argument(
+            "--validate",
+            action="store_true",
+            help="report undefined environments, mismatched base_python, unknown keys and values of the wrong type",
+        )
+        parser.add_argument(
+            "--from-ini",
+            type=Path,
+            default=None,
+            metavar="path",
+            help="convert this tox.ini into a tox.toml next to it, then format that",
+        )
 
     @property
     def override_cli_from_section(self) -> tuple[str, ...]:
         """Path where config overrides live."""
         return ("tox-toml-fmt",)
 
-    def format(self, text: str, opt: PyProjectFmtNamespace) -> str:  # ruff: ignore[no-self-use]
+    def format(self, text: str, opt: PyProjectFmtNamespace) -> Formatted:
         """
         Perform the formatting.
 
         :param text: content to operate on
         :param opt: formatter config
-        :return: formatted text
+        :return: formatted text with the diagnostics for entries left as written
         """
         settings = Settings(
             column_width=opt.column_width,
@@ -73,7 +102,14 @@ class ToxTOMLFormatter(TOMLFormatter[PyProjectFmtNamespace]):
             collapse_tables=opt.collapse_tables,
             skip_wrap_for_keys=opt.skip_wrap_for_keys,
             pin_envs=opt.pin_envs,
+            select=opt.select,
+            ignore=opt.ignore,
+            explain=opt.explain,
+            hoist_shared=opt.hoist_shared,
+            validate=opt.validate,
         )
+        if self._ini is not None:  # text is the empty tox.toml the conversion goes to
+            return convert_ini(self._ini, settings)
         return format_toml(text, settings)
 
 
@@ -84,7 +120,35 @@ def runner(args: Sequence[str] | None = None) -> int:
     :param args: CLI arguments
     :return: exit code
     """
-    return run(ToxTOMLFormatter(), args)
+    args = sys.argv[1:] if args is None else args
+    parser = ArgumentParser(prog="tox-toml-fmt", add_help=False)
+    parser.add_argument("--from-ini", type=Path, default=None)
+    known, _ = parser.parse_known_args(args)
+    if known.from_ini is None:
+        return run(ToxTOMLFormatter(), args)
+    return _from_ini(known.from_ini, args)
+
+
+def _from_ini(ini: Path, args: Sequence[str]) -> int:
+    """Convert a tox.ini into a tox.toml next to it, formatted with the options any input there would get."""
+    parser = build_cli(ToxTOMLFormatter())[0]
+    if len(parser.parse_args([*args, str(ini)]).inputs) > 1:  # the tox.ini stands in for the tox.toml to create
+        parser.error("argument --from-ini: converts one tox.ini, pass no other inputs")
+    target = ini.with_name("tox.toml")
+    if target.exists():
+        print(f"{target}: already exists, not overwriting it", file=sys.stderr)  # ruff: ignore[print]
+        return 1
+    try:
+        text = ini.read_text(encoding="utf-8")
+    except OSError as exc:
+        print(f"{ini}: {exc}", file=sys.stderr)  # ruff: ignore[print]
+        return 1
+    target.touch()
+    try:
+        return run(ToxTOMLFormatter(text), [*args, str(target)])
+    finally:
+        if not target.stat().st_size:  # --check and --stdout leave it unwritten
+            target.unlink()
 
 
 def _build_our_cli() -> ArgumentParser:
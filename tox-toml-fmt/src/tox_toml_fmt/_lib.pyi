@@ -13,6 +13,11 @@ class Settings:
         collapse_tables: Sequence[str],
         skip_wrap_for_keys: Sequence[str],
         pin_envs: Sequence[str],
+        select: Sequence[str] = (),
+        ignore: Sequence[str] = (),
+        explain: bool = False,
+        hoist_shared: bool = False,
+        validate: bool = False,
     ) -> None: ...
     @property
     def column_width(self) -> int: ...
@@ -32,5 +37,48 @@ class Settings:
     def skip_wrap_for_keys(self) -> Sequence[str]: ...
     @property
     def pin_envs(self) -> Sequence[str]: ...
+    @property
+    def select(self) -> Sequence[str]: ...
+    @property
+    def ignore(self) -> Sequence[str]: ...
+    @property
+    def explain(self) -> bool: ...
+    @property
+    def hoist_shared(self) -> bool: ...
+    @property
+    def validate(self) -> bool: ...
+
+class Diagnostic:
+    @property
+    def offset(self) -> int: ...
+    @property
+    def line(self) -> int: ...
+    @property
+    def column(self) -> int: ...
+    @property
+    def key_path(self) -> str: ...
+    @property
+    def value(self) -> str: ...
+    @property
+    def message(self) -> str: ...
+
+class Explanation:
+    @property
+    def rule(self) -> str: ...
+    @property
+    def key_path(self) -> str: ...
+    @property
+    def before(self) -> str: ...
+    @property
+    def after(self) -> str: ...
+
+class Formatted:
+    @property
+    def content(self) -> str: ...
+    @property
+    def diagnostics(self) -> list[Diagnostic]: ...
+    @property
+    def explanations(self) -> list[Explanation]: ...
 
-def format_toml(content: str, settings: Settings) -> str: ...
+def format_toml(content: str, settings: Settings) -> Formatted: ...
+def convert_ini(content: str, settings: Settings) -> Formatted: ...
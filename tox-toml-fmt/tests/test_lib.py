@@ -53,7 +53,7 @@ def test_format_toml(start: str, expected: str) -> None:
         skip_wrap_for_keys=[],
         pin_envs=[],
     )
-    res = format_toml(dedent(start), settings)
+    res = format_toml(dedent(start), settings).content
     assert res == dedent(expected)
 
 
@@ -78,6 +78,6 @@ def test_sub_table_spacing_separates_sub_tables() -> None:
         skip_wrap_for_keys=[],
         pin_envs=[],
     )
-    res = format_toml(start, settings)
+    res = format_toml(start, settings).content
     assert "\n\n[env_run_base]" in res
     assert "\n\n[env.test]" in res
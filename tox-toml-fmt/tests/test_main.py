@@ -176,3 +176,102 @@ def test_tox_toml_config(tmp_path: Path, capsys: pytest.CaptureFixture[str]) ->
     out, err = capsys.readouterr()
     assert out
     assert not err
+
+
+def test_ignore_config(tmp_path: Path) -> None:
+    txt = """\
+env_list = ["lint", "fix"]
+
+[tox-toml-fmt]
+ignore = ["tox.env-list-sort"]
+"""
+    filename = tmp_path / "tox.toml"
+    filename.write_text(txt)
+    run([str(filename)])
+    assert filename.read_text().startswith('env_list = [ "lint", "fix" ]\n')
+
+
+def test_hoist_shared_cli(tmp_path: Path) -> None:
+    txt = """\
+env_list = ["a", "b"]
+
+[env.a]
+skip_install = true
+
+[env.b]
+skip_install = true
+"""
+    filename = tmp_path / "tox.toml"
+    filename.write_text(txt)
+    run([str(filename), "--hoist-shared"])
+    got = filename.read_text()
+    assert "[env_run_base]\nskip_install = true\n" in got
+    assert got.count("skip_install") == 1
+
+
+def test_validate_config(tmp_path: Path, capsys: pytest.CaptureFixture[str]) -> None:
+    txt = """\
+[env.a]
+dep = ["pytest"]
+
+[tox-toml-fmt]
+validate = true
+"""
+    filename = tmp_path / "tox.toml"
+    filename.write_text(txt)
+    assert run([str(filename)]) == 1
+    assert "did you mean deps?" in capsys.readouterr().err
+
+
+def test_from_ini(tmp_path: Path) -> None:
+    ini = tmp_path / "tox.ini"
+    ini.write_text("[tox]\nenvlist = py312, lint\n\n[testenv]\ndeps = pytest\ncommands = pytest\n")
+    assert run(["--from-ini", str(ini), "--no-print-diff"]) == 1
+    got = (tmp_path / "tox.toml").read_text()
+    assert "envlist" not in got
+    assert "env_list = [" in got
+    assert "[env_run_base]\n" in got
+    assert 'deps = [ "pytest" ]\n' in got
+    assert ini.read_text().startswith("[tox]\n")
+
+
+def test_from_ini_keeps_existing_toml(tmp_path: Path, capsys: pytest.CaptureFixture[str]) -> None:
+    ini = tmp_path / "tox.ini"
+    ini.write_text("[tox]\nenvlist = py312\n")
+    (tmp_path / "tox.toml").write_text("requires = []\n")
+    assert run(["--from-ini", str(ini)]) == 1
+    assert (tmp_path / "tox.toml").read_text() == "requires = []\n"
+    assert "already exists" in capsys.readouterr().err
+
+
+def test_from_ini_uses_shared_config(tmp_path: Path) -> None:
+    ini = tmp_path / "tox.ini"
+    ini.write_text("[tox]\nenvlist = py312, lint\n")
+    (tmp_path / "tox-toml-fmt.toml").write_text("column_width = 20\n")
+    assert run(["--from-ini", str(ini), "--no-print-diff"]) == 1
+    assert (tmp_path / "tox.toml").read_text().startswith("env_list = [\n")
+
+
+def test_from_ini_check_creates_nothing(tmp_path: Path, capsys: pytest.CaptureFixture[str]) -> None:
+    ini = tmp_path / "tox.ini"
+    ini.write_text("[tox]\nenvlist = py312\n")
+    assert run(["--check", "--from-ini", str(ini)]) == 1
+    assert not (tmp_path / "tox.toml").exists()
+    assert "+env_list" in capsys.readouterr().out
+
+
+def test_from_ini_rejects_other_inputs(tmp_path: Path, capsys: pytest.CaptureFixture[str]) -> None:
+    ini = tmp_path / "tox.ini"
+    ini.write_text("[tox]\nenvlist = py312\n")
+    other = tmp_path / "other.toml"
+    other.write_text("requires = []\n")
+    with pytest.raises(SystemExit):
+        run(["--from-ini", str(ini), str(other)])
+    assert not (tmp_path / "tox.toml").exists()
+    assert "pass no other inputs" in capsys.readouterr().err
+
+
+def test_from_ini_in_help(capsys: pytest.CaptureFixture[str]) -> None:
+    with pytest.raises(SystemExit):
+        run(["--help"])
+    assert "--from-ini" in capsys.readouterr().out